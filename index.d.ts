@@ -13,11 +13,42 @@ export declare class AnyListClient {
   /** Create a new list */
   createList(name: string): Promise<List>;
   /** Get a specific list by ID */
-  getListById(listId: string): Promise<List>;
+  getListById(
+    listId: string,
+    options?: GetListOptions | undefined | null,
+  ): Promise<List>;
+  /**
+   * Get a page of a list's items, in the order `getListById()` returns
+   * them, so a large list doesn't have to cross into JavaScript at once
+   * Each page reads the list afresh, so items added or removed between
+   * pages can shift the ones after them.
+   */
+  getListItems(
+    listId: string,
+    options?: ListItemsOptions | undefined | null,
+  ): Promise<ListItemsPage>;
   /** Get a list by name */
   getListByName(name: string): Promise<List>;
-  /** Rename a list */
-  renameList(listId: string, newName: string): Promise<void>;
+  /**
+   * Find the list with this name, or create it if there isn't one
+   * This isn't atomic: calls on one client never create the list twice, but
+   * calls from different clients can each create it. When several lists have
+   * the name, the one with the lowest ID is returned, and a list this call
+   * created is deleted if it isn't that one, but a call that finishes before
+   * another client creates its copy can still leave a duplicate behind
+   */
+  findOrCreateList(name: string): Promise<List>;
+  /**
+   * Rename a list, along with its linked favourites list if that still has
+   * the list's old name
+   */
+  renameList(listId: string, newName: string): Promise<OperationReceipt>;
+  /**
+   * Get a single item from a list by ID
+   * AnyList has no endpoint for one item, so this reads the list the way
+   * `getListById` does, including changes that haven't been sent yet
+   */
+  getItemById(listId: string, itemId: string): Promise<ListItem>;
   /** Add an item to a list */
   addItem(listId: string, name: string): Promise<ListItem>;
   /** Add an item with details to a list */
@@ -28,12 +59,36 @@ export declare class AnyListClient {
     note?: string | undefined | null,
     category?: string | undefined | null,
   ): Promise<ListItem>;
+  /**
+   * Add an item unless an item with the same name is already on the list
+   * A matching unchecked item is returned as-is; a matching checked item is unchecked
+   */
+  addItemIfNotExists(
+    listId: string,
+    name: string,
+    details?: string | undefined | null,
+  ): Promise<ListItem>;
+  /** Change only the supplied fields of an item */
+  patchItem(
+    listId: string,
+    itemId: string,
+    patch: ItemPatch,
+  ): Promise<ListItem>;
+  /**
+   * Update the item with a matching name, or create it if the list has none
+   * The matched item is unchecked unless the patch says otherwise
+   */
+  upsertItem(
+    listId: string,
+    name: string,
+    patch: ItemPatch,
+  ): Promise<UpsertItemResult>;
   /** Delete an item from a list */
-  deleteItem(listId: string, itemId: string): Promise<void>;
+  deleteItem(listId: string, itemId: string): Promise<OperationReceipt>;
   /** Cross off (check) an item */
-  crossOffItem(listId: string, itemId: string): Promise<void>;
+  crossOffItem(listId: string, itemId: string): Promise<OperationReceipt>;
   /** Uncheck an item */
-  uncheckItem(listId: string, itemId: string): Promise<void>;
+  uncheckItem(listId: string, itemId: string): Promise<OperationReceipt>;
   /** Update an existing item */
   updateItem(
     listId: string,
@@ -42,70 +97,482 @@ export declare class AnyListClient {
     quantity?: string | undefined | null,
     note?: string | undefined | null,
     category?: string | undefined | null,
-  ): Promise<void>;
+  ): Promise<OperationReceipt>;
   /** Delete multiple items at once */
-  bulkDeleteItems(listId: string, itemIds: Array<string>): Promise<void>;
+  bulkDeleteItems(
+    listId: string,
+    itemIds: Array<string>,
+  ): Promise<OperationReceipt>;
+  /** Cross off (check) multiple items at once */
+  bulkCrossOffItems(
+    listId: string,
+    itemIds: Array<string>,
+  ): Promise<OperationReceipt>;
+  /** Uncheck multiple items at once */
+  bulkUncheckItems(
+    listId: string,
+    itemIds: Array<string>,
+  ): Promise<OperationReceipt>;
+  /**
+   * Set the category of many items in a single request, returning the
+   * updated items
+   * Nothing is changed if any item isn't on the list
+   */
+  bulkCategorizeItems(
+    listId: string,
+    changes: Array<ItemCategoryChange>,
+  ): Promise<Array<ListItem>>;
   /** Delete all crossed off (checked) items from a list */
-  deleteAllCrossedOffItems(listId: string): Promise<void>;
-  /** Get all recipes */
-  getRecipes(): Promise<Array<Recipe>>;
+  deleteAllCrossedOffItems(listId: string): Promise<OperationReceipt>;
+  /**
+   * Start a batch of item changes that are sent together, so they all
+   * succeed or all fail
+   * Changes in a batch skip category rules, the offline queue, and
+   * optimistic updates.
+   */
+  batch(): Batch;
+  /**
+   * Get items deleted from a list through this client, most recent first
+   * AnyList has no server-side trash, so only deletions made by this client are remembered
+   */
+  getDeletedItems(listId: string): Array<ListItem>;
+  /** Re-add a deleted item to its list, returning the restored item (which has a new ID) */
+  restoreItem(listId: string, itemId: string): Promise<ListItem>;
+  /** Get all recipes, or only those matching a filter */
+  getRecipes(filter?: RecipeFilter | undefined | null): Promise<Array<Recipe>>;
   /** Get a specific recipe by ID */
   getRecipeById(recipeId: string): Promise<Recipe>;
+  /**
+   * Get several recipes by ID in one request, in the order given
+   * IDs that don't match a recipe are skipped
+   */
+  getRecipesByIds(recipeIds: Array<string>): Promise<Array<Recipe>>;
   /** Get a recipe by name */
   getRecipeByName(name: string): Promise<Recipe>;
+  /**
+   * Search recipes for every word of a query (case-insensitive)
+   * Recipes whose name matches are listed first
+   */
+  searchRecipes(
+    query: string,
+    options?: SearchRecipesOptions | undefined | null,
+  ): Promise<Array<Recipe>>;
+  /**
+   * Find recipes with an ingredient matching a name, ignoring case, plurals,
+   * and small typos; the closest matches are listed first
+   */
+  findRecipesUsingIngredient(ingredient: string): Promise<Array<Recipe>>;
+  /** Get recipes newest first, by when they were added or last changed */
+  getRecentRecipes(
+    options?: RecentRecipesOptions | undefined | null,
+  ): Promise<Array<Recipe>>;
   /** Create a new recipe with full metadata support */
   createRecipe(options: CreateRecipeOptions): Promise<Recipe>;
-  /** Add recipe ingredients to a list with optional scale factor */
+  /** Get a recipe with its ingredients and servings scaled, without changing anything */
+  scaleRecipe(recipeId: string, factor: number): Promise<Recipe>;
+  /**
+   * Add recipe ingredients to a list with optional scale factor
+   * Ingredients linked to another recipe (see `Ingredient.recipeId`) and
+   * counted in batches are replaced by that recipe's ingredients
+   */
   addRecipeToList(
     recipeId: string,
     listId: string,
     scaleFactor?: number | undefined | null,
-  ): Promise<void>;
+  ): Promise<OperationReceipt>;
+  /**
+   * Add several recipes' ingredients to a list in one request, expanding
+   * linked recipes like `addRecipeToList`
+   */
+  addRecipesToList(
+    listId: string,
+    recipes: Array<RecipeSelection>,
+  ): Promise<OperationReceipt>;
   /**
    * Update an existing recipe
    * Note: The recipe name cannot be changed (use the existing name in options)
    */
   updateRecipe(recipeId: string, options: CreateRecipeOptions): Promise<Recipe>;
+  /**
+   * Change only the given fields of a recipe, keeping everything else as
+   * AnyList currently has it
+   */
+  patchRecipe(recipeId: string, patch: RecipePatch): Promise<Recipe>;
   /** Delete a recipe */
-  deleteRecipe(recipeId: string): Promise<void>;
+  deleteRecipe(recipeId: string): Promise<OperationReceipt>;
+  /**
+   * Get earlier versions of a recipe, most recent first
+   * AnyList keeps no history, so only changes made through this client are remembered
+   */
+  getRecipeHistory(recipeId: string): Array<RecipeVersion>;
+  /** Restore an earlier version of a recipe, recreating it if it was deleted */
+  revertRecipe(recipeId: string, versionId: string): Promise<Recipe>;
   /** Delete a list */
-  deleteList(listId: string): Promise<void>;
+  deleteList(listId: string): Promise<OperationReceipt>;
   /**
    * Upload a photo for use with recipes
    * Returns the photo ID which can be used with createRecipe
    */
   uploadPhoto(data: Buffer, filename: string): Promise<string>;
+  /** Set a recipe's rating (1-5, or 0 to clear it) without changing anything else */
+  rateRecipe(recipeId: string, rating: number): Promise<Recipe>;
+  /**
+   * Record that a recipe was cooked on a date (YYYY-MM-DD, default today)
+   * by adding it to the meal plan
+   */
+  markRecipeCooked(
+    recipeId: string,
+    date?: Date | number | string | undefined | null,
+  ): Promise<MealPlanEvent>;
+  /**
+   * Copy a recipe, including its ingredients, steps, and photo
+   * The copy is named "<name> (copy)" unless a new name is given
+   */
+  duplicateRecipe(
+    recipeId: string,
+    newName?: string | undefined | null,
+  ): Promise<Recipe>;
+  /**
+   * Create a recipe that is just photos, e.g. of the pages of a cookbook
+   * recipe, in order
+   */
+  createPhotoRecipe(
+    name: string,
+    photos: Array<Buffer>,
+    sourceName?: string | undefined | null,
+  ): Promise<Recipe>;
+  /** Download all of a recipe's photos, in order */
+  getRecipePhotos(recipeId: string): Promise<Array<RecipePhoto>>;
+  /** Remove a recipe's photo */
+  clearRecipePhoto(recipeId: string): Promise<Recipe>;
+  /**
+   * Upload a new photo and make it the recipe's photo in one step
+   * The previous photo is detached from the recipe but not deleted, as
+   * AnyList has no endpoint for deleting photos
+   */
+  replaceRecipePhoto(
+    recipeId: string,
+    data: Buffer,
+    filename: string,
+  ): Promise<Recipe>;
+  /** Download a recipe photo by its photo ID */
+  downloadRecipePhoto(photoId: string): Promise<RecipePhoto>;
+  /**
+   * Create a recipe from a web page's schema.org (JSON-LD or microdata) markup
+   * The page's main image is uploaded as the recipe photo when available
+   */
+  importRecipeFromUrl(url: string): Promise<Recipe>;
+  /** Create a recipe from an already-extracted schema.org JSON-LD Recipe object */
+  importRecipeFromSchemaOrg(json: any): Promise<Recipe>;
+  /**
+   * Create recipes (with their photos) from a Paprika `.paprikarecipes`
+   * export, reporting which were created and which failed
+   * A recipe that fails doesn't stop the others being imported
+   */
+  importRecipesFromPaprika(data: Buffer): Promise<RecipeImportResult>;
+  /**
+   * Create recipes from a Mealie JSON export, reporting which were created
+   * and which failed
+   * Tags and categories are added as recipe collections, creating any that don't exist
+   * A recipe that fails doesn't stop the others being imported
+   */
+  importRecipesFromMealie(json: any): Promise<RecipeImportResult>;
+  /** Export a recipe as Markdown or JSON */
+  exportRecipe(recipeId: string, format: ExportFormat): Promise<string>;
+  /** Render a recipe as a standalone, printable HTML document with its photo inlined */
+  renderRecipeHtml(
+    recipeId: string,
+    options?: RenderRecipeHtmlOptions | undefined | null,
+  ): Promise<string>;
+  /**
+   * Back up every recipe as a single JSON document
+   * Photos are embedded as base64 when `includePhotos` is set; a photo that
+   * can't be downloaded is left out, with the error in its recipe's
+   * `photoError`
+   */
+  exportAllRecipes(
+    options?: ExportAllRecipesOptions | undefined | null,
+  ): Promise<string>;
+  /**
+   * Back up every list and recipe as a single JSON snapshot: the same
+   * document as `exportAllRecipes()`, with the lists added
+   */
+  exportSnapshot(
+    options?: ExportAllRecipesOptions | undefined | null,
+  ): Promise<string>;
+  /**
+   * Recreate the lists and recipes in a snapshot or recipe backup,
+   * reporting which were created and which skipped, with their old and
+   * new IDs
+   * Lists and recipes are matched by name, and items by name within their
+   * list. `merge` (the default) keeps the ones that already exist and adds
+   * the rest; `replace` empties matching lists before adding the
+   * snapshot's items and replaces matching recipes, deleting each only
+   * once its replacement has been created.
+   * Lists and recipes that aren't in the snapshot are left alone.
+   */
+  restoreFromSnapshot(
+    snapshot: string,
+    options?: RestoreOptions | undefined | null,
+  ): Promise<RestoreResult>;
+  /**
+   * Call `callback` with each item added, item checked or unchecked, and
+   * list renamed, by anyone, until the returned subscription is
+   * unsubscribed
+   * Changes are picked up from AnyList's realtime channel, falling back to
+   * polling while it can't be reached
+   */
+  subscribe(
+    callback: (event: ChangeEvent) => unknown,
+    options?: SubscribeOptions | undefined | null,
+  ): Promise<Subscription>;
+  /**
+   * POST each item added, item checked or unchecked, and list renamed to a
+   * webhook as JSON, signed when `secret` is set, until the returned
+   * subscription is unsubscribed
+   * Deliveries are retried a few times while the webhook can't be
+   * reached; `onFailure` is called with events that still couldn't be
+   * delivered.
+   */
+  startWebhookBridge(
+    options: WebhookOptions,
+    onFailure?: (failure: WebhookFailure) => unknown,
+  ): Promise<Subscription>;
+  /**
+   * Poll a list every `intervalMs` and call `callback` with what changed
+   * since the last poll, skipping polls where nothing did
+   */
+  watchList(
+    listId: string,
+    intervalMs: number,
+    callback: (changes: ListChanges) => unknown,
+  ): Promise<Subscription>;
+  /**
+   * Get the lists, recipes, and meal plan events changed since an earlier
+   * call's `cursor`, or since a time in milliseconds since the epoch
+   * Without either, everything is returned along with a cursor to start
+   * from. Meal plan events have no modification time, so all of them are
+   * returned when asking since a time.
+   */
+  getChangesSince(
+    since?: number | string | undefined | null,
+  ): Promise<AccountChanges>;
+  /**
+   * Cache `getLists()` and `getRecipes()` results for a while, so repeated
+   * calls don't each fetch them; replaces earlier options and clears the
+   * cache
+   * Changes made through this client drop the cached results they affect;
+   * call `invalidateCache()` to see changes made elsewhere straight away
+   */
+  setCacheOptions(options: CacheOptions): void;
+  /**
+   * Drop cached results of one type, or all of them, so the next read
+   * fetches them again
+   */
+  invalidateCache(entity?: CacheEntity | undefined | null): void;
+  /**
+   * Keep cached lists and recipes fresh by refetching them in the
+   * background, calling `callback` after each refresh; replaces an
+   * earlier schedule
+   * Each type is fetched straight away and then on its interval. While a
+   * type is refreshed, reads use the refreshed copy whatever the cache
+   * options, until two refreshes in a row fail.
+   */
+  startAutoRefresh(
+    options: AutoRefreshOptions,
+    callback?: (event: RefreshEvent) => unknown,
+  ): void;
+  /** Stop refreshing the cache in the background */
+  stopAutoRefresh(): void;
+  /**
+   * Queue changes made while AnyList can't be reached in a file, and send
+   * them in order once it can, calling `callback` as each is queued, sent,
+   * or refused
+   * Changes already queued in the file are picked up. Adding items and
+   * checking or unchecking them are queued; other changes fail as usual.
+   * Pass `null` to stop queueing, once the queue is empty.
+   */
+  setOfflineQueue(
+    path?: string | undefined | null,
+    callback?: (event: QueueEvent) => unknown,
+  ): void;
+  /**
+   * Decide what happens to queued and optimistic changes that conflict
+   * with changes made elsewhere in the meantime (e.g. checking an item
+   * someone deleted)
+   * `callback` gets each conflict and returns a resolution; without one,
+   * the other change is kept. Pass `null` to remove it.
+   */
+  setConflictResolver(
+    callback?: (conflict: QueueConflict) => ConflictResolution,
+  ): void;
+  /** Get the changes waiting in the offline queue, oldest first */
+  getQueuedOperations(): Array<QueuedOperation>;
+  /**
+   * Send queued changes in order, stopping if AnyList can't be reached,
+   * and return how many were sent
+   * Queued changes are also sent before the next change made through this
+   * client.
+   */
+  flushQueue(): Promise<number>;
+  /**
+   * Apply item changes to a local copy of the lists straight away and
+   * send them to AnyList in the background, calling `callback` as each
+   * is confirmed or rolled back
+   * While changes are unsent, `getLists()` and `getListById()` read the
+   * local copy. Adding items and checking or unchecking them are
+   * optimistic; other changes wait for AnyList as usual. Pass `false` to
+   * stop, which lets changes already made finish sending.
+   */
+  setOptimisticUpdates(
+    enabled: boolean,
+    callback?: (event: OptimisticEvent) => unknown,
+  ): void;
+  /**
+   * Record every change made through this client in a file, appending a
+   * line of JSON per change once it finishes
+   * Clients in other processes can share the file. Pass `null` to stop
+   * recording.
+   */
+  setJournalFile(path?: string | undefined | null): void;
+  /**
+   * Get the changes recorded in the journal file, including those made
+   * by other clients sharing it, oldest first
+   */
+  getJournal(options?: JournalOptions | undefined | null): JournalEntry[];
+  /**
+   * Report how well this client is keeping up with AnyList: when lists
+   * and recipes were last fetched, how many changes are queued, the last
+   * failure, and whether subscriptions are getting realtime updates
+   */
+  getSyncStatus(): SyncStatus;
+  /**
+   * Check each batch against AnyList's response once it's committed, and
+   * call `callback` for any list that has drifted from what the batch read
+   * (e.g. changed elsewhere in the meantime, or some changes not applied)
+   * Cached lists are forgotten when drift is found, so they're fetched
+   * again. Pass `null` to stop checking.
+   */
+  setDriftDetection(callback?: (event: DriftEvent) => unknown): void;
+  /** Get all of a list's settings */
+  getListSettings(listId: string): Promise<ListSettings>;
+  /** Change a list's settings */
+  updateListSettings(
+    listId: string,
+    patch: ListSettingsPatch,
+  ): Promise<ListSettings>;
+  /** Get a list's reminder and notification settings */
+  getListNotificationSettings(
+    listId: string,
+  ): Promise<ListNotificationSettings>;
+  /** Change a list's reminder and notification settings */
+  updateListNotificationSettings(
+    listId: string,
+    patch: ListNotificationSettingsPatch,
+  ): Promise<ListNotificationSettings>;
   /** Create a new category in a list */
   createCategory(
     listId: string,
     categoryGroupId: string,
     name: string,
   ): Promise<Category>;
+  /**
+   * Get the category in a group with this name (ignoring case and extra
+   * spaces), creating it if it doesn't exist
+   */
+  ensureCategory(
+    listId: string,
+    categoryGroupId: string,
+    name: string,
+  ): Promise<Category>;
   /** Delete a category from a list */
-  deleteCategory(listId: string, categoryId: string): Promise<void>;
+  deleteCategory(listId: string, categoryId: string): Promise<OperationReceipt>;
   /** Rename a category */
   renameCategory(
     listId: string,
     categoryGroupId: string,
     categoryId: string,
     newName: string,
-  ): Promise<void>;
+  ): Promise<OperationReceipt>;
+  /** Get a list's category groups, each with its categories */
+  getCategoryGroupsForList(listId: string): Promise<Array<CategoryGroup>>;
+  /** Create an empty category group (e.g. "By Meal") in a list */
+  createCategoryGroup(listId: string, name: string): Promise<CategoryGroup>;
+  /**
+   * Delete a category group, along with its categories, from a list
+   * The group the list is currently grouped by can't be deleted
+   */
+  deleteCategoryGroup(
+    listId: string,
+    groupId: string,
+  ): Promise<OperationReceipt>;
+  /**
+   * Get the category group a list's items are grouped by, or none if the
+   * list hasn't chosen one
+   */
+  getActiveCategoryGroup(listId: string): Promise<CategoryGroup | null>;
+  /**
+   * Group a list's items by one of its category groups (e.g. switch from
+   * "By Aisle" to "By Recipe")
+   */
+  setActiveCategoryGroup(
+    listId: string,
+    groupId: string,
+  ): Promise<CategoryGroup>;
+  /**
+   * Add a rule that categorizes items added without a category
+   * Exact rules are tried first, then the longest matching prefix, then
+   * regular expressions in the order they were added
+   */
+  addCategoryRule(rule: CategoryRuleInput): CategoryRule;
+  /** Remove a category rule, returning whether there was one with that ID */
+  removeCategoryRule(id: string): boolean;
+  /** Get the category rules, in the order they were added */
+  getCategoryRules(): Array<CategoryRule>;
+  /**
+   * Keep the category rules in a JSON file, loading them from it if it
+   * exists and saving the current rules to it otherwise
+   * The file is rewritten whenever a rule is added or removed
+   */
+  setCategoryRulesFile(path: string): Array<CategoryRule>;
+  /** Get the category the rules give an item name, if any */
+  matchCategoryRule(
+    name: string,
+    listId?: string | undefined | null,
+  ): string | null;
   /** Get all stores for a list */
   getStoresForList(listId: string): Promise<Array<Store>>;
   /** Create a new store for a list */
   createStore(listId: string, name: string): Promise<Store>;
+  /**
+   * Get the store in a list with this name (ignoring case and extra
+   * spaces), creating it if it doesn't exist
+   */
+  ensureStore(listId: string, name: string): Promise<Store>;
   /** Update a store's name */
-  updateStore(listId: string, storeId: string, newName: string): Promise<void>;
+  updateStore(
+    listId: string,
+    storeId: string,
+    newName: string,
+  ): Promise<OperationReceipt>;
   /** Get store filters for a list */
   getStoreFiltersForList(listId: string): Promise<Array<StoreFilter>>;
   /** Delete a store from a list */
-  deleteStore(listId: string, storeId: string): Promise<void>;
+  deleteStore(listId: string, storeId: string): Promise<OperationReceipt>;
+  /** Get the items on a list to buy at one store, in list order */
+  getItemsByStore(
+    listId: string,
+    storeId: string,
+    options?: ItemsByStoreOptions | undefined | null,
+  ): Promise<Array<ListItem>>;
   /** Get all favourite items across all lists */
   getFavourites(): Promise<Array<FavouriteItem>>;
   /** Get all favourites lists (starter lists) */
   getFavouritesLists(): Promise<Array<FavouritesList>>;
   /** Get favourites for a specific shopping list */
   getFavouritesForList(shoppingListId: string): Promise<FavouritesList>;
+  /** Rename a favourites list */
+  renameFavouritesList(id: string, newName: string): Promise<FavouritesList>;
   /** Add a favourite item to the default list */
   addFavourite(
     name: string,
@@ -117,63 +584,371 @@ export declare class AnyListClient {
     name: string,
     category?: string | undefined | null,
   ): Promise<FavouriteItem>;
+  /** Add several favourites to a list in a single request, in the order given */
+  addFavourites(
+    listId: string,
+    items: Array<FavouriteInput>,
+  ): Promise<Array<FavouriteItem>>;
+  /**
+   * Add a shopping list's crossed off items to its linked favourites list,
+   * skipping any already there, and return the new favourites
+   */
+  promoteCheckedItemsToFavourites(
+    shoppingListId: string,
+  ): Promise<Array<FavouriteItem>>;
+  /**
+   * Set the order of a favourites list (e.g. by store aisle)
+   * Favourites that aren't listed keep their order after the listed ones
+   */
+  reorderFavourites(
+    listId: string,
+    itemIds: Array<string>,
+  ): Promise<Array<FavouriteItem>>;
   /** Remove a favourite item from a list */
-  removeFavourite(listId: string, itemId: string): Promise<void>;
+  removeFavourite(listId: string, itemId: string): Promise<OperationReceipt>;
+  /**
+   * Change only the supplied fields of a favourite, keeping its place in
+   * the list
+   */
+  updateFavourite(
+    listId: string,
+    itemId: string,
+    patch: FavouritePatch,
+  ): Promise<FavouriteItem>;
   /** Add a favourite item to a shopping list */
   addFavouriteToShoppingList(
     favouriteListId: string,
     favouriteId: string,
     shoppingListId: string,
   ): Promise<ListItem>;
+  /**
+   * Add every favourite in a favourites list to a shopping list in a single
+   * request, returning the items added or unchecked
+   */
+  addAllFavouritesToShoppingList(
+    favouritesListId: string,
+    shoppingListId: string,
+    options?: AddAllFavouritesOptions | undefined | null,
+  ): Promise<Array<ListItem>>;
+  /**
+   * Get how often each favourite in a list has been added to a shopping
+   * list, most used first
+   * AnyList doesn't track this, so only additions made through this client are counted
+   */
+  getFavouriteStats(listId: string): Promise<Array<FavouriteStats>>;
+  /**
+   * Set the time zone (an IANA name like "America/Vancouver") that `Date`s
+   * passed to meal plan methods are read in, or `null` for the system's
+   * A `Date` falls on the day it is in this time zone, so a server running
+   * in UTC can still plan meals on its users' local days
+   */
+  setTimeZone(timeZone?: string | undefined | null): void;
+  /** Get the time zone set with `setTimeZone`, if any */
+  getTimeZone(): string | null;
+  /**
+   * Get the meal plan calendar, whose ID is needed to create, update, and
+   * delete meal plan events
+   */
+  getMealPlanCalendar(): Promise<MealPlanCalendar>;
   /** Get meal plan events for a date range */
   getMealPlanEvents(
-    startDate: string,
-    endDate: string,
+    startDate: Date | number | string,
+    endDate: Date | number | string,
   ): Promise<Array<MealPlanEvent>>;
+  /** Get a meal plan event by ID */
+  getMealPlanEventById(eventId: string): Promise<MealPlanEvent>;
   /** Create a meal plan event */
   createMealPlanEvent(
     calendarId: string,
-    date: string,
+    date: Date | number | string,
     recipeId?: string | undefined | null,
     title?: string | undefined | null,
     labelId?: string | undefined | null,
   ): Promise<MealPlanEvent>;
+  /** Add a note to a day of the meal plan (e.g. "Eating out") */
+  createNoteEvent(
+    date: Date | number | string,
+    text: string,
+    labelId?: string | undefined | null,
+  ): Promise<MealPlanEvent>;
+  /**
+   * Plan leftovers of a recipe event on another day
+   * Leftovers are left out when adding meal plan ingredients to a list
+   */
+  createLeftoversEvent(
+    date: Date | number | string,
+    sourceEventId: string,
+    labelId?: string | undefined | null,
+  ): Promise<MealPlanEvent>;
+  /**
+   * Create several meal plan events in a single request
+   * Events on the same day keep the order they're given in
+   */
+  bulkCreateMealPlanEvents(
+    events: Array<MealPlanEventInput>,
+  ): Promise<Array<MealPlanEvent>>;
   /** Update a meal plan event */
   updateMealPlanEvent(
     calendarId: string,
     eventId: string,
-    date: string,
+    date: Date | number | string,
     recipeId?: string | undefined | null,
     title?: string | undefined | null,
     labelId?: string | undefined | null,
-  ): Promise<void>;
+  ): Promise<OperationReceipt>;
   /** Delete a meal plan event */
-  deleteMealPlanEvent(calendarId: string, eventId: string): Promise<void>;
-  /** Add meal plan ingredients to a shopping list */
+  deleteMealPlanEvent(
+    calendarId: string,
+    eventId: string,
+  ): Promise<OperationReceipt>;
+  /**
+   * Set the order of a day's meal plan events (e.g. breakfast, lunch, dinner)
+   * Events on that day that aren't listed keep their order after the listed ones
+   */
+  reorderMealPlanEvents(
+    date: Date | number | string,
+    eventIds: Array<string>,
+  ): Promise<Array<MealPlanEvent>>;
+  /**
+   * Copy every meal plan event in the week starting on one date to the
+   * week starting on another (both YYYY-MM-DD), returning the new events
+   */
+  copyMealPlanWeek(
+    fromStartDate: Date | number | string,
+    toStartDate: Date | number | string,
+  ): Promise<Array<MealPlanEvent>>;
+  /**
+   * Save the meal plan events in the week starting on a date (YYYY-MM-DD)
+   * as a named template
+   * AnyList has nowhere to store templates, so they're kept by this client only
+   */
+  saveMealPlanTemplate(
+    name: string,
+    weekStart: Date | number | string,
+  ): Promise<MealPlanTemplate>;
+  /** Get the meal plan templates saved through this client */
+  getMealPlanTemplates(): Array<MealPlanTemplate>;
+  /**
+   * Add a template's events to the week starting on a date (YYYY-MM-DD),
+   * returning the new events
+   */
+  applyMealPlanTemplate(
+    templateId: string,
+    weekStart: Date | number | string,
+  ): Promise<Array<MealPlanEvent>>;
+  /** Forget a saved meal plan template */
+  deleteMealPlanTemplate(templateId: string): void;
+  /**
+   * Add the ingredients of the recipes planned between two dates (inclusive)
+   * to a shopping list, scaled by each event's scale factor
+   * Leftovers are skipped, since their ingredients were bought already
+   */
   addMealPlanIngredientsToList(
     listId: string,
-    startDate: string,
-    endDate: string,
-  ): Promise<void>;
+    startDate: Date | number | string,
+    endDate: Date | number | string,
+    options?: MealPlanIngredientsOptions | undefined | null,
+  ): Promise<OperationReceipt>;
+  /**
+   * Plan a recipe for each day of the week starting on a date (YYYY-MM-DD),
+   * preferring recipes that haven't been planned for the longest
+   * The new events are ordinary events; delete any that don't suit
+   */
+  suggestMealPlan(
+    weekStart: Date | number | string,
+    options?: SuggestMealPlanOptions | undefined | null,
+  ): Promise<Array<MealPlanEvent>>;
+  /**
+   * Search meal plan event titles, recipe names, and notes for every word
+   * of a query (case-insensitive), most recent first
+   */
+  searchMealPlanEvents(
+    query: string,
+    options?: SearchMealPlanOptions | undefined | null,
+  ): Promise<Array<MealPlanEvent>>;
+  /**
+   * Summarize the meal plan between two dates (YYYY-MM-DD, inclusive): how
+   * often each recipe and label was planned, and which days have no dinner
+   */
+  getMealPlanStats(
+    startDate: Date | number | string,
+    endDate: Date | number | string,
+  ): Promise<MealPlanStats>;
+  /**
+   * Add up the nutrition of the recipes planned between two dates
+   * (YYYY-MM-DD, inclusive), per day and in total
+   * Each planned recipe or leftovers counts as one serving, read from the
+   * recipe's nutrition text (e.g. "Calories: 320 kcal", "Protein: 12 g")
+   */
+  getMealPlanNutrition(
+    startDate: Date | number | string,
+    endDate: Date | number | string,
+  ): Promise<MealPlanNutrition>;
+  /**
+   * Render a readable summary of the meal plan between two dates
+   * (YYYY-MM-DD, inclusive), for posting to a chat channel
+   * Each day lists its meals in order with their label, a link to the
+   * recipe's source, and its prep time; days with no meals say so
+   */
+  renderMealPlanDigest(
+    startDate: Date | number | string,
+    endDate: Date | number | string,
+    format: DigestFormat,
+  ): Promise<string>;
+  /**
+   * Build an iCalendar (.ics) document of the meal plan between two dates
+   * (YYYY-MM-DD, inclusive), with recipe names and event notes
+   * Unlike `enableIcalendar`, nothing is shared; the document is built locally
+   */
+  exportMealPlanToIcs(
+    startDate: Date | number | string,
+    endDate: Date | number | string,
+  ): Promise<string>;
   /** Enable iCalendar sync and get the URL */
   enableIcalendar(): Promise<ICalendarInfo>;
   /** Disable iCalendar sync */
-  disableIcalendar(): Promise<void>;
+  disableIcalendar(): Promise<OperationReceipt>;
   /** Get the iCalendar URL if enabled */
   getIcalendarUrl(): Promise<string | null>;
+  /**
+   * Download the hosted iCalendar feed and read its events
+   * Fails if iCalendar sync isn't enabled
+   */
+  fetchIcalendarEvents(): Promise<Array<ICalendarEvent>>;
   /** Get all recipe collections */
   getRecipeCollections(): Promise<Array<RecipeCollection>>;
+  /** Get a recipe collection by ID */
+  getRecipeCollectionById(collectionId: string): Promise<RecipeCollection>;
+  /** Get a recipe collection by name */
+  getRecipeCollectionByName(name: string): Promise<RecipeCollection>;
+  /** Get the recipes in a collection, in the collection's order */
+  getRecipesInCollection(collectionId: string): Promise<Array<Recipe>>;
   /** Create a new recipe collection */
   createRecipeCollection(name: string): Promise<RecipeCollection>;
   /** Delete a recipe collection */
-  deleteRecipeCollection(collectionId: string): Promise<void>;
+  deleteRecipeCollection(collectionId: string): Promise<OperationReceipt>;
   /** Add a recipe to a collection */
-  addRecipeToCollection(collectionId: string, recipeId: string): Promise<void>;
+  addRecipeToCollection(
+    collectionId: string,
+    recipeId: string,
+  ): Promise<OperationReceipt>;
   /** Remove a recipe from a collection */
   removeRecipeFromCollection(
     collectionId: string,
     recipeId: string,
-  ): Promise<void>;
+  ): Promise<OperationReceipt>;
+  /**
+   * Encode a list operation as a `PBListOperationList`, for
+   * `submitRawOperation()`
+   * The operation gets a new operation ID. Encoded lists can be joined
+   * with `Buffer.concat()` to submit several operations at once.
+   */
+  encodeOperation(operation: RawListOperation): Buffer;
+  /**
+   * Send an encoded `PBListOperationList` of changes to a list, for
+   * operations this library doesn't wrap yet
+   * Every operation in it must be for the list. AnyList's response isn't
+   * checked beyond its status, so check the list afterwards.
+   */
+  submitRawOperation(
+    listId: string,
+    operations: Buffer,
+  ): Promise<OperationReceipt>;
+  /**
+   * Send a request to an AnyList endpoint with the client's credentials,
+   * for endpoints this library doesn't wrap yet
+   * `path` is relative to https://www.anylist.com (e.g. "data/user-data/get").
+   * `body`, usually an encoded protobuf message, is sent as the `operations`
+   * form field, the way AnyList expects. Returns the raw response body.
+   * Requests aren't journaled or ordered with other changes.
+   */
+  request(
+    method: string,
+    path: string,
+    body?: Buffer | undefined | null,
+  ): Promise<Buffer>;
+}
+
+/** What changed in the account, as returned by `getChangesSince()` */
+export interface AccountChanges {
+  /** Pass to the next `getChangesSince()` to get the changes after these */
+  cursor: string;
+  /** Lists that were created or changed, with all their items */
+  lists: Array<List>;
+  recipes: Array<Recipe>;
+  mealPlanEvents: Array<MealPlanEvent>;
+  /**
+   * Deleted since the cursor; always empty when changes are asked for
+   * since a time
+   */
+  deletedListIds: Array<string>;
+  deletedRecipeIds: Array<string>;
+  deletedMealPlanEventIds: Array<string>;
+}
+
+/** Options for adding a whole favourites list to a shopping list */
+export interface AddAllFavouritesOptions {
+  /**
+   * Leave out favourites already on the shopping list, unchecking any that
+   * were crossed off instead of adding them again (default: false)
+   */
+  skipExisting?: boolean;
+}
+
+/**
+ * Item changes collected by `AnyListClient.batch()`, which are sent to
+ * AnyList together by `commit()`
+ */
+export declare class Batch {
+  /** How many changes are waiting to be committed */
+  get size(): number;
+  /**
+   * Add an item when the batch is committed, returning the ID it will
+   * have, which later changes in the batch can use
+   */
+  addItem(
+    listId: string,
+    name: string,
+    quantity?: string | undefined | null,
+    note?: string | undefined | null,
+    category?: string | undefined | null,
+  ): string;
+  /** Set an item's category when the batch is committed */
+  setCategory(listId: string, itemId: string, category: string): void;
+  /** Cross off an item when the batch is committed */
+  crossOffItem(listId: string, itemId: string): void;
+  /**
+   * Send every change in one request, returning how many were sent
+   * Nothing is changed if any list or item they refer to doesn't exist.
+   * The batch is emptied once it's sent, and keeps its changes if it
+   * couldn't be, so it can be committed again.
+   */
+  commit(): Promise<number>;
+}
+
+/**
+ * How often `startAutoRefresh()` refreshes each type, in milliseconds;
+ * types without an interval aren't refreshed
+ */
+export interface AutoRefreshOptions {
+  lists?: number;
+  recipes?: number;
+}
+
+/** A type of data the client can cache */
+export declare enum CacheEntity {
+  Lists = "lists",
+  Recipes = "recipes",
+}
+
+/**
+ * How long to cache `getLists()` and `getRecipes()` results, in
+ * milliseconds; types without a time aren't cached
+ */
+export interface CacheOptions {
+  listsTtlMs?: number;
+  /** Also used by recipe searches and filters */
+  recipesTtlMs?: number;
 }
 
 /** A category for organizing list items */
@@ -191,6 +966,96 @@ export interface CategoryGroup {
   categories: Array<Category>;
 }
 
+/** A rule for categorizing new items by name */
+export interface CategoryRule {
+  id: string;
+  pattern: string;
+  matchType: CategoryRuleMatch;
+  category: string;
+  /** Only apply the rule to this list */
+  listId?: string;
+}
+
+/** A category rule to add */
+export interface CategoryRuleInput {
+  pattern: string;
+  /** How the pattern is compared with names (default: exact) */
+  matchType?: CategoryRuleMatch;
+  category: string;
+  /** Only apply the rule to this list */
+  listId?: string;
+}
+
+/** How a category rule's pattern is compared with item names */
+export declare enum CategoryRuleMatch {
+  /** The whole name, ignoring case */
+  Exact = "exact",
+  /** The start of the name, ignoring case */
+  Prefix = "prefix",
+  /** A regular expression, ignoring case */
+  Regex = "regex",
+}
+
+/** A change to a shopping list, as delivered to `subscribe()` callbacks */
+export interface ChangeEvent {
+  type: ChangeEventType;
+  listId: string;
+  /** The list's name after the change */
+  listName: string;
+  /** The item as it is now, for item events */
+  item?: ListItem;
+  /** The list's previous name, for `listRenamed` */
+  oldName?: string;
+}
+
+/** What changed in a shopping list */
+export declare enum ChangeEventType {
+  /** An item was added to the list */
+  ItemAdded = "itemAdded",
+  /** An item was checked or unchecked */
+  ItemChecked = "itemChecked",
+  /** The list was renamed */
+  ListRenamed = "listRenamed",
+}
+
+/**
+ * How to settle a conflict between a queued or optimistic change and the
+ * list
+ */
+export declare enum ConflictResolution {
+  /** Send the change as it is */
+  KeepLocal = "keepLocal",
+  /** Drop the change */
+  KeepRemote = "keepRemote",
+  /**
+   * Combine them: a duplicate item is unchecked instead of added again,
+   * and a renamed item is checked or unchecked under its new name; other
+   * conflicts are kept remote
+   */
+  Merge = "merge",
+}
+
+/** How a queued or optimistic change conflicts with the list as it is now */
+export declare enum ConflictType {
+  /** The list was deleted */
+  ListDeleted = "listDeleted",
+  /** The item to check or uncheck was deleted */
+  ItemDeleted = "itemDeleted",
+  /** An item with the same name was added to the list */
+  DuplicateItem = "duplicateItem",
+  /** The item to check or uncheck was renamed */
+  ItemRenamed = "itemRenamed",
+}
+
+/**
+ * Convert ingredient quantities between US measures and metric
+ * (e.g. 1 cup = 240 ml, 8 oz = 230 g, 500 g = 1 lb)
+ */
+export declare function convertIngredientUnits(
+  ingredients: Array<Ingredient>,
+  system: UnitSystem,
+): Array<Ingredient>;
+
 /** Options for creating a new recipe */
 export interface CreateRecipeOptions {
   /** Recipe name (required) */
@@ -199,6 +1064,11 @@ export interface CreateRecipeOptions {
   ingredients: Array<IngredientInput>;
   /** Preparation/cooking steps */
   preparationSteps: Array<string>;
+  /**
+   * Structured steps with section headers, used instead of
+   * `preparationSteps` when given
+   */
+  steps?: Array<RecipeStepInput>;
   /** Recipe notes/description */
   note?: string;
   /** Source name (e.g., "Web", "Cookbook") */
@@ -207,28 +1077,110 @@ export interface CreateRecipeOptions {
   sourceUrl?: string;
   /** Serving size (e.g., "4 servings") */
   servings?: string;
-  /** Prep time in minutes */
-  prepTime?: number;
-  /** Cook time in minutes */
-  cookTime?: number;
+  /** Prep time in minutes, or an ISO 8601 duration (e.g. "PT15M") */
+  prepTime?: number | string;
+  /** Cook time in minutes, or an ISO 8601 duration (e.g. "PT1H") */
+  cookTime?: number | string;
   /** Rating from 1-5 */
   rating?: number;
   /** Nutritional information */
   nutritionalInfo?: string;
   /** Photo ID (from upload_photo) */
   photoId?: string;
+  /**
+   * URL of an image to download and use as the photo, when `photoId`
+   * isn't given
+   */
+  photoUrl?: string;
 }
 
-/** A favourite item (starter list item) */
-export interface FavouriteItem {
-  id: string;
-  listId: string;
+/** Planned nutrition for one day */
+export interface DayNutrition {
+  /** Date (YYYY-MM-DD) */
+  date: string;
+  /** One serving of each planned recipe with nutrition information */
+  totals: NutritionTotals;
+  /** Number of planned recipes counted in the totals */
+  recipeCount: number;
+}
+
+/**
+ * Compare two snapshots (or recipe backups) and return what changed from `a`
+ * to `b`: lists and recipes added and removed, items added, removed, and
+ * edited in each list, and the fields changed in each recipe
+ */
+export declare function diffSnapshots(a: string, b: string): SnapshotDiff;
+
+/** Output format for a meal plan digest */
+export declare enum DigestFormat {
+  Markdown = "markdown",
+  Html = "html",
+}
+
+/**
+ * A list that no longer matches what a batch expected, as delivered to the
+ * `setDriftDetection()` callback
+ */
+export interface DriftEvent {
+  listId: string;
+  reason: DriftReason;
+  /** The items whose changes are affected, if any */
+  itemIds: Array<string>;
+}
+
+/** How a list drifted from what a batch expected of it */
+export declare enum DriftReason {
+  /**
+   * The list was changed between the batch reading it and AnyList
+   * applying the batch
+   */
+  ListChanged = "listChanged",
+  /** AnyList didn't apply some of the batch's changes */
+  ChangesSkipped = "changesSkipped",
+  /** AnyList asked for the list to be fetched again in full */
+  RefreshRequired = "refreshRequired",
+}
+
+/** Options for backing up all recipes */
+export interface ExportAllRecipesOptions {
+  /** Embed each recipe's photo as base64 (default false) */
+  includePhotos?: boolean;
+}
+
+/** Output format for exported recipes */
+export declare enum ExportFormat {
+  Markdown = "markdown",
+  Json = "json",
+}
+
+/** A favourite to add to a favourites list */
+export interface FavouriteInput {
   name: string;
   quantity?: string;
   details?: string;
   category?: string;
 }
 
+/** A favourite item (starter list item) */
+export interface FavouriteItem {
+  id: string;
+  listId: string;
+  name: string;
+  quantity?: string;
+  details?: string;
+  category?: string;
+  /** Position in the list, once it has been arranged */
+  sortIndex?: number;
+}
+
+/** Fields to change on a favourite; omitted fields are left as they are */
+export interface FavouritePatch {
+  name?: string;
+  quantity?: string;
+  details?: string;
+  category?: string;
+}
+
 /** A list of favourite items (starter list) */
 export interface FavouritesList {
   id: string;
@@ -237,6 +1189,39 @@ export interface FavouritesList {
   shoppingListId?: string;
 }
 
+/** How often a favourite has been added to a shopping list */
+export interface FavouriteStats {
+  favouriteId: string;
+  name: string;
+  timesAdded: number;
+  /** When it was last added, in milliseconds since the epoch */
+  lastAddedAt?: number;
+}
+
+/** Options for fetching a single list */
+export interface GetListOptions {
+  /** Include checked (crossed off) items (default: true) */
+  includeChecked?: boolean;
+}
+
+/** Get JSON Schemas for all exported types, keyed by type name */
+export declare function getSchemas(): Record<string, any>;
+
+/** Format a number of minutes for display (e.g. 90 = "1 hr 30 min") */
+export declare function humanizeMinutes(minutes: number): string;
+
+/** An event read from the hosted iCalendar feed */
+export interface ICalendarEvent {
+  uid: string;
+  summary?: string;
+  description?: string;
+  url?: string;
+  /** Start date (YYYY-MM-DD), or date and time for timed events */
+  start: string;
+  /** End date, exclusive for all-day events */
+  end?: string;
+}
+
 /** iCalendar sync information */
 export interface ICalendarInfo {
   enabled: boolean;
@@ -249,6 +1234,14 @@ export interface Ingredient {
   name: string;
   quantity?: string;
   note?: string;
+  /** True for section headers, e.g. "For the sauce" */
+  isHeader?: boolean;
+  /**
+   * The recipe this ingredient is made from, when it's marked "see recipe"
+   * (as its note, or after its name: "Pizza dough — see recipe") and names
+   * another recipe
+   */
+  recipeId?: string;
 }
 
 /** Input for creating a new ingredient */
@@ -256,6 +1249,65 @@ export interface IngredientInput {
   name: string;
   quantity?: string;
   note?: string;
+  /** Start a new section, e.g. "For the sauce" (quantity and note are ignored) */
+  isHeader?: boolean;
+}
+
+/** A new category for an item */
+export interface ItemCategoryChange {
+  itemId: string;
+  category: string;
+}
+
+/** Fields to change on an item; omitted fields are left as they are */
+export interface ItemPatch {
+  name?: string;
+  quantity?: string;
+  note?: string;
+  category?: string;
+  checked?: boolean;
+}
+
+/** Which items to include when listing a store's items */
+export interface ItemsByStoreOptions {
+  /** Include items with no store, which can be bought anywhere (default: true) */
+  includeUnassigned?: boolean;
+  /** Include checked (crossed off) items (default: false) */
+  includeChecked?: boolean;
+}
+
+/** A change made through a client, as recorded in the journal */
+export interface JournalEntry {
+  id: string;
+  /** The method that made the change (e.g. "addItem") */
+  operation: string;
+  /** The IDs it was given, then the IDs of anything it created or returned */
+  entityIds: Array<string>;
+  /** When the change started and finished, in milliseconds since the epoch */
+  startedAt: number;
+  finishedAt: number;
+  succeeded: boolean;
+  /** Why the change failed */
+  error?: string;
+  /** The ID of the process that made the change */
+  processId: number;
+}
+
+/** Which journal entries `getJournal()` returns */
+export interface JournalOptions {
+  /**
+   * Only entries for changes that finished at or after this time, in
+   * milliseconds since the epoch
+   */
+  since?: number;
+}
+
+/** How many events had a label */
+export interface LabelFrequency {
+  /** The label's ID, or none for unlabelled events */
+  labelId?: string;
+  name?: string;
+  count: number;
 }
 
 /** A grocery list */
@@ -265,6 +1317,26 @@ export interface List {
   items: Array<ListItem>;
 }
 
+/**
+ * What changed in a list between two reads, as delivered to `watchList()`
+ * callbacks
+ */
+export interface ListChanges {
+  listId: string;
+  /** The list's name now */
+  name: string;
+  /** The list's previous name, if it was renamed */
+  oldName?: string;
+  added: Array<ListItem>;
+  /** Items that were deleted, as they were before */
+  removed: Array<ListItem>;
+  /**
+   * Items with any other change (e.g. checked, renamed, or a new
+   * quantity), as they are now
+   */
+  updated: Array<ListItem>;
+}
+
 /** A grocery list item */
 export interface ListItem {
   id: string;
@@ -275,6 +1347,96 @@ export interface ListItem {
   category?: string;
 }
 
+/** Which of a list's items `getListItems()` returns */
+export interface ListItemsOptions {
+  /** How many items to skip (default: 0) */
+  offset?: number;
+  /** Maximum number of items to return (default: all) */
+  limit?: number;
+  /** Include checked (crossed off) items (default: true) */
+  includeChecked?: boolean;
+}
+
+/** A page of a list's items */
+export interface ListItemsPage {
+  items: Array<ListItem>;
+  /** How many items the list has in all */
+  total: number;
+  /** The offset of the next page, if there are more items */
+  nextOffset?: number;
+}
+
+/** Reminder and notification settings for a list */
+export interface ListNotificationSettings {
+  /** Remind collaborators when they arrive at one of the list's locations */
+  locationNotificationsEnabled: boolean;
+  /** What the app icon badge counts for this list */
+  badgeMode?: string;
+  /** Locations that trigger reminders (managed in the AnyList apps) */
+  notificationLocations: Array<NotificationLocation>;
+}
+
+/** Changes to a list's notification settings; omitted fields are left as they are */
+export interface ListNotificationSettingsPatch {
+  locationNotificationsEnabled?: boolean;
+  badgeMode?: string;
+}
+
+/** Display and behaviour settings for a list */
+export interface ListSettings {
+  hideCategories: boolean;
+  hideCompletedItems: boolean;
+  hidePrices: boolean;
+  hideRunningTotals: boolean;
+  hideStoreNames: boolean;
+  /** Running total shown on the left of the list footer */
+  leftRunningTotalType?: number;
+  /** Running total shown on the right of the list footer */
+  rightRunningTotalType?: number;
+  listItemSortOrder?: string;
+  /** Category group used to group the list's items */
+  categoryGroupId?: string;
+  /** Store filter applied by default */
+  storeFilterId?: string;
+  rememberItemCategories: boolean;
+  favouritesAutocompleteEnabled: boolean;
+  recentItemsAutocompleteEnabled: boolean;
+  genericGroceryAutocompleteEnabled: boolean;
+  listThemeId?: string;
+  badgeMode?: string;
+  locationNotificationsEnabled: boolean;
+  /** Favourites list linked to this list (read-only) */
+  linkedFavouritesListId?: string;
+}
+
+/** Changes to a list's settings; omitted fields are left as they are */
+export interface ListSettingsPatch {
+  hideCategories?: boolean;
+  hideCompletedItems?: boolean;
+  hidePrices?: boolean;
+  hideRunningTotals?: boolean;
+  hideStoreNames?: boolean;
+  leftRunningTotalType?: number;
+  rightRunningTotalType?: number;
+  listItemSortOrder?: string;
+  categoryGroupId?: string;
+  storeFilterId?: string;
+  rememberItemCategories?: boolean;
+  favouritesAutocompleteEnabled?: boolean;
+  recentItemsAutocompleteEnabled?: boolean;
+  genericGroceryAutocompleteEnabled?: boolean;
+  listThemeId?: string;
+  badgeMode?: string;
+  locationNotificationsEnabled?: boolean;
+}
+
+/** The account's meal plan calendar */
+export interface MealPlanCalendar {
+  id: string;
+  /** Labels in display order */
+  labels: Array<MealPlanLabel>;
+}
+
 /** A meal plan event */
 export interface MealPlanEvent {
   id: string;
@@ -283,6 +1445,254 @@ export interface MealPlanEvent {
   recipeId?: string;
   labelId?: string;
   details?: string;
+  /** Position among the day's events; lower comes first */
+  sortIndex?: number;
+  /** Whether the event plans a recipe or is a note (`title` holds the note) */
+  eventType: MealPlanEventType;
+}
+
+/** Input for creating a meal plan event */
+export interface MealPlanEventInput {
+  date: Date | number | string;
+  recipeId?: string;
+  /** Title, for events without a recipe */
+  title?: string;
+  labelId?: string;
+  details?: string;
+}
+
+/** What a meal plan event is for */
+export declare enum MealPlanEventType {
+  /** A planned recipe */
+  Recipe = "recipe",
+  /** Leftovers of an earlier recipe event, which need no shopping */
+  Leftovers = "leftovers",
+  /** A note for the day with no recipe (e.g. "Eating out") */
+  Note = "note",
+}
+
+/** Which planned meals to shop for */
+export interface MealPlanIngredientsOptions {
+  /** Leave out events for these recipes */
+  excludeRecipeIds?: Array<string>;
+  /** Leave out events with these labels (e.g. breakfast) */
+  excludeLabelIds?: Array<string>;
+  /** Leave out events before today, which have already been cooked */
+  excludeCooked?: boolean;
+  /** Scale factors keyed by event ID, used instead of the events' own */
+  scaleFactors?: Record<string, number>;
+}
+
+/** A label for meal plan events (e.g. "Dinner") */
+export interface MealPlanLabel {
+  id: string;
+  name: string;
+  /** Color as a hex string, e.g. "ff9500" */
+  hexColor?: string;
+}
+
+/** Planned nutrition over a date range */
+export interface MealPlanNutrition {
+  /** Every day in the range, in order */
+  days: Array<DayNutrition>;
+  total: NutritionTotals;
+  dailyAverage: NutritionTotals;
+  /** Planned recipes whose nutrition couldn't be read, so aren't counted */
+  recipesWithoutNutrition: Array<string>;
+}
+
+/** A summary of the meal plan over a date range */
+export interface MealPlanStats {
+  eventCount: number;
+  /** Planned recipes, most frequent first */
+  recipes: Array<RecipeFrequency>;
+  /** Event labels, most frequent first */
+  labels: Array<LabelFrequency>;
+  /**
+   * Days (YYYY-MM-DD) with no event labelled "Dinner", or with no events at
+   * all if there is no such label
+   */
+  daysWithoutDinner: Array<string>;
+}
+
+/** A named week of meal plan events that can be applied to any week */
+export interface MealPlanTemplate {
+  id: string;
+  name: string;
+  events: Array<MealPlanTemplateEvent>;
+}
+
+/** An event in a meal plan template */
+export interface MealPlanTemplateEvent {
+  /** Day of the week the event falls on, counted from the week's first day (0-6) */
+  day: number;
+  title?: string;
+  recipeId?: string;
+  labelId?: string;
+  details?: string;
+}
+
+/** A location that triggers reminders for a list */
+export interface NotificationLocation {
+  id: string;
+  name?: string;
+  address?: string;
+  latitude?: number;
+  longitude?: number;
+}
+
+/** Calories (kcal) and macronutrients (grams) */
+export interface NutritionTotals {
+  calories: number;
+  protein: number;
+  carbohydrates: number;
+  fat: number;
+}
+
+/**
+ * What a change that has nothing else to return returns, to match it up
+ * with its journal entry
+ */
+export interface OperationReceipt {
+  /** The ID of the change's entry in the journal */
+  id: string;
+  /** The method that made the change (e.g. "deleteItem") */
+  operation: string;
+  /**
+   * When AnyList accepted the change, in milliseconds since the epoch by
+   * this machine's clock
+   */
+  completedAt: number;
+}
+
+/**
+ * Something that happened to a change made optimistically, as delivered to
+ * the `setOptimisticUpdates()` callback
+ */
+export interface OptimisticEvent {
+  type: OptimisticEventType;
+  operation: QueuedOperation;
+  /** Why the change couldn't be sent, for `rolledBack` */
+  error?: string;
+}
+
+/** What happened to a change made optimistically */
+export declare enum OptimisticEventType {
+  /** AnyList accepted the change */
+  Confirmed = "confirmed",
+  /** The change couldn't be sent, so it was undone locally */
+  RolledBack = "rolledBack",
+}
+
+/**
+ * Read the schema.org recipe (JSON-LD or microdata) in a web page's HTML into
+ * the options for `createRecipe`, without creating it
+ * The page's main image becomes `photoUrl` when its URL is absolute
+ */
+export declare function parseRecipeHtml(html: string): CreateRecipeOptions;
+
+/**
+ * A queued or optimistic change that conflicts with changes made elsewhere
+ * since it was made, as passed to the `setConflictResolver()` callback
+ */
+export interface QueueConflict {
+  type: ConflictType;
+  operation: QueuedOperation;
+  /**
+   * The item already on the list, for `duplicateItem`, or as it is now,
+   * for `itemRenamed`
+   */
+  remoteItem?: ListItem;
+}
+
+/** A kind of change the offline queue can hold */
+export declare enum QueuedChange {
+  /** Add an item */
+  Add = "add",
+  /** Cross off (check) an item */
+  Check = "check",
+  /** Uncheck an item */
+  Uncheck = "uncheck",
+}
+
+/** A change waiting in the offline queue */
+export interface QueuedOperation {
+  /** The operation's ID, which AnyList sees once it's sent */
+  id: string;
+  change: QueuedChange;
+  /** What the change does (e.g. "Add Milk") */
+  description: string;
+  listId: string;
+  itemId?: string;
+  /** The item's name when the change was made, if it's known */
+  name?: string;
+  /** When the change was made, in milliseconds since the epoch */
+  queuedAt: number;
+}
+
+/**
+ * Something that happened to a queued change, as delivered to the
+ * `setOfflineQueue()` callback
+ */
+export interface QueueEvent {
+  type: QueueEventType;
+  operation: QueuedOperation;
+  /** Why AnyList refused the change, for `rejected` */
+  error?: string;
+}
+
+/** What happened to a change in the offline queue */
+export declare enum QueueEventType {
+  /** AnyList couldn't be reached, so the change was queued */
+  Queued = "queued",
+  /** The change was sent to AnyList, or merged with a conflicting one */
+  Flushed = "flushed",
+  /** AnyList refused the change, so it was dropped from the queue */
+  Rejected = "rejected",
+  /** The change conflicted with one made elsewhere, which was kept */
+  Discarded = "discarded",
+}
+
+/**
+ * A list item for `encodeOperation()`, with the fields of AnyList's
+ * `PBListItem`
+ */
+export interface RawListItem {
+  /** Generated if not given */
+  identifier?: string;
+  name?: string;
+  quantity?: string;
+  details?: string;
+  category?: string;
+  checked?: boolean;
+}
+
+/**
+ * A list operation for `encodeOperation()`, with the fields of AnyList's
+ * `PBListOperation`
+ */
+export interface RawListOperation {
+  /** What the operation does (e.g. "set-list-item-checked") */
+  handlerId: string;
+  listId: string;
+  listItemId?: string;
+  updatedValue?: string;
+  originalValue?: string;
+  listItem?: RawListItem;
+}
+
+/** Options for listing recent recipes */
+export interface RecentRecipesOptions {
+  /** Order by when recipes were added or last changed (default: added) */
+  by?: RecentRecipesOrder;
+  /** Maximum number of recipes to return (default: all) */
+  limit?: number;
+}
+
+/** Which date orders recently used recipes */
+export declare enum RecentRecipesOrder {
+  Added = "added",
+  Updated = "updated",
 }
 
 /** A recipe */
@@ -297,9 +1707,41 @@ export interface Recipe {
   servings?: string;
   prepTime?: number;
   cookTime?: number;
+  /** Prep and cook time together, in minutes */
+  totalTime?: number;
   rating?: number;
   nutritionalInfo?: string;
   photoId?: string;
+  /** All of the recipe's photos, in order */
+  photoIds: Array<string>;
+  /** Whether the recipe is written out or just photos */
+  kind: RecipeKind;
+  /** Timers mentioned in the preparation steps (e.g. "bake 25 minutes") */
+  timers: Array<RecipeTimer>;
+  /** Preparation steps with section headers marked and timers attached */
+  steps: Array<RecipeStep>;
+  /** When the recipe was added, in milliseconds since the epoch */
+  createdAt?: number;
+  /** When the recipe was last changed, in milliseconds since the epoch */
+  updatedAt?: number;
+  /**
+   * Date of the most recent meal plan event for this recipe up to today
+   * (YYYY-MM-DD)
+   */
+  lastCookedAt?: string;
+}
+
+/** A recipe added, removed, or changed between two snapshots */
+export interface RecipeChanges {
+  recipeId: string;
+  name: string;
+  /** Its name in the earlier snapshot, if it was renamed */
+  oldName?: string;
+  /**
+   * The fields that changed (e.g. "ingredients", "rating"), for changed
+   * recipes
+   */
+  changedFields: Array<string>;
 }
 
 /** A collection of recipes */
@@ -309,6 +1751,197 @@ export interface RecipeCollection {
   recipeIds: Array<string>;
 }
 
+/** Conditions recipes must meet to be returned by `getRecipes` */
+export interface RecipeFilter {
+  /** Only recipes rated at least this (1-5) */
+  minRating?: number;
+  /**
+   * Only recipes whose prep and cook time add up to at most this many
+   * minutes; recipes without times are left out
+   */
+  maxTotalTime?: number;
+  /** Only recipes in this collection */
+  collectionId?: string;
+}
+
+/** How often a recipe was planned */
+export interface RecipeFrequency {
+  recipeId: string;
+  /** The recipe's name, if it still exists */
+  name?: string;
+  count: number;
+}
+
+/** Layout for recipes rendered as HTML */
+export declare enum RecipeHtmlTemplate {
+  /** Photo, note, ingredients, steps, and nutrition */
+  Full = "full",
+  /** Just what's needed while cooking: servings, times, ingredients, and steps */
+  Compact = "compact",
+}
+
+/**
+ * A recipe from an export that couldn't be imported, or couldn't be added to
+ * one of its collections
+ */
+export interface RecipeImportFailure {
+  /** The recipe's name in the export */
+  name: string;
+  error: string;
+}
+
+/** What `importRecipesFromPaprika()` or `importRecipesFromMealie()` did */
+export interface RecipeImportResult {
+  created: Array<Recipe>;
+  /**
+   * Recipes that couldn't be created, or whose photo couldn't be uploaded,
+   * and created recipes that couldn't be added to a collection
+   */
+  failed: Array<RecipeImportFailure>;
+}
+
+/** What a recipe is made of */
+export declare enum RecipeKind {
+  /** Ingredients and steps */
+  Standard = "standard",
+  /** Only photos, e.g. of a cookbook page */
+  Photo = "photo",
+}
+
+/** Changes to a recipe; omitted fields are left as they are */
+export interface RecipePatch {
+  name?: string;
+  ingredients?: Array<IngredientInput>;
+  preparationSteps?: Array<string>;
+  /** Structured steps, used instead of `preparationSteps` when given */
+  steps?: Array<RecipeStepInput>;
+  note?: string;
+  sourceName?: string;
+  sourceUrl?: string;
+  servings?: string;
+  /** Minutes, or an ISO 8601 duration */
+  prepTime?: number | string;
+  /** Minutes, or an ISO 8601 duration */
+  cookTime?: number | string;
+  /** Rating from 1-5, or 0 to clear it */
+  rating?: number;
+  nutritionalInfo?: string;
+  photoId?: string;
+  /**
+   * URL of an image to download and use as the photo, when `photoId`
+   * isn't given
+   */
+  photoUrl?: string;
+}
+
+/** A downloaded recipe photo */
+export interface RecipePhoto {
+  data: Buffer;
+  /** MIME type of the image, e.g. "image/jpeg" */
+  contentType: string;
+}
+
+/** A recipe field that searches can match against */
+export declare enum RecipeSearchField {
+  Name = "name",
+  Ingredients = "ingredients",
+  Note = "note",
+  Steps = "steps",
+}
+
+/** A recipe to add to a list, with an optional scale factor */
+export interface RecipeSelection {
+  recipeId: string;
+  /** Multiply ingredient quantities by this (default 1) */
+  scaleFactor?: number;
+}
+
+/** A preparation step, or a header starting a section of steps */
+export interface RecipeStep {
+  text: string;
+  /** True for section headers, e.g. "Make the sauce" */
+  isHeader: boolean;
+  /** Timers mentioned in this step */
+  timers: Array<RecipeTimer>;
+}
+
+/** Input for a preparation step */
+export interface RecipeStepInput {
+  text: string;
+  /** Start a new section, e.g. "Make the sauce" */
+  isHeader?: boolean;
+}
+
+/** A cooking timer mentioned in a recipe step */
+export interface RecipeTimer {
+  /** Index of the step in `preparationSteps` */
+  stepIndex: number;
+  seconds: number;
+  /** The text the timer was found in, e.g. "25 minutes" */
+  label: string;
+}
+
+/** An earlier version of a recipe */
+export interface RecipeVersion {
+  id: string;
+  /** When the recipe was changed from this version, in milliseconds since the epoch */
+  savedAt: number;
+  recipe: Recipe;
+}
+
+/** A background refresh, as delivered to the `startAutoRefresh()` callback */
+export interface RefreshEvent {
+  type: RefreshEventType;
+  entity: CacheEntity;
+  /** Why the refresh failed, for `failed` */
+  error?: string;
+}
+
+/** What happened to a background refresh */
+export declare enum RefreshEventType {
+  Refreshed = "refreshed",
+  Failed = "failed",
+}
+
+/** Options for rendering a recipe as HTML */
+export interface RenderRecipeHtmlOptions {
+  /** Layout to use (default: full) */
+  template?: RecipeHtmlTemplate;
+}
+
+/** A list, item, or recipe from a snapshot and the one it was restored as */
+export interface RestoredEntity {
+  type: SnapshotEntity;
+  /** Its ID in the snapshot */
+  oldId: string;
+  /** Its ID now: the one created, or the existing one it matched */
+  newId: string;
+  name: string;
+  /** The list it's on now, for items */
+  listId?: string;
+}
+
+/** How `restoreFromSnapshot()` treats lists and recipes that already exist */
+export declare enum RestoreMode {
+  /** Keep them, adding only what's missing */
+  Merge = "merge",
+  /** Replace them with the snapshot's */
+  Replace = "replace",
+}
+
+/** Options for restoring a snapshot */
+export interface RestoreOptions {
+  /** Defaults to `merge` */
+  mode?: RestoreMode;
+}
+
+/** What `restoreFromSnapshot()` did */
+export interface RestoreResult {
+  created: Array<RestoredEntity>;
+  /** Entities that already existed, so weren't created again */
+  skipped: Array<RestoredEntity>;
+}
+
 /** Saved authentication tokens for resuming sessions */
 export interface SavedTokens {
   userId: string;
@@ -317,6 +1950,47 @@ export interface SavedTokens {
   isPremiumUser: boolean;
 }
 
+/**
+ * Scale ingredient quantities by a factor, adjusting fractions and units
+ * (e.g. 1/2 cup × 3 = 1 1/2 cups, 2 tsp × 3 = 2 tbsp)
+ */
+export declare function scaleIngredients(
+  ingredients: Array<Ingredient>,
+  factor: number,
+): Array<Ingredient>;
+
+/** Date range for searching meal plan events */
+export interface SearchMealPlanOptions {
+  /** Earliest date to search */
+  startDate?: Date | number | string;
+  /** Latest date to search */
+  endDate?: Date | number | string;
+}
+
+/** Options for searching recipes */
+export interface SearchRecipesOptions {
+  /** Fields to search (default: all) */
+  fields?: Array<RecipeSearchField>;
+}
+
+/** What changed between two snapshots, as returned by `diffSnapshots()` */
+export interface SnapshotDiff {
+  listsAdded: Array<List>;
+  listsRemoved: Array<List>;
+  /** Lists in both snapshots whose name or items changed */
+  listsChanged: Array<ListChanges>;
+  recipesAdded: Array<RecipeChanges>;
+  recipesRemoved: Array<RecipeChanges>;
+  recipesChanged: Array<RecipeChanges>;
+}
+
+/** A kind of entity in a snapshot */
+export declare enum SnapshotEntity {
+  List = "list",
+  ListItem = "listItem",
+  Recipe = "recipe",
+}
+
 /** A store for organizing where to buy items */
 export interface Store {
   id: string;
@@ -330,3 +2004,115 @@ export interface StoreFilter {
   name: string;
   storeIds: Array<string>;
 }
+
+/** How to watch for changes */
+export interface SubscribeOptions {
+  /**
+   * Listen on AnyList's realtime channel, polling only while it's
+   * disconnected (default: true)
+   */
+  realtime?: boolean;
+  /** How often to poll for changes, in milliseconds (default: 30000) */
+  pollIntervalMs?: number;
+}
+
+/**
+ * A running `subscribe()` or `watchList()`; callbacks keep coming until
+ * `unsubscribe()` is called
+ */
+export declare class Subscription {
+  /**
+   * Whether changes are arriving over AnyList's realtime channel, rather
+   * than by polling
+   */
+  get realtime(): boolean;
+  /** Stop delivering events */
+  unsubscribe(): void;
+}
+
+/** Options for suggesting a week of meals */
+export interface SuggestMealPlanOptions {
+  /** Pick recipes from this collection (default: all recipes) */
+  collectionId?: string;
+  /** Skip recipes planned this many days before the week (default 14) */
+  avoidRecentDays?: number;
+  /**
+   * Label for the new events (e.g. dinner); days that already have an event
+   * with this label, or any recipe when no label is given, are skipped
+   */
+  labelId?: string;
+}
+
+/** A change or fetch that failed */
+export interface SyncError {
+  /** What was being done (e.g. "addItem" or "getLists") */
+  operation: string;
+  message: string;
+  /** When it failed, in milliseconds since the epoch */
+  at: number;
+}
+
+/**
+ * How well a client is keeping up with AnyList, as returned by
+ * `getSyncStatus()`
+ */
+export interface SyncStatus {
+  /**
+   * When the lists were last fetched from AnyList, in milliseconds since
+   * the epoch; cached reads don't count
+   */
+  listsFetchedAt?: number;
+  /** When the recipes were last fetched from AnyList */
+  recipesFetchedAt?: number;
+  /** How many changes are waiting in the offline queue */
+  queuedOperations: number;
+  /** The most recent failure, which may be older than the last fetch */
+  lastError?: SyncError;
+  /**
+   * Whether any running subscription is connected to AnyList's realtime
+   * channel
+   */
+  realtimeConnected: boolean;
+}
+
+/** A system of measurement */
+export declare enum UnitSystem {
+  Metric = "metric",
+  Imperial = "imperial",
+}
+
+/** Which action an upsert performed */
+export declare enum UpsertAction {
+  Created = "created",
+  Updated = "updated",
+}
+
+/** The result of upserting an item */
+export interface UpsertItemResult {
+  action: UpsertAction;
+  item: ListItem;
+}
+
+/** A change event that couldn't be delivered to the webhook */
+export interface WebhookFailure {
+  event: ChangeEvent;
+  error: string;
+}
+
+/** Where to deliver change events, for `startWebhookBridge()` */
+export interface WebhookOptions {
+  /** The URL each event is POSTed to as JSON */
+  url: string;
+  /**
+   * Sign requests with this shared secret (see the README for how to
+   * check signatures)
+   */
+  secret?: string;
+  /**
+   * Listen on AnyList's realtime channel, polling only while it's
+   * disconnected (default: true)
+   */
+  realtime?: boolean;
+  /** How often to poll for changes, in milliseconds (default: 30000) */
+  pollIntervalMs?: number;
+}
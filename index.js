@@ -784,5 +784,67 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`);
 }
 
-const { AnyListClient } = nativeBinding;
-export { AnyListClient };
+const {
+  AnyListClient,
+  Batch,
+  CacheEntity,
+  CategoryRuleMatch,
+  ChangeEventType,
+  ConflictResolution,
+  ConflictType,
+  convertIngredientUnits,
+  diffSnapshots,
+  DigestFormat,
+  DriftReason,
+  ExportFormat,
+  getSchemas,
+  humanizeMinutes,
+  MealPlanEventType,
+  OptimisticEventType,
+  parseRecipeHtml,
+  QueuedChange,
+  QueueEventType,
+  RecentRecipesOrder,
+  RecipeHtmlTemplate,
+  RecipeKind,
+  RecipeSearchField,
+  RefreshEventType,
+  RestoreMode,
+  scaleIngredients,
+  SnapshotEntity,
+  Subscription,
+  UnitSystem,
+  UpsertAction,
+} = nativeBinding;
+export {
+  AnyListClient,
+  Batch,
+  CacheEntity,
+  CategoryRuleMatch,
+  ChangeEventType,
+  ConflictResolution,
+  ConflictType,
+  convertIngredientUnits,
+  diffSnapshots,
+  DigestFormat,
+  DriftReason,
+  ExportFormat,
+  getSchemas,
+  humanizeMinutes,
+  MealPlanEventType,
+  OptimisticEventType,
+  parseRecipeHtml,
+  QueuedChange,
+  QueueEventType,
+  RecentRecipesOrder,
+  RecipeHtmlTemplate,
+  RecipeKind,
+  RecipeSearchField,
+  RefreshEventType,
+  RestoreMode,
+  scaleIngredients,
+  SnapshotEntity,
+  Subscription,
+  UnitSystem,
+  UpsertAction,
+};
@@ -0,0 +1,125 @@
+//! An opt-in cache of lists and recipes.
+//!
+//! Each entity type has its own time to live and is only cached once one is
+//! set. Entries are kept whole, as the account's full set of lists or
+//! recipes, since that's how AnyList returns them.
+//!
+//! While `startAutoRefresh()` refreshes a type, its entry is kept without a
+//! time to live, until two refreshes in a row have been missed.
+//!
+//! Each entry counts how many times it's been dropped. A fetch notes the
+//! count when it starts and only stores its result if the count hasn't moved,
+//! so a fetch that was under way when a change was made can't put back what
+//! the change made out of date.
+
+use std::time::{Duration, Instant};
+
+use crate::{CacheEntity, CacheOptions, List, Recipe};
+
+struct Entry<T> {
+    ttl: Option<Duration>,
+    /// How often the entry is refreshed in the background
+    refresh: Option<Duration>,
+    value: Option<(Instant, T)>,
+    /// How many times the value has been dropped
+    generation: u64,
+}
+
+impl<T> Default for Entry<T> {
+    fn default() -> Self {
+        Entry {
+            ttl: None,
+            refresh: None,
+            value: None,
+            generation: 0,
+        }
+    }
+}
+
+impl<T: Clone> Entry<T> {
+    fn get(&self) -> Option<T> {
+        let (stored_at, value) = self.value.as_ref()?;
+        let age = stored_at.elapsed();
+        let fresh = self.ttl.is_some_and(|ttl| age < ttl)
+            || self.refresh.is_some_and(|refresh| age < refresh * 2);
+        fresh.then(|| value.clone())
+    }
+
+    /// Store a value fetched when the entry was at `generation`
+    fn put(&mut self, generation: u64, value: impl FnOnce() -> T) {
+        if generation == self.generation && (self.ttl.is_some() || self.refresh.is_some()) {
+            self.value = Some((Instant::now(), value()));
+        }
+    }
+
+    fn clear(&mut self) {
+        self.value = None;
+        self.generation += 1;
+    }
+
+    fn set_ttl(&mut self, ttl_ms: Option<u32>) {
+        self.ttl = ttl_ms
+            .filter(|ms| *ms > 0)
+            .map(|ms| Duration::from_millis(ms.into()));
+        self.clear();
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Cache {
+    lists: Entry<Vec<List>>,
+    recipes: Entry<Vec<Recipe>>,
+}
+
+impl Cache {
+    pub fn configure(&mut self, options: &CacheOptions) {
+        self.lists.set_ttl(options.lists_ttl_ms);
+        self.recipes.set_ttl(options.recipes_ttl_ms);
+    }
+
+    /// Set how often each type is refreshed in the background, or `None`
+    /// for types that aren't
+    pub fn set_refresh(&mut self, lists: Option<Duration>, recipes: Option<Duration>) {
+        self.lists.refresh = lists;
+        self.recipes.refresh = recipes;
+    }
+
+    /// Where an entity type's entry is up to, for fetches to pass back to
+    /// `put_lists` or `put_recipes`
+    pub fn generation(&self, entity: CacheEntity) -> u64 {
+        match entity {
+            CacheEntity::Lists => self.lists.generation,
+            CacheEntity::Recipes => self.recipes.generation,
+        }
+    }
+
+    pub fn lists(&self) -> Option<Vec<List>> {
+        self.lists.get()
+    }
+
+    /// Store lists fetched when the entry was at `generation`, unless it's
+    /// been dropped since
+    pub fn put_lists(&mut self, generation: u64, lists: &[List]) {
+        self.lists.put(generation, || lists.to_vec());
+    }
+
+    pub fn recipes(&self) -> Option<Vec<Recipe>> {
+        self.recipes.get()
+    }
+
+    /// Store recipes fetched when the entry was at `generation`, unless it's
+    /// been dropped since
+    pub fn put_recipes(&mut self, generation: u64, recipes: &[Recipe]) {
+        self.recipes.put(generation, || recipes.to_vec());
+    }
+
+    /// Drop one entity type's cached values, or all of them
+    pub fn invalidate(&mut self, entity: Option<CacheEntity>) {
+        if entity != Some(CacheEntity::Recipes) {
+            self.lists.clear();
+        }
+        if entity != Some(CacheEntity::Lists) {
+            self.recipes.clear();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Changes between two reads of the user's shopping lists.
+//!
+//! Lists and items are matched by ID, so a renamed list or item is told apart
+//! from one that was replaced. Lists that appeared or disappeared in between
+//! aren't reported.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{ChangeEvent, ChangeEventType, List, ListChanges, ListItem};
+
+fn item_event(kind: ChangeEventType, list: &List, item: &ListItem) -> ChangeEvent {
+    ChangeEvent {
+        kind,
+        list_id: list.id.clone(),
+        list_name: list.name.clone(),
+        item: Some(item.clone()),
+        old_name: None,
+    }
+}
+
+/// The changes that turn `before` into `after`, in the order of `after`
+pub(crate) fn diff_lists(before: &[List], after: &[List]) -> Vec<ChangeEvent> {
+    let before: HashMap<&str, &List> = before.iter().map(|l| (l.id.as_str(), l)).collect();
+    let mut events = Vec::new();
+
+    for list in after {
+        let Some(old) = before.get(list.id.as_str()) else {
+            continue;
+        };
+        if old.name != list.name {
+            events.push(ChangeEvent {
+                kind: ChangeEventType::ListRenamed,
+                list_id: list.id.clone(),
+                list_name: list.name.clone(),
+                item: None,
+                old_name: Some(old.name.clone()),
+            });
+        }
+
+        let old_items: HashMap<&str, &ListItem> =
+            old.items.iter().map(|i| (i.id.as_str(), i)).collect();
+        for item in &list.items {
+            match old_items.get(item.id.as_str()) {
+                None => events.push(item_event(ChangeEventType::ItemAdded, list, item)),
+                Some(old_item) if old_item.checked != item.checked => {
+                    events.push(item_event(ChangeEventType::ItemChecked, list, item))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    events
+}
+
+/// Everything that changed in a list between two reads, or `None` if nothing
+/// did
+pub(crate) fn diff_list(before: &List, after: &List) -> Option<ListChanges> {
+    let old_items: HashMap<&str, &ListItem> =
+        before.items.iter().map(|i| (i.id.as_str(), i)).collect();
+    let new_ids: HashSet<&str> = after.items.iter().map(|i| i.id.as_str()).collect();
+
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for item in &after.items {
+        match old_items.get(item.id.as_str()) {
+            None => added.push(item.clone()),
+            Some(old) if *old != item => updated.push(item.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed: Vec<ListItem> = before
+        .items
+        .iter()
+        .filter(|i| !new_ids.contains(i.id.as_str()))
+        .cloned()
+        .collect();
+    let old_name = Some(before.name.clone()).filter(|name| *name != after.name);
+
+    if old_name.is_none() && added.is_empty() && removed.is_empty() && updated.is_empty() {
+        return None;
+    }
+    Some(ListChanges {
+        list_id: after.id.clone(),
+        name: after.name.clone(),
+        old_name,
+        added,
+        removed,
+        updated,
+    })
+}
@@ -0,0 +1,55 @@
+//! Sharing one fetch between callers that ask for the same thing at once.
+//!
+//! While a fetch for a key is in flight, later callers wait for it and get a
+//! copy of its result instead of starting their own. Once it finishes, the
+//! next caller starts a new one, so results are never reused afterwards;
+//! keeping them is the cache's job.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use napi::{Error, Result};
+use tokio::sync::OnceCell;
+
+type Fetch<T> = Arc<OnceCell<Result<T>>>;
+
+pub(crate) struct Coalesced<T> {
+    in_flight: Mutex<HashMap<String, Fetch<T>>>,
+}
+
+impl<T> Default for Coalesced<T> {
+    fn default() -> Self {
+        Coalesced {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> Coalesced<T> {
+    /// Run `fetch`, or wait for the one already running for `key`
+    pub async fn get<F>(&self, key: &str, fetch: impl FnOnce() -> F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let cell = Arc::clone(
+            self.in_flight
+                .lock()
+                .unwrap()
+                .entry(key.to_string())
+                .or_default(),
+        );
+        // If the caller running the fetch gives up on it, a waiting caller
+        // runs it instead
+        let result = cell.get_or_init(fetch).await;
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(key).is_some_and(|c| Arc::ptr_eq(c, &cell)) {
+            in_flight.remove(key);
+        }
+        match result {
+            Ok(value) => Ok(value.clone()),
+            Err(e) => Err(Error::new(e.status, e.reason.clone())),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Cursors for `getChangesSince()`.
+//!
+//! A cursor records the version of every list, recipe, and meal plan event the
+//! caller has seen: AnyList's modification time for lists and recipes, and the
+//! logical timestamp for meal plan events, which have no modification time.
+//! Comparing those with the account's current versions finds what was
+//! created, changed, or deleted since. Cursors are JSON, base64 encoded so
+//! callers can treat them as opaque strings.
+
+use std::collections::HashMap;
+
+use anylist_rs::protobuf::anylist::PbUserDataResponse;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use napi::{Error, Result, Status};
+use serde::{Deserialize, Serialize};
+
+use crate::{AccountChanges, List, MealPlanEvent, Recipe};
+
+/// The versions a caller has seen
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct Cursor {
+    lists: HashMap<String, f64>,
+    recipes: HashMap<String, f64>,
+    events: HashMap<String, u64>,
+}
+
+impl Cursor {
+    pub fn decode(cursor: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor.trim())
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or_else(|| Error::new(Status::InvalidArg, "Invalid sync cursor"))
+    }
+
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+}
+
+/// Where to start looking for changes
+pub(crate) enum Since {
+    /// Everything
+    Start,
+    /// Milliseconds since the Unix epoch
+    Timestamp(f64),
+    Cursor(Cursor),
+}
+
+/// IDs seen before that are gone now
+fn deleted<V>(before: &HashMap<String, V>, now: &HashMap<String, V>) -> Vec<String> {
+    let mut ids: Vec<String> = before
+        .keys()
+        .filter(|id| !now.contains_key(*id))
+        .cloned()
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// What changed in the account since `since`
+pub(crate) fn changes_since(data: &PbUserDataResponse, since: Since) -> AccountChanges {
+    let (before, after) = match since {
+        Since::Start => (None, None),
+        // Seconds, as AnyList timestamps are
+        Since::Timestamp(timestamp) => (None, Some(timestamp / 1000.0)),
+        Since::Cursor(cursor) => (Some(cursor), None),
+    };
+
+    let lists: Vec<_> = data
+        .shopping_lists_response
+        .iter()
+        .flat_map(|r| r.new_lists.iter().chain(&r.modified_lists))
+        .collect();
+    let recipes: Vec<_> = data
+        .recipe_data_response
+        .iter()
+        .flat_map(|r| &r.recipes)
+        .collect();
+    let events: Vec<_> = data
+        .meal_planning_calendar_response
+        .iter()
+        .flat_map(|c| &c.events)
+        .collect();
+
+    let now = Cursor {
+        lists: lists
+            .iter()
+            .map(|l| (l.identifier.clone(), l.timestamp.unwrap_or_default()))
+            .collect(),
+        recipes: recipes
+            .iter()
+            .map(|r| (r.identifier.clone(), r.timestamp.unwrap_or_default()))
+            .collect(),
+        events: events
+            .iter()
+            .map(|e| {
+                (
+                    e.identifier.clone(),
+                    e.logical_timestamp.unwrap_or_default(),
+                )
+            })
+            .collect(),
+    };
+
+    let changed = |seen: Option<f64>, version: f64| match (&before, after) {
+        (Some(_), _) => seen != Some(version),
+        (None, Some(after)) => version > after,
+        (None, None) => true,
+    };
+    let empty = Cursor::default();
+    let seen = before.as_ref().unwrap_or(&empty);
+
+    AccountChanges {
+        lists: lists
+            .iter()
+            .filter(|l| {
+                changed(
+                    seen.lists.get(&l.identifier).copied(),
+                    l.timestamp.unwrap_or_default(),
+                )
+            })
+            .map(|l| List::from(*l))
+            .collect(),
+        recipes: recipes
+            .iter()
+            .filter(|r| {
+                changed(
+                    seen.recipes.get(&r.identifier).copied(),
+                    r.timestamp.unwrap_or_default(),
+                )
+            })
+            .map(|r| Recipe::from(*r))
+            .collect(),
+        meal_plan_events: events
+            .iter()
+            // A timestamp can't tell whether a meal plan event changed, so
+            // every event is returned for one
+            .filter(|e| {
+                before.is_none() || seen.events.get(&e.identifier) != now.events.get(&e.identifier)
+            })
+            .map(|e| MealPlanEvent::from(*e))
+            .collect(),
+        deleted_list_ids: deleted(&seen.lists, &now.lists),
+        deleted_recipe_ids: deleted(&seen.recipes, &now.recipes),
+        deleted_meal_plan_event_ids: deleted(&seen.events, &now.events),
+        cursor: now.encode(),
+    }
+}
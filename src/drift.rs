@@ -0,0 +1,82 @@
+//! Checking that a batch left its lists the way it expected.
+//!
+//! AnyList answers a batch of list operations with the logical timestamps
+//! each list had before and after it, the operations it applied, and the
+//! lists it wants fetched again in full. Comparing those with what the batch
+//! read before sending catches lists that have drifted from the client's
+//! view of them, rather than leaving it to surface later as duplicates.
+
+use std::collections::HashMap;
+
+use anylist_rs::protobuf::anylist::{PbEditOperationResponse, PbListOperation};
+use prost::Message;
+
+use crate::{DriftEvent, DriftReason};
+
+/// What AnyList's response to a batch says went differently than expected,
+/// given each list's logical timestamp when the batch read it
+pub(crate) fn detect(
+    response: &[u8],
+    operations: &[PbListOperation],
+    read_at: &HashMap<String, Option<u64>>,
+) -> Vec<DriftEvent> {
+    // Nothing to compare against if AnyList didn't say
+    let Ok(response) = PbEditOperationResponse::decode(response) else {
+        return Vec::new();
+    };
+
+    let mut events = Vec::new();
+    for (list_id, expected) in read_at {
+        let on_list: Vec<&PbListOperation> = operations
+            .iter()
+            .filter(|o| o.list_id.as_ref() == Some(list_id))
+            .collect();
+        let item_ids = |operations: &[&PbListOperation]| {
+            let mut ids: Vec<String> = Vec::new();
+            for id in operations.iter().filter_map(|o| o.list_item_id.as_ref()) {
+                if !ids.contains(id) {
+                    ids.push(id.clone());
+                }
+            }
+            ids
+        };
+        let event = |reason, item_ids| DriftEvent {
+            list_id: list_id.clone(),
+            reason,
+            item_ids,
+        };
+
+        let original = response
+            .original_logical_timestamps
+            .iter()
+            .find(|t| t.identifier.as_ref() == Some(list_id))
+            .and_then(|t| t.logical_timestamp);
+        if let (Some(expected), Some(original)) = (expected, original) {
+            if *expected != original {
+                events.push(event(DriftReason::ListChanged, item_ids(&on_list)));
+            }
+        }
+
+        if !response.processed_operations.is_empty() {
+            let skipped: Vec<&PbListOperation> = on_list
+                .iter()
+                .copied()
+                .filter(|o| {
+                    o.metadata
+                        .as_ref()
+                        .and_then(|m| m.operation_id.as_ref())
+                        .is_some_and(|id| !response.processed_operations.contains(id))
+                })
+                .collect();
+            if !skipped.is_empty() {
+                events.push(event(DriftReason::ChangesSkipped, item_ids(&skipped)));
+            }
+        }
+
+        if response.full_refresh_timestamp_ids.contains(list_id) {
+            events.push(event(DriftReason::RefreshRequired, Vec::new()));
+        }
+    }
+    events.sort_by(|a, b| a.list_id.cmp(&b.list_id));
+    events
+}
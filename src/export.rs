@@ -0,0 +1,416 @@
+//! Rendering recipes and meal plans for use outside AnyList.
+
+use chrono::NaiveDate;
+use napi::{Error, Result, Status};
+use serde_json::Value;
+
+use crate::{Ingredient, List, Recipe, RecipeHtmlTemplate};
+
+/// Format a number of minutes like "1 hr 15 min"
+pub(crate) fn format_minutes(minutes: i32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} hr", h),
+        (h, m) => format!("{} hr {} min", h, m),
+    }
+}
+
+/// Format an ingredient as a single line, e.g. "2 cups flour, sifted"
+pub(crate) fn ingredient_line(ingredient: &Ingredient) -> String {
+    if ingredient.is_header == Some(true) {
+        return ingredient.name.clone();
+    }
+
+    let mut line = match &ingredient.quantity {
+        Some(quantity) => format!("{} {}", quantity, ingredient.name),
+        None => ingredient.name.clone(),
+    };
+    if let Some(note) = &ingredient.note {
+        line.push_str(", ");
+        line.push_str(note);
+    }
+    line
+}
+
+/// Render a recipe as a Markdown document
+pub(crate) fn to_markdown(recipe: &Recipe, photo_urls: &[String]) -> String {
+    let mut out = format!("# {}\n", recipe.name);
+
+    if let Some(url) = photo_urls.first() {
+        out.push_str(&format!("\n![{}]({})\n", recipe.name, url));
+    }
+
+    if let Some(note) = &recipe.note {
+        out.push_str(&format!("\n{}\n", note));
+    }
+
+    let mut metadata = Vec::new();
+    if let Some(servings) = &recipe.servings {
+        metadata.push(format!("**Servings:** {}", servings));
+    }
+    if let Some(prep_time) = recipe.prep_time {
+        metadata.push(format!("**Prep time:** {}", format_minutes(prep_time)));
+    }
+    if let Some(cook_time) = recipe.cook_time {
+        metadata.push(format!("**Cook time:** {}", format_minutes(cook_time)));
+    }
+    if let Some(rating) = recipe.rating {
+        metadata.push(format!("**Rating:** {}/5", rating));
+    }
+    match (&recipe.source_name, &recipe.source_url) {
+        (Some(name), Some(url)) => metadata.push(format!("**Source:** [{}]({})", name, url)),
+        (Some(name), None) => metadata.push(format!("**Source:** {}", name)),
+        (None, Some(url)) => metadata.push(format!("**Source:** <{}>", url)),
+        (None, None) => {}
+    }
+    if !metadata.is_empty() {
+        out.push('\n');
+        for line in metadata {
+            out.push_str(&format!("- {}\n", line));
+        }
+    }
+
+    if !recipe.ingredients.is_empty() {
+        out.push_str("\n## Ingredients\n\n");
+        for ingredient in &recipe.ingredients {
+            if ingredient.is_header == Some(true) {
+                out.push_str(&format!("\n**{}**\n\n", ingredient.name));
+            } else {
+                out.push_str(&format!("- {}\n", ingredient_line(ingredient)));
+            }
+        }
+    }
+
+    if !recipe.steps.is_empty() {
+        out.push_str("\n## Instructions\n\n");
+        let mut number = 0;
+        for step in &recipe.steps {
+            if step.is_header {
+                out.push_str(&format!("\n**{}**\n\n", step.text));
+            } else {
+                number += 1;
+                out.push_str(&format!("{}. {}\n", number, step.text));
+            }
+        }
+    }
+
+    if let Some(nutrition) = &recipe.nutritional_info {
+        out.push_str(&format!("\n## Nutrition\n\n{}\n", nutrition));
+    }
+
+    out
+}
+
+fn json_error(err: serde_json::Error) -> Error {
+    Error::new(Status::GenericFailure, err.to_string())
+}
+
+/// The canonical JSON form of a recipe, with sorted keys
+pub(crate) fn to_json_value(recipe: &Recipe, photo_urls: &[String]) -> Result<Value> {
+    let mut json = serde_json::to_value(recipe).map_err(json_error)?;
+    if let Value::Object(fields) = &mut json {
+        fields.insert("photoUrls".to_string(), Value::from(photo_urls.to_vec()));
+    }
+
+    Ok(json)
+}
+
+/// Render a recipe as pretty-printed JSON with sorted keys
+pub(crate) fn to_json(recipe: &Recipe, photo_urls: &[String]) -> Result<String> {
+    serde_json::to_string_pretty(&to_json_value(recipe, photo_urls)?).map_err(json_error)
+}
+
+/// Escape text for use in HTML
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Styles for rendered HTML, sized for a screen and tidy when printed
+const HTML_STYLE: &str = "\
+body { font-family: Georgia, serif; line-height: 1.5; max-width: 42rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+h1 { margin-bottom: 0.25rem; }
+h2 { font-size: 1.2rem; border-bottom: 1px solid #ccc; padding-bottom: 0.2rem; }
+h3 { font-size: 1rem; margin: 0.75rem 0 0.25rem; }
+img { max-width: 100%; border-radius: 4px; }
+.meta { list-style: none; padding: 0; display: flex; flex-wrap: wrap; gap: 0.25rem 1.5rem; color: #555; }
+.note { font-style: italic; white-space: pre-line; }
+li.header { list-style: none; margin: 0.5rem 0 0 -1.25rem; font-weight: bold; }
+.compact { font-size: 0.9rem; margin: 1rem auto; }
+.compact h2 { margin: 0.75rem 0 0.25rem; }
+@media print { body { margin: 0; max-width: none; } a { color: inherit; text-decoration: none; } img { max-height: 3in; } h2 { break-after: avoid; } li { break-inside: avoid; } }
+";
+
+/// Render a recipe as a standalone, printable HTML document
+pub(crate) fn to_html(
+    recipe: &Recipe,
+    photo: Option<&EmbeddedPhoto>,
+    template: &RecipeHtmlTemplate,
+) -> String {
+    use base64::Engine;
+
+    let full = matches!(template, RecipeHtmlTemplate::Full);
+    let name = escape_html(&recipe.name);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n{}</style>\n</head>\n<body{}>\n<h1>{}</h1>\n",
+        name,
+        HTML_STYLE,
+        if full { "" } else { " class=\"compact\"" },
+        name
+    );
+
+    if let (true, Some(photo)) = (full, photo) {
+        out.push_str(&format!(
+            "<img src=\"data:{};base64,{}\" alt=\"{}\">\n",
+            escape_html(&photo.content_type),
+            base64::engine::general_purpose::STANDARD.encode(&photo.data),
+            name
+        ));
+    }
+
+    if let (true, Some(note)) = (full, &recipe.note) {
+        out.push_str(&format!("<p class=\"note\">{}</p>\n", escape_html(note)));
+    }
+
+    let mut metadata = Vec::new();
+    if let Some(servings) = &recipe.servings {
+        metadata.push(format!(
+            "<strong>Servings:</strong> {}",
+            escape_html(servings)
+        ));
+    }
+    if let Some(prep_time) = recipe.prep_time {
+        metadata.push(format!(
+            "<strong>Prep time:</strong> {}",
+            format_minutes(prep_time)
+        ));
+    }
+    if let Some(cook_time) = recipe.cook_time {
+        metadata.push(format!(
+            "<strong>Cook time:</strong> {}",
+            format_minutes(cook_time)
+        ));
+    }
+    if let (true, Some(rating)) = (full, recipe.rating) {
+        metadata.push(format!("<strong>Rating:</strong> {}/5", rating));
+    }
+    if full {
+        match (&recipe.source_name, &recipe.source_url) {
+            (name, Some(url)) => metadata.push(format!(
+                "<strong>Source:</strong> <a href=\"{}\">{}</a>",
+                escape_html(url),
+                escape_html(name.as_deref().unwrap_or(url))
+            )),
+            (Some(name), None) => {
+                metadata.push(format!("<strong>Source:</strong> {}", escape_html(name)))
+            }
+            (None, None) => {}
+        }
+    }
+    if !metadata.is_empty() {
+        out.push_str("<ul class=\"meta\">\n");
+        for item in metadata {
+            out.push_str(&format!("<li>{}</li>\n", item));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    if !recipe.ingredients.is_empty() {
+        out.push_str("<h2>Ingredients</h2>\n<ul>\n");
+        for ingredient in &recipe.ingredients {
+            let class = if ingredient.is_header == Some(true) {
+                " class=\"header\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<li{}>{}</li>\n",
+                class,
+                escape_html(&ingredient_line(ingredient))
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    if !recipe.steps.is_empty() {
+        out.push_str("<h2>Instructions</h2>\n");
+        // Headers split the steps into several lists that keep counting on
+        let mut number = 0;
+        let mut in_list = false;
+        for step in &recipe.steps {
+            if step.is_header {
+                if in_list {
+                    out.push_str("</ol>\n");
+                    in_list = false;
+                }
+                out.push_str(&format!("<h3>{}</h3>\n", escape_html(&step.text)));
+                continue;
+            }
+            if !in_list {
+                out.push_str(&format!("<ol start=\"{}\">\n", number + 1));
+                in_list = true;
+            }
+            number += 1;
+            out.push_str(&format!("<li>{}</li>\n", escape_html(&step.text)));
+        }
+        if in_list {
+            out.push_str("</ol>\n");
+        }
+    }
+
+    if let (true, Some(nutrition)) = (full, &recipe.nutritional_info) {
+        out.push_str(&format!(
+            "<h2>Nutrition</h2>\n<p class=\"note\">{}</p>\n",
+            escape_html(nutrition)
+        ));
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// A meal in a meal plan digest
+pub(crate) struct DigestMeal {
+    pub label: Option<String>,
+    pub title: String,
+    pub url: Option<String>,
+    pub prep_time: Option<i32>,
+}
+
+/// A day in a meal plan digest, with its meals in planned order
+pub(crate) struct DigestDay {
+    pub date: NaiveDate,
+    pub meals: Vec<DigestMeal>,
+}
+
+fn digest_day_name(date: NaiveDate) -> String {
+    date.format("%A, %B %-d").to_string()
+}
+
+/// Render a meal plan digest as Markdown, one section per day
+pub(crate) fn digest_to_markdown(days: &[DigestDay]) -> String {
+    let mut out = String::new();
+    for day in days {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("## {}\n\n", digest_day_name(day.date)));
+        if day.meals.is_empty() {
+            out.push_str("_Nothing planned_\n");
+        }
+        for meal in &day.meals {
+            let mut line = String::from("- ");
+            if let Some(label) = &meal.label {
+                line.push_str(&format!("**{}:** ", label));
+            }
+            match &meal.url {
+                Some(url) => line.push_str(&format!("[{}]({})", meal.title, url)),
+                None => line.push_str(&meal.title),
+            }
+            if let Some(minutes) = meal.prep_time {
+                line.push_str(&format!(" (prep {})", format_minutes(minutes)));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Render a meal plan digest as an HTML fragment, ready to embed in a message
+pub(crate) fn digest_to_html(days: &[DigestDay]) -> String {
+    let mut out = String::new();
+    for day in days {
+        out.push_str(&format!("<h2>{}</h2>\n", digest_day_name(day.date)));
+        if day.meals.is_empty() {
+            out.push_str("<p><em>Nothing planned</em></p>\n");
+            continue;
+        }
+        out.push_str("<ul>\n");
+        for meal in &day.meals {
+            let mut item = String::new();
+            if let Some(label) = &meal.label {
+                item.push_str(&format!("<strong>{}:</strong> ", escape_html(label)));
+            }
+            match &meal.url {
+                Some(url) => item.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(url),
+                    escape_html(&meal.title)
+                )),
+                None => item.push_str(&escape_html(&meal.title)),
+            }
+            if let Some(minutes) = meal.prep_time {
+                item.push_str(&format!(" (prep {})", format_minutes(minutes)));
+            }
+            out.push_str(&format!("<li>{}</li>\n", item));
+        }
+        out.push_str("</ul>\n");
+    }
+    out
+}
+
+/// A recipe's photo embedded in a backup
+pub(crate) struct EmbeddedPhoto {
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Version of the backup format written by `to_backup` and `to_snapshot`
+pub(crate) const BACKUP_VERSION: u32 = 1;
+
+/// Recipes as backed up, with photos embedded as base64
+fn backup_recipes(recipes: Vec<(Value, Option<EmbeddedPhoto>)>) -> Vec<Value> {
+    use base64::Engine;
+
+    recipes
+        .into_iter()
+        .map(|(mut json, photo)| {
+            if let (Value::Object(fields), Some(photo)) = (&mut json, photo) {
+                fields.insert(
+                    "photo".to_string(),
+                    serde_json::json!({
+                        "contentType": photo.content_type,
+                        "data": base64::engine::general_purpose::STANDARD.encode(photo.data),
+                    }),
+                );
+            }
+            json
+        })
+        .collect()
+}
+
+/// Render recipes as a single JSON backup, embedding photos as base64
+pub(crate) fn to_backup(recipes: Vec<(Value, Option<EmbeddedPhoto>)>) -> Result<String> {
+    serde_json::to_string(&serde_json::json!({
+        "version": BACKUP_VERSION,
+        "recipes": backup_recipes(recipes),
+    }))
+    .map_err(json_error)
+}
+
+/// Render lists and recipes as a single JSON snapshot: a recipe backup with
+/// the lists added
+pub(crate) fn to_snapshot(
+    lists: &[List],
+    recipes: Vec<(Value, Option<EmbeddedPhoto>)>,
+) -> Result<String> {
+    serde_json::to_string(&serde_json::json!({
+        "version": BACKUP_VERSION,
+        "lists": lists,
+        "recipes": backup_recipes(recipes),
+    }))
+    .map_err(json_error)
+}
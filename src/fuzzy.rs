@@ -0,0 +1,75 @@
+//! Fuzzy matching of ingredient names.
+//!
+//! Names are compared word by word after lowercasing and trimming plural
+//! endings, so "chicken thigh" finds "Boneless chicken thighs". Longer words
+//! also tolerate a single typo ("parmesan" and "parmasan").
+
+/// Words in a name, lowercased with plural endings removed
+pub(crate) fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| stem(&word.to_lowercase()))
+        .collect()
+}
+
+fn stem(word: &str) -> String {
+    if word.len() > 4 {
+        if let Some(stem) = word.strip_suffix("ies") {
+            return format!("{}y", stem);
+        }
+        for suffix in ["oes", "ches", "shes", "sses", "xes"] {
+            if word.ends_with(suffix) {
+                return word[..word.len() - 2].to_string();
+            }
+        }
+    }
+    if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+/// Whether two words differ by at most one insertion, deletion, or substitution
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    if short == long {
+        return true;
+    }
+
+    // Skip the first differing character (in both words if they're the same length)
+    let prefix = short.iter().zip(&long).take_while(|(x, y)| x == y).count();
+    let short_rest = if short.len() == long.len() {
+        prefix + 1
+    } else {
+        prefix
+    };
+    short[short_rest..] == long[prefix + 1..]
+}
+
+/// How well a name matches the query words: 2 if every word appears as is,
+/// 1 if some only match with a typo, or `None` if any word is missing
+pub(crate) fn match_score(query: &[String], name: &str) -> Option<u8> {
+    let name_words = words(name);
+    let mut score = 2;
+
+    for word in query {
+        if name_words.contains(word) {
+            continue;
+        }
+        if word.chars().count() >= 5
+            && name_words
+                .iter()
+                .any(|candidate| within_one_edit(word, candidate))
+        {
+            score = 1;
+            continue;
+        }
+        return None;
+    }
+
+    Some(score)
+}
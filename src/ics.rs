@@ -0,0 +1,169 @@
+//! Building and reading iCalendar (RFC 5545) documents.
+//!
+//! Meal plan events have a date but no time, so each becomes an all-day
+//! event. Lines use CRLF endings and are folded at 75 bytes as the standard
+//! requires. Reading handles the subset AnyList's hosted feed uses: VEVENTs
+//! with text properties and date or date-time values.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::ICalendarEvent;
+
+/// A meal plan event ready to be written as a VEVENT
+pub(crate) struct IcsEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Escape text for a property value
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Undo `escape`
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Format a DATE ("20240115") as "2024-01-15" and a DATE-TIME
+/// ("20240115T183000Z") as "2024-01-15T18:30:00Z"
+fn format_date(value: &str) -> String {
+    let digits = |range: std::ops::Range<usize>| value.get(range).unwrap_or_default();
+    if value.len() == 8 {
+        return format!("{}-{}-{}", digits(0..4), digits(4..6), digits(6..8));
+    }
+    if value.len() >= 15 && value.as_bytes()[8] == b'T' {
+        return format!(
+            "{}-{}-{}T{}:{}:{}{}",
+            digits(0..4),
+            digits(4..6),
+            digits(6..8),
+            digits(9..11),
+            digits(11..13),
+            digits(13..15),
+            digits(15..value.len()),
+        );
+    }
+    value.to_string()
+}
+
+/// Read the events in an iCalendar document
+pub(crate) fn parse_events(text: &str) -> Vec<ICalendarEvent> {
+    // Folded lines continue with a leading space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<ICalendarEvent> = None;
+    for line in &lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters like ";VALUE=DATE" don't change how values are read here
+        let name = name.split(';').next().unwrap_or_default().to_uppercase();
+
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(ICalendarEvent::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                events.extend(current.take());
+            }
+            ("UID", Some(event)) => event.uid = unescape(value),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("DESCRIPTION", Some(event)) => event.description = Some(unescape(value)),
+            ("URL", Some(event)) => event.url = Some(value.to_string()),
+            ("DTSTART", Some(event)) => event.start = format_date(value),
+            ("DTEND", Some(event)) => event.end = Some(format_date(value)),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Add a content line, folding it so no line is longer than 75 bytes
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards their length
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Render events as a VCALENDAR document
+pub(crate) fn to_ics(events: &[IcsEvent], stamp: DateTime<Utc>) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//anylist-napi//Meal Plan//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "X-WR-CALNAME:Meal Plan");
+
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape(&event.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+        );
+        if let Some(end) = event.date.succ_opt() {
+            push_line(
+                &mut out,
+                &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            );
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(description)));
+        }
+        if let Some(category) = &event.category {
+            push_line(&mut out, &format!("CATEGORIES:{}", escape(category)));
+        }
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
@@ -0,0 +1,141 @@
+//! An append-only record of the changes made through the client.
+//!
+//! Each change is written to the journal file as a line of JSON once it
+//! finishes, whether or not it succeeded. Lines are only ever appended, so
+//! several processes sharing an account can share one file, and nothing is
+//! rewritten if one of them exits part way through.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use napi::{Error, Result, Status};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{status, transport, JournalEntry};
+
+fn file_error(action: &str, path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::new(
+        Status::GenericFailure,
+        format!("Couldn't {} {}: {}", action, path.display(), message),
+    )
+}
+
+/// The IDs of the entities a change returned, for changes that create them
+pub(crate) fn returned_ids(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(id) => vec![id.clone()],
+        Value::Object(fields) => fields
+            .get("id")
+            .and_then(Value::as_str)
+            .map(|id| vec![id.to_string()])
+            .unwrap_or_default(),
+        Value::Array(values) => values.iter().flat_map(returned_ids).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Journal {
+    path: Option<PathBuf>,
+}
+
+impl Journal {
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Append to a file, creating it if it doesn't exist
+    pub fn use_file(&mut self, path: PathBuf) -> Result<()> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| file_error("open the journal", &path, e))?;
+        self.path = Some(path);
+        Ok(())
+    }
+
+    pub fn disable(&mut self) {
+        self.path = None;
+    }
+
+    pub fn append(&self, entry: &JournalEntry) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut line =
+            serde_json::to_vec(entry).map_err(|e| file_error("write to the journal", path, e))?;
+        line.push(b'\n');
+        // One write per entry, so entries appended by other processes at the
+        // same time don't interleave
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|e| file_error("write to the journal", path, e))
+    }
+
+    /// Entries that finished at or after `since`, oldest first
+    pub fn entries(&self, since: Option<f64>) -> Result<Vec<JournalEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(file_error("read the journal", path, e)),
+        };
+
+        let mut entries: Vec<JournalEntry> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            // A line being written by another process may be incomplete
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter(|entry: &JournalEntry| since.is_none_or(|since| entry.finished_at >= since))
+            .collect();
+        entries.sort_by(|a, b| a.finished_at.total_cmp(&b.finished_at));
+        Ok(entries)
+    }
+}
+
+/// Make a change, recording it in the journal as entry `id` once it
+/// finishes, and noting it for `getSyncStatus()` if it fails
+pub(crate) async fn journaled<T: Serialize>(
+    journal: &Mutex<Journal>,
+    sync_state: &Mutex<status::SyncState>,
+    id: String,
+    operation: &str,
+    mut entity_ids: Vec<String>,
+    change: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    let journal_enabled = journal.lock().unwrap().is_enabled();
+    let started_at = transport::current_timestamp() * 1000.0;
+    let result = change.await;
+    if let Err(e) = &result {
+        sync_state.lock().unwrap().failed(operation, e);
+    }
+    if !journal_enabled {
+        return result;
+    }
+    if let Ok(value) = result.as_ref().map(serde_json::to_value) {
+        entity_ids.extend(value.iter().flat_map(returned_ids));
+    }
+    let entry = JournalEntry {
+        id,
+        operation: operation.to_string(),
+        entity_ids,
+        started_at,
+        finished_at: transport::current_timestamp() * 1000.0,
+        succeeded: result.is_ok(),
+        error: result.as_ref().err().map(|e| e.reason.clone()),
+        process_id: std::process::id(),
+    };
+    // The change has been made (or not) either way, so it's still reported
+    // as it went if it couldn't be recorded
+    let _ = journal.lock().unwrap().append(&entry);
+    result
+}
@@ -1,28 +1,113 @@
 #![deny(clippy::all)]
 
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
 use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+    ThreadsafeFunction, ThreadsafeFunctionCallMode, UnknownReturnValue,
+};
 use napi_derive::napi;
+use prost::Message;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+mod cache;
+mod changes;
+mod coalesce;
+mod cursor;
+mod drift;
+mod export;
+mod fuzzy;
+mod ics;
+mod journal;
+mod list_locks;
+mod mealie;
+mod nutrition;
+mod operations;
+mod optimistic;
+mod paprika;
+mod quantity;
+mod queue;
+mod refresh;
+mod rules;
+mod schema;
+mod schema_org;
+mod snapshot;
+mod status;
+mod timers;
+mod transport;
+mod webhook;
+
+pub use schema::get_schemas;
 
 // Re-export anylist_rs types for internal use
+use anylist_rs::operations::{
+    build_bulk_remove_items_operation, BulkRemoveItemsParams, ItemToRemove,
+};
+use anylist_rs::protobuf::anylist::{
+    PbCalendarEvent, PbCalendarOperationList, PbIngredient, PbListCategory, PbListCategoryGroup,
+    PbListItem, PbListOperation, PbListOperationList, PbListResponse, PbListSettings,
+    PbListSettingsOperation, PbListSettingsOperationList, PbRecipe, PbRecipeOperationList,
+    PbShoppingList, PbStarterList, PbStarterListOperationList, PbUserDataResponse,
+};
+use anylist_rs::realtime::RealtimeSync;
 use anylist_rs::{
-    AnyListClient as RsClient, Category as RsCategory, CategoryGroup as RsCategoryGroup,
-    FavouriteItem as RsFavouriteItem, FavouritesList as RsFavouritesList,
+    AnyListClient as RsClient, AnyListError, Category as RsCategory,
+    CategoryGroup as RsCategoryGroup, FavouriteItem as RsFavouriteItem,
     ICalendarInfo as RsICalendarInfo, Ingredient as RsIngredient, List as RsList,
-    ListItem as RsListItem, MealPlanEvent as RsMealPlanEvent, Recipe as RsRecipe, RecipeBuilder,
+    ListItem as RsListItem, MealPlanEvent as RsMealPlanEvent, RecipeBuilder,
     RecipeCollection as RsRecipeCollection, SavedTokens as RsSavedTokens, Store as RsStore,
-    StoreFilter as RsStoreFilter,
+    StoreFilter as RsStoreFilter, SyncEvent,
 };
 
 /// Input for creating a new ingredient
 #[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct IngredientInput {
     pub name: String,
     pub quantity: Option<String>,
     pub note: Option<String>,
+    /// Start a new section, e.g. "For the sauce" (quantity and note are ignored)
+    pub is_header: Option<bool>,
+}
+
+/// AnyList marks section headers in ingredients and steps with a leading "#"
+fn header_name(name: &str) -> Option<&str> {
+    name.trim_start()
+        .strip_prefix('#')
+        .map(|name| name.trim_start_matches('#').trim())
+}
+
+impl From<&IngredientInput> for PbIngredient {
+    fn from(input: &IngredientInput) -> Self {
+        if input.is_header == Some(true) {
+            let name = header_name(&input.name).unwrap_or(input.name.trim());
+            return PbIngredient {
+                name: Some(format!("# {}", name)),
+                ..Default::default()
+            };
+        }
+
+        PbIngredient {
+            name: Some(input.name.clone()),
+            quantity: input.quantity.clone(),
+            note: input.note.clone(),
+            ..Default::default()
+        }
+    }
 }
 
 impl From<&IngredientInput> for RsIngredient {
     fn from(input: &IngredientInput) -> Self {
+        if input.is_header == Some(true) {
+            let name = header_name(&input.name).unwrap_or(input.name.trim());
+            return RsIngredient::new(format!("# {}", name));
+        }
+
         let mut ingredient = RsIngredient::new(&input.name);
         if let Some(ref qty) = input.quantity {
             ingredient = ingredient.quantity_of(qty);
@@ -39,8 +124,220 @@ fn to_napi_error(err: anylist_rs::AnyListError) -> Error {
     Error::new(Status::GenericFailure, format!("{}", err))
 }
 
+/// The error anylist_rs reports when a deletion matches no items
+fn no_matching_items() -> Error {
+    to_napi_error(AnyListError::NotFound(
+        "No matching items found".to_string(),
+    ))
+}
+
+/// Check a scale factor is a positive number
+fn check_scale_factor(factor: f64) -> Result<()> {
+    if factor.is_finite() && factor > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::new(
+            Status::InvalidArg,
+            format!("Scale factor must be a positive number, got {}", factor),
+        ))
+    }
+}
+
+/// Scale ingredient quantities by a factor, adjusting fractions and units
+/// (e.g. 1/2 cup × 3 = 1 1/2 cups, 2 tsp × 3 = 2 tbsp)
+#[napi]
+pub fn scale_ingredients(ingredients: Vec<Ingredient>, factor: f64) -> Result<Vec<Ingredient>> {
+    check_scale_factor(factor)?;
+
+    Ok(ingredients
+        .into_iter()
+        .map(|ingredient| Ingredient {
+            quantity: ingredient
+                .quantity
+                .map(|quantity| quantity::scale_quantity(&quantity, factor)),
+            ..ingredient
+        })
+        .collect())
+}
+
+/// A system of measurement
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+/// Convert ingredient quantities between US measures and metric
+/// (e.g. 1 cup = 240 ml, 8 oz = 230 g, 500 g = 1 lb)
+#[napi]
+pub fn convert_ingredient_units(
+    ingredients: Vec<Ingredient>,
+    system: UnitSystem,
+) -> Vec<Ingredient> {
+    ingredients
+        .into_iter()
+        .map(|ingredient| Ingredient {
+            quantity: ingredient
+                .quantity
+                .map(|quantity| quantity::convert_quantity(&quantity, &system)),
+            ..ingredient
+        })
+        .collect()
+}
+
+/// Compare two snapshots (or recipe backups) and return what changed from `a`
+/// to `b`: lists and recipes added and removed, items added, removed, and
+/// edited in each list, and the fields changed in each recipe
+#[napi]
+pub fn diff_snapshots(a: String, b: String) -> Result<SnapshotDiff> {
+    snapshot::diff(&a, &b)
+}
+
+/// Read the schema.org recipe (JSON-LD or microdata) in a web page's HTML into
+/// the options for `createRecipe`, without creating it
+/// The page's main image becomes `photoUrl` when its URL is absolute
+#[napi]
+pub fn parse_recipe_html(html: String) -> Result<CreateRecipeOptions> {
+    let data = schema_org::find_recipe(&html).ok_or_else(|| {
+        Error::new(
+            Status::InvalidArg,
+            "No schema.org recipe found in the HTML".to_string(),
+        )
+    })?;
+    let mut options = schema_org::create_recipe_options(&data)?;
+    options.photo_url = schema_org::image_url(&data).filter(|url| reqwest::Url::parse(url).is_ok());
+
+    Ok(options)
+}
+
+/// Format a number of minutes for display (e.g. 90 = "1 hr 30 min")
+#[napi]
+pub fn humanize_minutes(minutes: i32) -> Result<String> {
+    if minutes < 0 {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!("Minutes must not be negative, got {}", minutes),
+        ));
+    }
+    Ok(export::format_minutes(minutes))
+}
+
+/// The day an instant falls on in a time zone, or the system's
+fn local_day_in(
+    time_zone: Option<chrono_tz::Tz>,
+    instant: chrono::DateTime<chrono::Utc>,
+) -> chrono::NaiveDate {
+    match time_zone {
+        Some(time_zone) => instant.with_timezone(&time_zone).date_naive(),
+        None => instant.with_timezone(&chrono::Local).date_naive(),
+    }
+}
+
+/// The recipes in a user data response, with when each was last cooked up
+/// to `today` (YYYY-MM-DD)
+fn recipes_from(data: &PbUserDataResponse, today: &str) -> Vec<Recipe> {
+    // Meal plan events up to today count as the recipe being cooked
+    let mut last_cooked: HashMap<&str, &str> = HashMap::new();
+    for event in data
+        .meal_planning_calendar_response
+        .iter()
+        .flat_map(|response| &response.events)
+    {
+        let (Some(recipe_id), Some(date)) = (&event.recipe_id, &event.date) else {
+            continue;
+        };
+        if date.as_str() > today {
+            continue;
+        }
+        let latest = last_cooked.entry(recipe_id).or_insert(date);
+        if date.as_str() > *latest {
+            *latest = date;
+        }
+    }
+
+    let mut recipes: Vec<Recipe> = data
+        .recipe_data_response
+        .iter()
+        .flat_map(|response| &response.recipes)
+        .filter(|recipe| recipe.name.is_some())
+        .map(|raw| {
+            let mut recipe = Recipe::from(raw);
+            recipe.last_cooked_at = last_cooked
+                .get(raw.identifier.as_str())
+                .map(|date| date.to_string());
+            recipe
+        })
+        .collect();
+
+    // Ingredients marked "see recipe" are made from the recipe they name
+    let ids_by_name: HashMap<String, String> = recipes
+        .iter()
+        .map(|recipe| (normalize_name(&recipe.name), recipe.id.clone()))
+        .collect();
+    for recipe in &mut recipes {
+        for ingredient in &mut recipe.ingredients {
+            if ingredient.is_header == Some(true) {
+                continue;
+            }
+            ingredient.recipe_id = sub_recipe_name(ingredient)
+                .and_then(|name| ids_by_name.get(&normalize_name(name)))
+                .filter(|id| **id != recipe.id)
+                .cloned();
+        }
+    }
+
+    recipes
+}
+
+/// The name of the recipe an ingredient is marked as made from, with "see
+/// recipe" as its note or after its name (e.g. "Pizza dough — see recipe")
+fn sub_recipe_name(ingredient: &Ingredient) -> Option<&str> {
+    let is_marker = |text: &str| {
+        text.trim_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+            .eq_ignore_ascii_case("see recipe")
+    };
+    if ingredient.note.as_deref().is_some_and(is_marker) {
+        return Some(ingredient.name.trim());
+    }
+
+    let name = ingredient.name.trim_end().trim_end_matches([')', '.']);
+    let marker = name.len().checked_sub("see recipe".len())?;
+    if !name.get(marker..)?.eq_ignore_ascii_case("see recipe") {
+        return None;
+    }
+    let name = &name[..marker];
+    let recipe = name.trim_end_matches(|c: char| c.is_whitespace() || "—–-,;:(".contains(c));
+    // The marker has to be set apart from the name
+    (recipe.len() < name.len() && !recipe.is_empty()).then_some(recipe)
+}
+
+/// How many batches of a sub-recipe an ingredient's quantity asks for: a bare
+/// count or a number of batches, but not a measure like "2 cups"
+fn batches(quantity: Option<&str>) -> Option<f64> {
+    let Some(quantity) = quantity.filter(|q| !q.trim().is_empty()) else {
+        return Some(1.0);
+    };
+    let (amount, unit) = quantity::parse_amount(quantity)?;
+    let unit = unit.to_lowercase();
+    ["", "x", "batch", "batches", "recipe", "recipes"]
+        .contains(&unit.as_str())
+        .then_some(amount.low)
+}
+
+/// Normalize an item name for duplicate detection
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
 /// Saved authentication tokens for resuming sessions
 #[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct SavedTokens {
     pub user_id: String,
     pub access_token: String,
@@ -72,6 +369,8 @@ impl From<SavedTokens> for RsSavedTokens {
 
 /// A grocery list item
 #[napi(object)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct ListItem {
     pub id: String,
     pub name: String,
@@ -94,1098 +393,8339 @@ impl From<&RsListItem> for ListItem {
     }
 }
 
-/// A grocery list
-#[napi(object)]
-pub struct List {
-    pub id: String,
-    pub name: String,
-    pub items: Vec<ListItem>,
-}
-
-impl From<&RsList> for List {
-    fn from(list: &RsList) -> Self {
-        List {
-            id: list.id().to_string(),
-            name: list.name().to_string(),
-            items: list.items().iter().map(ListItem::from).collect(),
+impl From<&PbListItem> for ListItem {
+    fn from(item: &PbListItem) -> Self {
+        ListItem {
+            id: item.identifier.clone(),
+            name: item.name.clone().unwrap_or_default(),
+            checked: item.checked == Some(true),
+            quantity: item.quantity.clone(),
+            note: item.details.clone().unwrap_or_default(),
+            category: item.category.clone(),
         }
     }
 }
 
-/// A recipe ingredient
+/// Fields to change on an item; omitted fields are left as they are
 #[napi(object)]
-pub struct Ingredient {
-    pub name: String,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemPatch {
+    pub name: Option<String>,
     pub quantity: Option<String>,
     pub note: Option<String>,
+    pub category: Option<String>,
+    pub checked: Option<bool>,
 }
 
-impl From<&RsIngredient> for Ingredient {
-    fn from(ingredient: &RsIngredient) -> Self {
-        Ingredient {
-            name: ingredient.name().to_string(),
-            quantity: ingredient.quantity().map(|s| s.to_string()),
-            note: ingredient.note().map(|s| s.to_string()),
-        }
-    }
+/// A new category for an item
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemCategoryChange {
+    pub item_id: String,
+    pub category: String,
 }
 
-/// A recipe
+/// Which action an upsert performed
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UpsertAction {
+    Created,
+    Updated,
+}
+
+/// A recipe field that searches can match against
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecipeSearchField {
+    Name,
+    Ingredients,
+    Note,
+    Steps,
+}
+
+/// Options for searching recipes
 #[napi(object)]
-pub struct Recipe {
-    pub id: String,
-    pub name: String,
-    pub ingredients: Vec<Ingredient>,
-    pub preparation_steps: Vec<String>,
-    pub note: Option<String>,
-    pub source_name: Option<String>,
-    pub source_url: Option<String>,
-    pub servings: Option<String>,
-    pub prep_time: Option<i32>,
-    pub cook_time: Option<i32>,
-    pub rating: Option<i32>,
-    pub nutritional_info: Option<String>,
-    pub photo_id: Option<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRecipesOptions {
+    /// Fields to search (default: all)
+    pub fields: Option<Vec<RecipeSearchField>>,
 }
 
-/// A category for organizing list items
+/// Conditions recipes must meet to be returned by `getRecipes`
 #[napi(object)]
-pub struct Category {
-    pub id: String,
-    pub name: String,
-    pub icon: Option<String>,
-    pub sort_index: i32,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeFilter {
+    /// Only recipes rated at least this (1-5)
+    pub min_rating: Option<i32>,
+    /// Only recipes whose prep and cook time add up to at most this many
+    /// minutes; recipes without times are left out
+    pub max_total_time: Option<i32>,
+    /// Only recipes in this collection
+    pub collection_id: Option<String>,
 }
 
-impl From<&RsCategory> for Category {
-    fn from(category: &RsCategory) -> Self {
-        Category {
-            id: category.id().to_string(),
-            name: category.name().to_string(),
-            icon: category.icon().map(|s| s.to_string()),
-            sort_index: category.sort_index(),
-        }
-    }
+/// Which date orders recently used recipes
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecentRecipesOrder {
+    Added,
+    Updated,
 }
 
-/// A group of categories
+/// Options for listing recent recipes
 #[napi(object)]
-pub struct CategoryGroup {
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentRecipesOptions {
+    /// Order by when recipes were added or last changed (default: added)
+    pub by: Option<RecentRecipesOrder>,
+    /// Maximum number of recipes to return (default: all)
+    pub limit: Option<u32>,
+}
+
+/// An earlier version of a recipe
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeVersion {
     pub id: String,
-    pub name: String,
-    pub categories: Vec<Category>,
+    /// When the recipe was changed from this version, in milliseconds since the epoch
+    pub saved_at: f64,
+    pub recipe: Recipe,
 }
 
-impl From<&RsCategoryGroup> for CategoryGroup {
-    fn from(group: &RsCategoryGroup) -> Self {
-        CategoryGroup {
-            id: group.id().to_string(),
-            name: group.name().to_string(),
-            categories: group.categories().iter().map(Category::from).collect(),
-        }
-    }
+/// An event in a meal plan template
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanTemplateEvent {
+    /// Day of the week the event falls on, counted from the week's first day (0-6)
+    pub day: u32,
+    pub title: Option<String>,
+    pub recipe_id: Option<String>,
+    pub label_id: Option<String>,
+    pub details: Option<String>,
 }
 
-/// A store for organizing where to buy items
+/// A named week of meal plan events that can be applied to any week
 #[napi(object)]
-pub struct Store {
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanTemplate {
     pub id: String,
     pub name: String,
-    pub sort_index: i32,
+    pub events: Vec<MealPlanTemplateEvent>,
 }
 
-impl From<&RsStore> for Store {
-    fn from(store: &RsStore) -> Self {
-        Store {
-            id: store.id().to_string(),
-            name: store.name().to_string(),
-            sort_index: store.sort_index(),
-        }
-    }
+/// A recipe to add to a list, with an optional scale factor
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeSelection {
+    pub recipe_id: String,
+    /// Multiply ingredient quantities by this (default 1)
+    pub scale_factor: Option<f64>,
 }
 
-/// A filter for stores
+/// A downloaded recipe photo
 #[napi(object)]
-pub struct StoreFilter {
-    pub id: String,
-    pub name: String,
-    pub store_ids: Vec<String>,
+pub struct RecipePhoto {
+    pub data: Buffer,
+    /// MIME type of the image, e.g. "image/jpeg"
+    pub content_type: String,
 }
 
-impl From<&RsStoreFilter> for StoreFilter {
-    fn from(filter: &RsStoreFilter) -> Self {
-        StoreFilter {
-            id: filter.id().to_string(),
-            name: filter.name().to_string(),
-            store_ids: filter.store_ids().iter().map(|s| s.to_string()).collect(),
-        }
-    }
+/// Options for backing up all recipes
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAllRecipesOptions {
+    /// Embed each recipe's photo as base64 (default false)
+    pub include_photos: Option<bool>,
 }
 
-/// A favourite item (starter list item)
+/// How `restoreFromSnapshot()` treats lists and recipes that already exist
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// Keep them, adding only what's missing
+    Merge,
+    /// Replace them with the snapshot's
+    Replace,
+}
+
+/// Options for restoring a snapshot
 #[napi(object)]
-pub struct FavouriteItem {
-    pub id: String,
-    pub list_id: String,
-    pub name: String,
-    pub quantity: Option<String>,
-    pub details: Option<String>,
-    pub category: Option<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreOptions {
+    /// Defaults to `merge`
+    pub mode: Option<RestoreMode>,
 }
 
-impl From<&RsFavouriteItem> for FavouriteItem {
-    fn from(item: &RsFavouriteItem) -> Self {
-        FavouriteItem {
-            id: item.id().to_string(),
-            list_id: item.list_id().to_string(),
-            name: item.name().to_string(),
-            quantity: item.quantity().map(|s| s.to_string()),
-            details: item.details().map(|s| s.to_string()),
-            category: item.category().map(|s| s.to_string()),
-        }
-    }
+/// A kind of entity in a snapshot
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotEntity {
+    List,
+    ListItem,
+    Recipe,
 }
 
-/// A list of favourite items (starter list)
+/// A list, item, or recipe from a snapshot and the one it was restored as
 #[napi(object)]
-pub struct FavouritesList {
-    pub id: String,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredEntity {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: SnapshotEntity,
+    /// Its ID in the snapshot
+    pub old_id: String,
+    /// Its ID now: the one created, or the existing one it matched
+    pub new_id: String,
     pub name: String,
-    pub items: Vec<FavouriteItem>,
-    pub shopping_list_id: Option<String>,
+    /// The list it's on now, for items
+    pub list_id: Option<String>,
 }
 
-impl From<&RsFavouritesList> for FavouritesList {
-    fn from(list: &RsFavouritesList) -> Self {
-        FavouritesList {
-            id: list.id().to_string(),
-            name: list.name().to_string(),
-            items: list.items().iter().map(FavouriteItem::from).collect(),
-            shopping_list_id: list.shopping_list_id().map(|s| s.to_string()),
-        }
-    }
+/// What `restoreFromSnapshot()` did
+#[napi(object)]
+#[derive(Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub created: Vec<RestoredEntity>,
+    /// Entities that already existed, so weren't created again
+    pub skipped: Vec<RestoredEntity>,
 }
 
-/// A meal plan event
+/// A recipe from an export that couldn't be imported, or couldn't be added to
+/// one of its collections
 #[napi(object)]
-pub struct MealPlanEvent {
-    pub id: String,
-    pub date: String,
-    pub title: Option<String>,
-    pub recipe_id: Option<String>,
-    pub label_id: Option<String>,
-    pub details: Option<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeImportFailure {
+    /// The recipe's name in the export
+    pub name: String,
+    pub error: String,
 }
 
-impl From<&RsMealPlanEvent> for MealPlanEvent {
-    fn from(event: &RsMealPlanEvent) -> Self {
-        MealPlanEvent {
-            id: event.id().to_string(),
-            date: event.date().to_string(),
-            title: event.title().map(|s| s.to_string()),
-            recipe_id: event.recipe_id().map(|s| s.to_string()),
-            label_id: event.label_id().map(|s| s.to_string()),
-            details: event.details().map(|s| s.to_string()),
-        }
-    }
+/// What `importRecipesFromPaprika()` or `importRecipesFromMealie()` did
+#[napi(object)]
+#[derive(Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeImportResult {
+    pub created: Vec<Recipe>,
+    /// Recipes that couldn't be created, or whose photo couldn't be uploaded,
+    /// and created recipes that couldn't be added to a collection
+    pub failed: Vec<RecipeImportFailure>,
 }
 
-/// iCalendar sync information
+/// A recipe added, removed, or changed between two snapshots
 #[napi(object)]
-pub struct ICalendarInfo {
-    pub enabled: bool,
-    pub url: Option<String>,
-    pub token: Option<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeChanges {
+    pub recipe_id: String,
+    pub name: String,
+    /// Its name in the earlier snapshot, if it was renamed
+    pub old_name: Option<String>,
+    /// The fields that changed (e.g. "ingredients", "rating"), for changed
+    /// recipes
+    pub changed_fields: Vec<String>,
 }
 
-impl From<&RsICalendarInfo> for ICalendarInfo {
-    fn from(info: &RsICalendarInfo) -> Self {
-        ICalendarInfo {
-            enabled: info.enabled(),
-            url: info.url().map(|s| s.to_string()),
-            token: info.token().map(|s| s.to_string()),
-        }
-    }
+/// What changed between two snapshots, as returned by `diffSnapshots()`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    pub lists_added: Vec<List>,
+    pub lists_removed: Vec<List>,
+    /// Lists in both snapshots whose name or items changed
+    pub lists_changed: Vec<ListChanges>,
+    pub recipes_added: Vec<RecipeChanges>,
+    pub recipes_removed: Vec<RecipeChanges>,
+    pub recipes_changed: Vec<RecipeChanges>,
 }
 
-/// A collection of recipes
+/// Output format for exported recipes
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+/// Layout for recipes rendered as HTML
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecipeHtmlTemplate {
+    /// Photo, note, ingredients, steps, and nutrition
+    Full,
+    /// Just what's needed while cooking: servings, times, ingredients, and steps
+    Compact,
+}
+
+/// Options for rendering a recipe as HTML
 #[napi(object)]
-pub struct RecipeCollection {
-    pub id: String,
-    pub name: String,
-    pub recipe_ids: Vec<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderRecipeHtmlOptions {
+    /// Layout to use (default: full)
+    pub template: Option<RecipeHtmlTemplate>,
 }
 
-impl From<&RsRecipeCollection> for RecipeCollection {
-    fn from(collection: &RsRecipeCollection) -> Self {
-        RecipeCollection {
-            id: collection.id().to_string(),
-            name: collection.name().to_string(),
-            recipe_ids: collection
-                .recipe_ids()
-                .iter()
-                .map(|s| s.to_string())
-                .collect(),
-        }
-    }
+/// The result of upserting an item
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertItemResult {
+    pub action: UpsertAction,
+    pub item: ListItem,
 }
 
-/// Options for creating a new recipe
+/// Options for fetching a single list
 #[napi(object)]
-pub struct CreateRecipeOptions {
-    /// Recipe name (required)
-    pub name: String,
-    /// List of ingredients
-    pub ingredients: Vec<IngredientInput>,
-    /// Preparation/cooking steps
-    pub preparation_steps: Vec<String>,
-    /// Recipe notes/description
-    pub note: Option<String>,
-    /// Source name (e.g., "Web", "Cookbook")
-    pub source_name: Option<String>,
-    /// Source URL
-    pub source_url: Option<String>,
-    /// Serving size (e.g., "4 servings")
-    pub servings: Option<String>,
-    /// Prep time in minutes
-    pub prep_time: Option<i32>,
-    /// Cook time in minutes
-    pub cook_time: Option<i32>,
-    /// Rating from 1-5
-    pub rating: Option<i32>,
-    /// Nutritional information
-    pub nutritional_info: Option<String>,
-    /// Photo ID (from upload_photo)
-    pub photo_id: Option<String>,
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetListOptions {
+    /// Include checked (crossed off) items (default: true)
+    pub include_checked: Option<bool>,
 }
 
-impl From<&RsRecipe> for Recipe {
-    fn from(recipe: &RsRecipe) -> Self {
-        Recipe {
-            id: recipe.id().to_string(),
-            name: recipe.name().to_string(),
-            ingredients: recipe.ingredients().iter().map(Ingredient::from).collect(),
-            preparation_steps: recipe
-                .preparation_steps()
+/// Which of a list's items `getListItems()` returns
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItemsOptions {
+    /// How many items to skip (default: 0)
+    pub offset: Option<u32>,
+    /// Maximum number of items to return (default: all)
+    pub limit: Option<u32>,
+    /// Include checked (crossed off) items (default: true)
+    pub include_checked: Option<bool>,
+}
+
+/// A page of a list's items
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItemsPage {
+    pub items: Vec<ListItem>,
+    /// How many items the list has in all
+    pub total: u32,
+    /// The offset of the next page, if there are more items
+    pub next_offset: Option<u32>,
+}
+
+/// A location that triggers reminders for a list
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationLocation {
+    pub id: String,
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Reminder and notification settings for a list
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListNotificationSettings {
+    /// Remind collaborators when they arrive at one of the list's locations
+    pub location_notifications_enabled: bool,
+    /// What the app icon badge counts for this list
+    pub badge_mode: Option<String>,
+    /// Locations that trigger reminders (managed in the AnyList apps)
+    pub notification_locations: Vec<NotificationLocation>,
+}
+
+/// Changes to a list's notification settings; omitted fields are left as they are
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListNotificationSettingsPatch {
+    pub location_notifications_enabled: Option<bool>,
+    pub badge_mode: Option<String>,
+}
+
+impl ListNotificationSettings {
+    fn new(list: &PbShoppingList, settings: &PbListSettings) -> Self {
+        ListNotificationSettings {
+            location_notifications_enabled: settings.location_notifications_enabled(),
+            badge_mode: settings.badge_mode.clone(),
+            notification_locations: list
+                .notification_locations
                 .iter()
-                .map(|s| s.to_string())
+                .map(|l| NotificationLocation {
+                    id: l.identifier.clone(),
+                    name: l.name.clone(),
+                    address: l.address.clone(),
+                    latitude: l.latitude,
+                    longitude: l.longitude,
+                })
                 .collect(),
-            note: recipe.note().map(|s| s.to_string()),
-            source_name: recipe.source_name().map(|s| s.to_string()),
-            source_url: recipe.source_url().map(|s| s.to_string()),
-            servings: recipe.servings().map(|s| s.to_string()),
-            prep_time: recipe.prep_time(),
-            cook_time: recipe.cook_time(),
-            rating: recipe.rating(),
-            nutritional_info: recipe.nutritional_info().map(|s| s.to_string()),
-            photo_id: recipe.photo_id().map(|s| s.to_string()),
         }
     }
 }
 
-/// The main AnyList client for interacting with the API
+/// Display and behaviour settings for a list
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSettings {
+    pub hide_categories: bool,
+    pub hide_completed_items: bool,
+    pub hide_prices: bool,
+    pub hide_running_totals: bool,
+    pub hide_store_names: bool,
+    /// Running total shown on the left of the list footer
+    pub left_running_total_type: Option<i32>,
+    /// Running total shown on the right of the list footer
+    pub right_running_total_type: Option<i32>,
+    pub list_item_sort_order: Option<String>,
+    /// Category group used to group the list's items
+    pub category_group_id: Option<String>,
+    /// Store filter applied by default
+    pub store_filter_id: Option<String>,
+    pub remember_item_categories: bool,
+    pub favourites_autocomplete_enabled: bool,
+    pub recent_items_autocomplete_enabled: bool,
+    pub generic_grocery_autocomplete_enabled: bool,
+    pub list_theme_id: Option<String>,
+    pub badge_mode: Option<String>,
+    pub location_notifications_enabled: bool,
+    /// Favourites list linked to this list (read-only)
+    pub linked_favourites_list_id: Option<String>,
+}
+
+impl ListSettings {
+    fn new(settings: &PbListSettings, linked_favourites_list_id: Option<String>) -> Self {
+        ListSettings {
+            hide_categories: settings.should_hide_categories(),
+            hide_completed_items: settings.should_hide_completed_items(),
+            hide_prices: settings.should_hide_prices(),
+            hide_running_totals: settings.should_hide_running_totals(),
+            hide_store_names: settings.should_hide_store_names(),
+            left_running_total_type: settings.left_running_total_type,
+            right_running_total_type: settings.right_running_total_type,
+            list_item_sort_order: settings.list_item_sort_order.clone(),
+            category_group_id: settings.list_category_group_id.clone(),
+            store_filter_id: settings.store_filter_id.clone(),
+            remember_item_categories: settings.should_remember_item_categories(),
+            favourites_autocomplete_enabled: settings.favorites_autocomplete_enabled(),
+            recent_items_autocomplete_enabled: settings.recent_items_autocomplete_enabled(),
+            generic_grocery_autocomplete_enabled: settings.generic_grocery_autocomplete_enabled(),
+            list_theme_id: settings.list_theme_id.clone(),
+            badge_mode: settings.badge_mode.clone(),
+            location_notifications_enabled: settings.location_notifications_enabled(),
+            linked_favourites_list_id,
+        }
+    }
+}
+
+/// Changes to a list's settings; omitted fields are left as they are
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSettingsPatch {
+    pub hide_categories: Option<bool>,
+    pub hide_completed_items: Option<bool>,
+    pub hide_prices: Option<bool>,
+    pub hide_running_totals: Option<bool>,
+    pub hide_store_names: Option<bool>,
+    pub left_running_total_type: Option<i32>,
+    pub right_running_total_type: Option<i32>,
+    pub list_item_sort_order: Option<String>,
+    pub category_group_id: Option<String>,
+    pub store_filter_id: Option<String>,
+    pub remember_item_categories: Option<bool>,
+    pub favourites_autocomplete_enabled: Option<bool>,
+    pub recent_items_autocomplete_enabled: Option<bool>,
+    pub generic_grocery_autocomplete_enabled: Option<bool>,
+    pub list_theme_id: Option<String>,
+    pub badge_mode: Option<String>,
+    pub location_notifications_enabled: Option<bool>,
+}
+
+impl ListSettingsPatch {
+    fn apply(self, settings: &mut PbListSettings) {
+        fn set<T>(field: &mut Option<T>, value: Option<T>) {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+
+        set(&mut settings.should_hide_categories, self.hide_categories);
+        set(
+            &mut settings.should_hide_completed_items,
+            self.hide_completed_items,
+        );
+        set(&mut settings.should_hide_prices, self.hide_prices);
+        set(
+            &mut settings.should_hide_running_totals,
+            self.hide_running_totals,
+        );
+        set(&mut settings.should_hide_store_names, self.hide_store_names);
+        set(
+            &mut settings.left_running_total_type,
+            self.left_running_total_type,
+        );
+        set(
+            &mut settings.right_running_total_type,
+            self.right_running_total_type,
+        );
+        set(
+            &mut settings.list_item_sort_order,
+            self.list_item_sort_order,
+        );
+        set(&mut settings.list_category_group_id, self.category_group_id);
+        set(&mut settings.store_filter_id, self.store_filter_id);
+        set(
+            &mut settings.should_remember_item_categories,
+            self.remember_item_categories,
+        );
+        set(
+            &mut settings.favorites_autocomplete_enabled,
+            self.favourites_autocomplete_enabled,
+        );
+        set(
+            &mut settings.recent_items_autocomplete_enabled,
+            self.recent_items_autocomplete_enabled,
+        );
+        set(
+            &mut settings.generic_grocery_autocomplete_enabled,
+            self.generic_grocery_autocomplete_enabled,
+        );
+        set(&mut settings.list_theme_id, self.list_theme_id);
+        set(&mut settings.badge_mode, self.badge_mode);
+        set(
+            &mut settings.location_notifications_enabled,
+            self.location_notifications_enabled,
+        );
+    }
+}
+
+/// A grocery list
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct List {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<ListItem>,
+}
+
+impl From<&PbShoppingList> for List {
+    fn from(list: &PbShoppingList) -> Self {
+        List {
+            id: list.identifier.clone(),
+            name: list.name.clone().unwrap_or_default(),
+            items: list.items.iter().map(ListItem::from).collect(),
+        }
+    }
+}
+
+impl From<&RsList> for List {
+    fn from(list: &RsList) -> Self {
+        List {
+            id: list.id().to_string(),
+            name: list.name().to_string(),
+            items: list.items().iter().map(ListItem::from).collect(),
+        }
+    }
+}
+
+/// What changed in a shopping list
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeEventType {
+    /// An item was added to the list
+    ItemAdded,
+    /// An item was checked or unchecked
+    ItemChecked,
+    /// The list was renamed
+    ListRenamed,
+}
+
+/// A change to a shopping list, as delivered to `subscribe()` callbacks
+#[napi(object)]
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeEvent {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: ChangeEventType,
+    pub list_id: String,
+    /// The list's name after the change
+    pub list_name: String,
+    /// The item as it is now, for item events
+    pub item: Option<ListItem>,
+    /// The list's previous name, for `listRenamed`
+    pub old_name: Option<String>,
+}
+
+/// How long to cache `getLists()` and `getRecipes()` results, in
+/// milliseconds; types without a time aren't cached
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheOptions {
+    pub lists_ttl_ms: Option<u32>,
+    /// Also used by recipe searches and filters
+    pub recipes_ttl_ms: Option<u32>,
+}
+
+/// A type of data the client can cache
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheEntity {
+    Lists,
+    Recipes,
+}
+
+/// How often `startAutoRefresh()` refreshes each type, in milliseconds;
+/// types without an interval aren't refreshed
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoRefreshOptions {
+    pub lists: Option<u32>,
+    pub recipes: Option<u32>,
+}
+
+/// What happened to a background refresh
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RefreshEventType {
+    Refreshed,
+    Failed,
+}
+
+/// A background refresh, as delivered to the `startAutoRefresh()` callback
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshEvent {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: RefreshEventType,
+    pub entity: CacheEntity,
+    /// Why the refresh failed, for `failed`
+    pub error: Option<String>,
+}
+
+type RefreshEventCallback =
+    ThreadsafeFunction<RefreshEvent, UnknownReturnValue, RefreshEvent, Status, false>;
+
+/// A kind of change the offline queue can hold
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QueuedChange {
+    /// Add an item
+    Add,
+    /// Cross off (check) an item
+    Check,
+    /// Uncheck an item
+    Uncheck,
+}
+
+/// A change waiting in the offline queue
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedOperation {
+    /// The operation's ID, which AnyList sees once it's sent
+    pub id: String,
+    pub change: QueuedChange,
+    /// What the change does (e.g. "Add Milk")
+    pub description: String,
+    pub list_id: String,
+    pub item_id: Option<String>,
+    /// The item's name when the change was made, if it's known
+    pub name: Option<String>,
+    /// When the change was made, in milliseconds since the epoch
+    pub queued_at: f64,
+}
+
+impl QueuedOperation {
+    fn new(
+        change: QueuedChange,
+        list_id: &str,
+        item_id: &str,
+        name: Option<&str>,
+        operation: &PbListOperation,
+    ) -> Self {
+        let description = match change {
+            QueuedChange::Add => format!("Add {}", name.unwrap_or_default()),
+            QueuedChange::Check => "Cross off item".to_string(),
+            QueuedChange::Uncheck => "Uncheck item".to_string(),
+        };
+        QueuedOperation {
+            id: operation
+                .metadata
+                .as_ref()
+                .and_then(|m| m.operation_id.clone())
+                .unwrap_or_default(),
+            change,
+            description,
+            list_id: list_id.to_string(),
+            item_id: Some(item_id.to_string()),
+            name: name.map(str::to_string),
+            queued_at: transport::current_timestamp() * 1000.0,
+        }
+    }
+}
+
+/// What happened to a change in the offline queue
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueEventType {
+    /// AnyList couldn't be reached, so the change was queued
+    Queued,
+    /// The change was sent to AnyList, or merged with a conflicting one
+    Flushed,
+    /// AnyList refused the change, so it was dropped from the queue
+    Rejected,
+    /// The change conflicted with one made elsewhere, which was kept
+    Discarded,
+}
+
+/// Something that happened to a queued change, as delivered to the
+/// `setOfflineQueue()` callback
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEvent {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: QueueEventType,
+    pub operation: QueuedOperation,
+    /// Why AnyList refused the change, for `rejected`
+    pub error: Option<String>,
+}
+
+type QueueEventCallback =
+    ThreadsafeFunction<QueueEvent, UnknownReturnValue, QueueEvent, Status, false>;
+
+/// How a queued or optimistic change conflicts with the list as it is now
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictType {
+    /// The list was deleted
+    ListDeleted,
+    /// The item to check or uncheck was deleted
+    ItemDeleted,
+    /// An item with the same name was added to the list
+    DuplicateItem,
+    /// The item to check or uncheck was renamed
+    ItemRenamed,
+}
+
+/// A queued or optimistic change that conflicts with changes made elsewhere
+/// since it was made, as passed to the `setConflictResolver()` callback
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueConflict {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: ConflictType,
+    pub operation: QueuedOperation,
+    /// The item already on the list, for `duplicateItem`, or as it is now,
+    /// for `itemRenamed`
+    pub remote_item: Option<ListItem>,
+}
+
+/// How to settle a conflict between a queued or optimistic change and the
+/// list
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    /// Send the change as it is
+    KeepLocal,
+    /// Drop the change
+    KeepRemote,
+    /// Combine them: a duplicate item is unchecked instead of added again,
+    /// and a renamed item is checked or unchecked under its new name; other
+    /// conflicts are kept remote
+    Merge,
+}
+
+type ConflictResolver =
+    ThreadsafeFunction<QueueConflict, ConflictResolution, QueueConflict, Status, false>;
+
+/// What happened to a change made optimistically
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum OptimisticEventType {
+    /// AnyList accepted the change
+    Confirmed,
+    /// The change couldn't be sent, so it was undone locally
+    RolledBack,
+}
+
+/// Something that happened to a change made optimistically, as delivered to
+/// the `setOptimisticUpdates()` callback
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimisticEvent {
+    #[napi(js_name = "type")]
+    #[serde(rename = "type")]
+    pub kind: OptimisticEventType,
+    pub operation: QueuedOperation,
+    /// Why the change couldn't be sent, for `rolledBack`
+    pub error: Option<String>,
+}
+
+type OptimisticEventCallback =
+    ThreadsafeFunction<OptimisticEvent, UnknownReturnValue, OptimisticEvent, Status, false>;
+
+/// How a list drifted from what a batch expected of it
+#[napi(string_enum = "camelCase")]
+#[derive(Clone, Copy, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum DriftReason {
+    /// The list was changed between the batch reading it and AnyList
+    /// applying the batch
+    ListChanged,
+    /// AnyList didn't apply some of the batch's changes
+    ChangesSkipped,
+    /// AnyList asked for the list to be fetched again in full
+    RefreshRequired,
+}
+
+/// A list that no longer matches what a batch expected, as delivered to the
+/// `setDriftDetection()` callback
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftEvent {
+    pub list_id: String,
+    pub reason: DriftReason,
+    /// The items whose changes are affected, if any
+    pub item_ids: Vec<String>,
+}
+
+type DriftEventCallback =
+    ThreadsafeFunction<DriftEvent, UnknownReturnValue, DriftEvent, Status, false>;
+
+/// A change made through a client, as recorded in the journal
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: String,
+    /// The method that made the change (e.g. "addItem")
+    pub operation: String,
+    /// The IDs it was given, then the IDs of anything it created or returned
+    pub entity_ids: Vec<String>,
+    /// When the change started and finished, in milliseconds since the epoch
+    pub started_at: f64,
+    pub finished_at: f64,
+    pub succeeded: bool,
+    /// Why the change failed
+    pub error: Option<String>,
+    /// The ID of the process that made the change
+    pub process_id: u32,
+}
+
+/// What a change that has nothing else to return returns, to match it up
+/// with its journal entry
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationReceipt {
+    /// The ID of the change's entry in the journal
+    pub id: String,
+    /// The method that made the change (e.g. "deleteItem")
+    pub operation: String,
+    /// When AnyList accepted the change, in milliseconds since the epoch by
+    /// this machine's clock
+    pub completed_at: f64,
+}
+
+/// A list item for `encodeOperation()`, with the fields of AnyList's
+/// `PBListItem`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RawListItem {
+    /// Generated if not given
+    pub identifier: Option<String>,
+    pub name: Option<String>,
+    pub quantity: Option<String>,
+    pub details: Option<String>,
+    pub category: Option<String>,
+    pub checked: Option<bool>,
+}
+
+/// A list operation for `encodeOperation()`, with the fields of AnyList's
+/// `PBListOperation`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RawListOperation {
+    /// What the operation does (e.g. "set-list-item-checked")
+    pub handler_id: String,
+    pub list_id: String,
+    pub list_item_id: Option<String>,
+    pub updated_value: Option<String>,
+    pub original_value: Option<String>,
+    pub list_item: Option<RawListItem>,
+}
+
+/// Which journal entries `getJournal()` returns
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalOptions {
+    /// Only entries for changes that finished at or after this time, in
+    /// milliseconds since the epoch
+    pub since: Option<f64>,
+}
+
+/// A change or fetch that failed
+#[napi(object)]
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncError {
+    /// What was being done (e.g. "addItem" or "getLists")
+    pub operation: String,
+    pub message: String,
+    /// When it failed, in milliseconds since the epoch
+    pub at: f64,
+}
+
+/// How well a client is keeping up with AnyList, as returned by
+/// `getSyncStatus()`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// When the lists were last fetched from AnyList, in milliseconds since
+    /// the epoch; cached reads don't count
+    pub lists_fetched_at: Option<f64>,
+    /// When the recipes were last fetched from AnyList
+    pub recipes_fetched_at: Option<f64>,
+    /// How many changes are waiting in the offline queue
+    pub queued_operations: u32,
+    /// The most recent failure, which may be older than the last fetch
+    pub last_error: Option<SyncError>,
+    /// Whether any running subscription is connected to AnyList's realtime
+    /// channel
+    pub realtime_connected: bool,
+}
+
+/// What changed in the account, as returned by `getChangesSince()`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChanges {
+    /// Pass to the next `getChangesSince()` to get the changes after these
+    pub cursor: String,
+    /// Lists that were created or changed, with all their items
+    pub lists: Vec<List>,
+    pub recipes: Vec<Recipe>,
+    pub meal_plan_events: Vec<MealPlanEvent>,
+    /// Deleted since the cursor; always empty when changes are asked for
+    /// since a time
+    pub deleted_list_ids: Vec<String>,
+    pub deleted_recipe_ids: Vec<String>,
+    pub deleted_meal_plan_event_ids: Vec<String>,
+}
+
+/// How to watch for changes
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeOptions {
+    /// Listen on AnyList's realtime channel, polling only while it's
+    /// disconnected (default: true)
+    pub realtime: Option<bool>,
+    /// How often to poll for changes, in milliseconds (default: 30000)
+    pub poll_interval_ms: Option<u32>,
+}
+
+/// How often a subscription polls when not connected to the realtime channel
+const DEFAULT_POLL_INTERVAL_MS: u32 = 30_000;
+
+type ChangeCallback =
+    ThreadsafeFunction<ChangeEvent, UnknownReturnValue, ChangeEvent, Status, false>;
+
+type ListChangesCallback =
+    ThreadsafeFunction<ListChanges, UnknownReturnValue, ListChanges, Status, false>;
+
+/// Where to deliver change events, for `startWebhookBridge()`
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookOptions {
+    /// The URL each event is POSTed to as JSON
+    pub url: String,
+    /// Sign requests with this shared secret (see the README for how to
+    /// check signatures)
+    pub secret: Option<String>,
+    /// Listen on AnyList's realtime channel, polling only while it's
+    /// disconnected (default: true)
+    pub realtime: Option<bool>,
+    /// How often to poll for changes, in milliseconds (default: 30000)
+    pub poll_interval_ms: Option<u32>,
+}
+
+/// A change event that couldn't be delivered to the webhook
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookFailure {
+    pub event: ChangeEvent,
+    pub error: String,
+}
+
+type WebhookFailureCallback =
+    ThreadsafeFunction<WebhookFailure, UnknownReturnValue, WebhookFailure, Status, false>;
+
+/// Where a `ListWatcher` reports changes
+enum ChangeSink {
+    Callback(ChangeCallback),
+    Webhook(webhook::Webhook),
+}
+
+/// What changed in a list between two reads, as delivered to `watchList()`
+/// callbacks
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListChanges {
+    pub list_id: String,
+    /// The list's name now
+    pub name: String,
+    /// The list's previous name, if it was renamed
+    pub old_name: Option<String>,
+    pub added: Vec<ListItem>,
+    /// Items that were deleted, as they were before
+    pub removed: Vec<ListItem>,
+    /// Items with any other change (e.g. checked, renamed, or a new
+    /// quantity), as they are now
+    pub updated: Vec<ListItem>,
+}
+
+/// Check a poll interval given in milliseconds
+fn poll_interval(ms: u32) -> Result<Duration> {
+    if ms == 0 {
+        return Err(Error::new(
+            Status::InvalidArg,
+            "Poll interval must be at least 1 ms",
+        ));
+    }
+    Ok(Duration::from_millis(ms.into()))
+}
+
+/// A running `subscribe()` or `watchList()`; callbacks keep coming until
+/// `unsubscribe()` is called
 #[napi]
-pub struct AnyListClient {
-    inner: RsClient,
+pub struct Subscription {
+    stop: Arc<tokio::sync::Notify>,
+    realtime: Arc<AtomicBool>,
 }
 
 #[napi]
-impl AnyListClient {
-    /// Login to AnyList with email and password
+impl Subscription {
+    /// Whether changes are arriving over AnyList's realtime channel, rather
+    /// than by polling
+    #[napi(getter)]
+    pub fn realtime(&self) -> bool {
+        self.realtime.load(Ordering::Relaxed)
+    }
+
+    /// Stop delivering events
     #[napi]
-    pub async fn login(email: String, password: String) -> Result<AnyListClient> {
-        let client = RsClient::login(&email, &password)
-            .await
-            .map_err(to_napi_error)?;
+    pub fn unsubscribe(&self) {
+        self.stop.notify_one();
+    }
+}
+
+/// Item changes collected by `AnyListClient.batch()`, which are sent to
+/// AnyList together by `commit()`
+#[napi]
+pub struct Batch {
+    client: Arc<RsClient>,
+    journal: Arc<Mutex<journal::Journal>>,
+    sync_state: Arc<Mutex<status::SyncState>>,
+    cache: Arc<Mutex<cache::Cache>>,
+    drift_events: Arc<Mutex<Option<DriftEventCallback>>>,
+    list_locks: Arc<list_locks::ListLocks>,
+    operations: Mutex<Vec<PbListOperation>>,
+}
+
+#[napi]
+impl Batch {
+    /// How many changes are waiting to be committed
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        self.operations.lock().unwrap().len() as u32
+    }
+
+    /// Add an item when the batch is committed, returning the ID it will
+    /// have, which later changes in the batch can use
+    #[napi]
+    pub fn add_item(
+        &self,
+        list_id: String,
+        name: String,
+        quantity: Option<String>,
+        note: Option<String>,
+        category: Option<String>,
+    ) -> String {
+        let operation = operations::add_item(
+            &self.client,
+            &list_id,
+            &name,
+            quantity,
+            note,
+            category,
+            None,
+        );
+        let item_id = operation.list_item_id.clone().unwrap_or_default();
+        self.operations.lock().unwrap().push(operation);
+        item_id
+    }
+
+    /// Set an item's category when the batch is committed
+    #[napi]
+    pub fn set_category(&self, list_id: String, item_id: String, category: String) {
+        self.operations
+            .lock()
+            .unwrap()
+            .push(operations::set_item_field(
+                &self.client,
+                "set-list-item-category-match-id",
+                &list_id,
+                &item_id,
+                &category,
+            ));
+    }
+
+    /// Cross off an item when the batch is committed
+    #[napi]
+    pub fn cross_off_item(&self, list_id: String, item_id: String) {
+        self.operations
+            .lock()
+            .unwrap()
+            .push(operations::set_item_checked(
+                &self.client,
+                &list_id,
+                &item_id,
+                true,
+            ));
+    }
+
+    /// Send every change in one request, returning how many were sent
+    /// Nothing is changed if any list or item they refer to doesn't exist.
+    /// The batch is emptied once it's sent, and keeps its changes if it
+    /// couldn't be, so it can be committed again.
+    #[napi]
+    pub async fn commit(&self) -> Result<u32> {
+        // Taken out so a commit made meanwhile can't send them again
+        let operations = std::mem::take(&mut *self.operations.lock().unwrap());
+        if operations.is_empty() {
+            return Ok(0);
+        }
+        let mut entity_ids: Vec<String> = Vec::new();
+        for operation in &operations {
+            for id in [&operation.list_id, &operation.list_item_id]
+                .into_iter()
+                .flatten()
+            {
+                if !entity_ids.contains(id) {
+                    entity_ids.push(id.clone());
+                }
+            }
+        }
+
+        let list_ids = operations
+            .iter()
+            .filter_map(|o| o.list_id.clone())
+            .collect();
+        let operation_list = PbListOperationList { operations };
+        let commit = journal::journaled(
+            &self.journal,
+            &self.sync_state,
+            transport::generate_id(),
+            "commitBatch",
+            entity_ids,
+            async {
+                let read_at = self.check(&operation_list.operations).await?;
+                let response = transport::post_message(
+                    &self.client,
+                    "data/shopping-lists/update",
+                    &operation_list,
+                )
+                .await
+                .map_err(to_napi_error)?;
+                self.verify(&response, &operation_list.operations, &read_at);
+                Ok(())
+            },
+        );
+        let result = self.list_locks.run_all(list_ids, commit).await;
+
+        let sent = operation_list.operations.len();
+        if let Err(e) = result {
+            // Put back ahead of any changes added meanwhile
+            let mut operations = self.operations.lock().unwrap();
+            operations.splice(0..0, operation_list.operations);
+            return Err(e);
+        }
+        self.cache
+            .lock()
+            .unwrap()
+            .invalidate(Some(CacheEntity::Lists));
+        Ok(sent as u32)
+    }
+}
+
+impl Batch {
+    /// Check that every list exists, and that every item not added by the
+    /// batch itself is on its list, and return each list's logical timestamp
+    async fn check(&self, operations: &[PbListOperation]) -> Result<HashMap<String, Option<u64>>> {
+        let data = self.client.get_user_data().await.map_err(to_napi_error)?;
+        let mut lists: HashMap<&str, HashSet<String>> = HashMap::new();
+        let mut read_at = HashMap::new();
+        for operation in operations {
+            let list_id = operation.list_id.as_deref().unwrap_or_default();
+            let item_id = operation.list_item_id.as_deref().unwrap_or_default();
+            let items = match lists.entry(list_id) {
+                std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    let list = find_shopping_list(&data, list_id)?;
+                    read_at.insert(list_id.to_string(), list.logical_clock_time);
+                    entry.insert(list.items.iter().map(|i| i.identifier.clone()).collect())
+                }
+            };
+            if operation.list_item.is_some() {
+                items.insert(item_id.to_string());
+            } else if !items.contains(item_id) {
+                return Err(to_napi_error(AnyListError::NotFound(format!(
+                    "Item with ID {} not found in list {}",
+                    item_id, list_id
+                ))));
+            }
+        }
+        Ok(read_at)
+    }
+
+    /// Report any list AnyList's response shows has drifted, once
+    /// `setDriftDetection` is called, and forget the cached lists if so
+    fn verify(
+        &self,
+        response: &[u8],
+        operations: &[PbListOperation],
+        read_at: &HashMap<String, Option<u64>>,
+    ) {
+        let drift_events = self.drift_events.lock().unwrap();
+        let Some(callback) = drift_events.as_ref() else {
+            return;
+        };
+        let events = drift::detect(response, operations, read_at);
+        if events.is_empty() {
+            return;
+        }
+        self.cache
+            .lock()
+            .unwrap()
+            .invalidate(Some(CacheEntity::Lists));
+        for event in events {
+            callback.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+        }
+    }
+}
+
+/// Re-reads the lists whenever AnyList says they changed, or every poll
+/// interval while the realtime channel is down, and reports what changed
+struct ListWatcher {
+    client: Arc<RsClient>,
+    lists: Vec<List>,
+    sync: Option<RealtimeSync>,
+    changed: tokio::sync::mpsc::UnboundedReceiver<()>,
+    poll_interval: Duration,
+    stop: Arc<tokio::sync::Notify>,
+    realtime: Arc<AtomicBool>,
+    sink: ChangeSink,
+    sync_state: Arc<Mutex<status::SyncState>>,
+}
+
+impl ListWatcher {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                _ = self.stop.notified() => break,
+                Some(()) = self.changed.recv() => {}
+                _ = tokio::time::sleep(self.poll_interval) => {
+                    let connected = match &self.sync {
+                        Some(sync) => sync.is_connected().await,
+                        None => false,
+                    };
+                    self.realtime.store(connected, Ordering::Relaxed);
+                    if connected {
+                        continue;
+                    }
+                }
+            }
+            // One read covers every notice that arrived in the meantime
+            while self.changed.try_recv().is_ok() {}
+
+            // Failed reads are retried at the next notice or poll
+            let latest = match self.client.get_lists().await.map_err(to_napi_error) {
+                Ok(latest) => latest,
+                Err(e) => {
+                    let operation = match self.sink {
+                        ChangeSink::Callback(_) => "subscribe",
+                        ChangeSink::Webhook(_) => "startWebhookBridge",
+                    };
+                    self.sync_state.lock().unwrap().failed(operation, &e);
+                    continue;
+                }
+            };
+            self.sync_state.lock().unwrap().fetched(CacheEntity::Lists);
+            let latest: Vec<List> = latest.iter().map(List::from).collect();
+            for event in changes::diff_lists(&self.lists, &latest) {
+                match &self.sink {
+                    ChangeSink::Callback(callback) => {
+                        callback.call(event, ThreadsafeFunctionCallMode::NonBlocking);
+                    }
+                    ChangeSink::Webhook(webhook) => webhook.deliver(event).await,
+                }
+            }
+            self.lists = latest;
+        }
+
+        if let Some(sync) = &mut self.sync {
+            let _ = sync.disconnect().await;
+        }
+        self.realtime.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Re-reads one list every poll interval and reports each set of changes
+struct ListPoller {
+    client: Arc<RsClient>,
+    list: List,
+    poll_interval: Duration,
+    stop: Arc<tokio::sync::Notify>,
+    callback: ListChangesCallback,
+}
 
-        Ok(AnyListClient { inner: client })
+impl ListPoller {
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                _ = self.stop.notified() => break,
+                _ = tokio::time::sleep(self.poll_interval) => {}
+            }
+
+            // Failed reads are retried at the next poll
+            let Ok(latest) = self.client.get_list_by_id(&self.list.id).await else {
+                continue;
+            };
+            let latest = List::from(&latest);
+            if let Some(changes) = changes::diff_list(&self.list, &latest) {
+                self.callback
+                    .call(changes, ThreadsafeFunctionCallMode::NonBlocking);
+            }
+            self.list = latest;
+        }
+    }
+}
+
+/// A recipe ingredient
+#[napi(object)]
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Ingredient {
+    pub name: String,
+    pub quantity: Option<String>,
+    pub note: Option<String>,
+    /// True for section headers, e.g. "For the sauce"
+    pub is_header: Option<bool>,
+    /// The recipe this ingredient is made from, when it's marked "see recipe"
+    /// (as its note, or after its name: "Pizza dough — see recipe") and names
+    /// another recipe
+    pub recipe_id: Option<String>,
+}
+
+impl From<&PbIngredient> for Ingredient {
+    fn from(ingredient: &PbIngredient) -> Self {
+        let name = ingredient.name.as_deref().unwrap_or_default();
+        let header = header_name(name);
+
+        Ingredient {
+            name: header.unwrap_or(name).to_string(),
+            quantity: ingredient.quantity.clone(),
+            note: ingredient.note.clone(),
+            is_header: Some(header.is_some()),
+            recipe_id: None,
+        }
+    }
+}
+
+/// What a recipe is made of
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RecipeKind {
+    /// Ingredients and steps
+    Standard,
+    /// Only photos, e.g. of a cookbook page
+    Photo,
+}
+
+/// A recipe
+#[napi(object)]
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipe {
+    pub id: String,
+    pub name: String,
+    pub ingredients: Vec<Ingredient>,
+    pub preparation_steps: Vec<String>,
+    pub note: Option<String>,
+    pub source_name: Option<String>,
+    pub source_url: Option<String>,
+    pub servings: Option<String>,
+    pub prep_time: Option<i32>,
+    pub cook_time: Option<i32>,
+    /// Prep and cook time together, in minutes
+    pub total_time: Option<i32>,
+    pub rating: Option<i32>,
+    pub nutritional_info: Option<String>,
+    pub photo_id: Option<String>,
+    /// All of the recipe's photos, in order
+    pub photo_ids: Vec<String>,
+    /// Whether the recipe is written out or just photos
+    pub kind: RecipeKind,
+    /// Timers mentioned in the preparation steps (e.g. "bake 25 minutes")
+    pub timers: Vec<RecipeTimer>,
+    /// Preparation steps with section headers marked and timers attached
+    pub steps: Vec<RecipeStep>,
+    /// When the recipe was added, in milliseconds since the epoch
+    pub created_at: Option<f64>,
+    /// When the recipe was last changed, in milliseconds since the epoch
+    pub updated_at: Option<f64>,
+    /// Date of the most recent meal plan event for this recipe up to today
+    /// (YYYY-MM-DD)
+    pub last_cooked_at: Option<String>,
+}
+
+/// A cooking timer mentioned in a recipe step
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeTimer {
+    /// Index of the step in `preparationSteps`
+    pub step_index: u32,
+    pub seconds: u32,
+    /// The text the timer was found in, e.g. "25 minutes"
+    pub label: String,
+}
+
+/// A preparation step, or a header starting a section of steps
+#[napi(object)]
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeStep {
+    pub text: String,
+    /// True for section headers, e.g. "Make the sauce"
+    pub is_header: bool,
+    /// Timers mentioned in this step
+    pub timers: Vec<RecipeTimer>,
+}
+
+/// Input for a preparation step
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeStepInput {
+    pub text: String,
+    /// Start a new section, e.g. "Make the sauce"
+    pub is_header: Option<bool>,
+}
+
+/// Preparation steps as AnyList stores them, from plain or structured steps
+fn step_lines(preparation_steps: Vec<String>, steps: Option<Vec<RecipeStepInput>>) -> Vec<String> {
+    let Some(steps) = steps else {
+        return preparation_steps;
+    };
+
+    steps
+        .into_iter()
+        .map(|step| match step.is_header {
+            Some(true) => format!("# {}", header_name(&step.text).unwrap_or(step.text.trim())),
+            _ => step.text,
+        })
+        .collect()
+}
+
+/// A category for organizing list items
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Category {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub sort_index: i32,
+}
+
+impl From<&RsCategory> for Category {
+    fn from(category: &RsCategory) -> Self {
+        Category {
+            id: category.id().to_string(),
+            name: category.name().to_string(),
+            icon: category.icon().map(|s| s.to_string()),
+            sort_index: category.sort_index(),
+        }
+    }
+}
+
+impl From<&PbListCategory> for Category {
+    fn from(category: &PbListCategory) -> Self {
+        Category {
+            id: category.identifier.clone().unwrap_or_default(),
+            name: category.name.clone().unwrap_or_default(),
+            icon: category.icon.clone(),
+            sort_index: category.sort_index.unwrap_or_default(),
+        }
+    }
+}
+
+/// How a category rule's pattern is compared with item names
+#[napi(string_enum = "lowercase")]
+#[derive(Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryRuleMatch {
+    /// The whole name, ignoring case
+    Exact,
+    /// The start of the name, ignoring case
+    Prefix,
+    /// A regular expression, ignoring case
+    Regex,
+}
+
+/// A rule for categorizing new items by name
+#[napi(object)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRule {
+    pub id: String,
+    pub pattern: String,
+    pub match_type: CategoryRuleMatch,
+    pub category: String,
+    /// Only apply the rule to this list
+    pub list_id: Option<String>,
+}
+
+/// A category rule to add
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRuleInput {
+    pub pattern: String,
+    /// How the pattern is compared with names (default: exact)
+    pub match_type: Option<CategoryRuleMatch>,
+    pub category: String,
+    /// Only apply the rule to this list
+    pub list_id: Option<String>,
+}
+
+/// A group of categories
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryGroup {
+    pub id: String,
+    pub name: String,
+    pub categories: Vec<Category>,
+}
+
+impl From<&RsCategoryGroup> for CategoryGroup {
+    fn from(group: &RsCategoryGroup) -> Self {
+        CategoryGroup {
+            id: group.id().to_string(),
+            name: group.name().to_string(),
+            categories: group.categories().iter().map(Category::from).collect(),
+        }
+    }
+}
+
+impl From<&PbListCategoryGroup> for CategoryGroup {
+    fn from(group: &PbListCategoryGroup) -> Self {
+        let mut categories: Vec<Category> = group.categories.iter().map(Category::from).collect();
+        categories.sort_by_key(|category| category.sort_index);
+
+        CategoryGroup {
+            id: group.identifier.clone().unwrap_or_default(),
+            name: group.name.clone().unwrap_or_default(),
+            categories,
+        }
+    }
+}
+
+/// Which items to include when listing a store's items
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemsByStoreOptions {
+    /// Include items with no store, which can be bought anywhere (default: true)
+    pub include_unassigned: Option<bool>,
+    /// Include checked (crossed off) items (default: false)
+    pub include_checked: Option<bool>,
+}
+
+/// A store for organizing where to buy items
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Store {
+    pub id: String,
+    pub name: String,
+    pub sort_index: i32,
+}
+
+impl From<&RsStore> for Store {
+    fn from(store: &RsStore) -> Self {
+        Store {
+            id: store.id().to_string(),
+            name: store.name().to_string(),
+            sort_index: store.sort_index(),
+        }
+    }
+}
+
+/// A filter for stores
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreFilter {
+    pub id: String,
+    pub name: String,
+    pub store_ids: Vec<String>,
+}
+
+impl From<&RsStoreFilter> for StoreFilter {
+    fn from(filter: &RsStoreFilter) -> Self {
+        StoreFilter {
+            id: filter.id().to_string(),
+            name: filter.name().to_string(),
+            store_ids: filter.store_ids().iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// A favourite item (starter list item)
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteItem {
+    pub id: String,
+    pub list_id: String,
+    pub name: String,
+    pub quantity: Option<String>,
+    pub details: Option<String>,
+    pub category: Option<String>,
+    /// Position in the list, once it has been arranged
+    pub sort_index: Option<i32>,
+}
+
+impl From<&RsFavouriteItem> for FavouriteItem {
+    fn from(item: &RsFavouriteItem) -> Self {
+        FavouriteItem {
+            id: item.id().to_string(),
+            list_id: item.list_id().to_string(),
+            name: item.name().to_string(),
+            quantity: item.quantity().map(|s| s.to_string()),
+            details: item.details().map(|s| s.to_string()),
+            category: item.category().map(|s| s.to_string()),
+            sort_index: None,
+        }
+    }
+}
+
+impl FavouriteItem {
+    fn from_pb(item: &PbListItem, list_id: &str) -> Self {
+        FavouriteItem {
+            id: item.identifier.clone(),
+            list_id: list_id.to_string(),
+            name: item.name.clone().unwrap_or_default(),
+            quantity: item.quantity.clone(),
+            details: item.details.clone(),
+            category: item.category.clone(),
+            sort_index: item.manual_sort_index,
+        }
+    }
+}
+
+/// A list of favourite items (starter list)
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouritesList {
+    pub id: String,
+    pub name: String,
+    pub items: Vec<FavouriteItem>,
+    pub shopping_list_id: Option<String>,
+}
+
+impl From<&PbStarterList> for FavouritesList {
+    fn from(list: &PbStarterList) -> Self {
+        FavouritesList {
+            id: list.identifier.clone(),
+            name: list.name.clone().unwrap_or_default(),
+            items: sorted_favourites(list)
+                .into_iter()
+                .map(|item| FavouriteItem::from_pb(item, &list.identifier))
+                .collect(),
+            shopping_list_id: list.list_id.clone(),
+        }
+    }
+}
+
+/// How often a favourite has been added to a shopping list
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteStats {
+    pub favourite_id: String,
+    pub name: String,
+    pub times_added: u32,
+    /// When it was last added, in milliseconds since the epoch
+    pub last_added_at: Option<f64>,
+}
+
+/// Options for adding a whole favourites list to a shopping list
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAllFavouritesOptions {
+    /// Leave out favourites already on the shopping list, unchecking any that
+    /// were crossed off instead of adding them again (default: false)
+    pub skip_existing: Option<bool>,
+}
+
+/// A favourite to add to a favourites list
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouriteInput {
+    pub name: String,
+    pub quantity: Option<String>,
+    pub details: Option<String>,
+    pub category: Option<String>,
+}
+
+/// Fields to change on a favourite; omitted fields are left as they are
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FavouritePatch {
+    pub name: Option<String>,
+    pub quantity: Option<String>,
+    pub details: Option<String>,
+    pub category: Option<String>,
+}
+
+/// What a meal plan event is for
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MealPlanEventType {
+    /// A planned recipe
+    Recipe,
+    /// Leftovers of an earlier recipe event, which need no shopping
+    Leftovers,
+    /// A note for the day with no recipe (e.g. "Eating out")
+    Note,
+}
+
+/// Title that marks a recipe event as leftovers
+const LEFTOVERS_TITLE: &str = "Leftovers";
+
+impl MealPlanEventType {
+    fn of(recipe_id: Option<&str>, title: Option<&str>) -> Self {
+        match (recipe_id, title) {
+            (Some(_), Some(title)) if title.eq_ignore_ascii_case(LEFTOVERS_TITLE) => {
+                MealPlanEventType::Leftovers
+            }
+            (Some(_), _) => MealPlanEventType::Recipe,
+            (None, _) => MealPlanEventType::Note,
+        }
+    }
+}
+
+/// A meal plan event
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanEvent {
+    pub id: String,
+    pub date: String,
+    pub title: Option<String>,
+    pub recipe_id: Option<String>,
+    pub label_id: Option<String>,
+    pub details: Option<String>,
+    /// Position among the day's events; lower comes first
+    pub sort_index: Option<i32>,
+    /// Whether the event plans a recipe or is a note (`title` holds the note)
+    pub event_type: MealPlanEventType,
+}
+
+/// Input for creating a meal plan event
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanEventInput {
+    pub date: MealPlanDate,
+    pub recipe_id: Option<String>,
+    /// Title, for events without a recipe
+    pub title: Option<String>,
+    pub label_id: Option<String>,
+    pub details: Option<String>,
+}
+
+/// Which planned meals to shop for
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanIngredientsOptions {
+    /// Leave out events for these recipes
+    pub exclude_recipe_ids: Option<Vec<String>>,
+    /// Leave out events with these labels (e.g. breakfast)
+    pub exclude_label_ids: Option<Vec<String>>,
+    /// Leave out events before today, which have already been cooked
+    pub exclude_cooked: Option<bool>,
+    /// Scale factors keyed by event ID, used instead of the events' own
+    pub scale_factors: Option<HashMap<String, f64>>,
+}
+
+/// How often a recipe was planned
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeFrequency {
+    pub recipe_id: String,
+    /// The recipe's name, if it still exists
+    pub name: Option<String>,
+    pub count: u32,
+}
+
+/// How many events had a label
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelFrequency {
+    /// The label's ID, or none for unlabelled events
+    pub label_id: Option<String>,
+    pub name: Option<String>,
+    pub count: u32,
+}
+
+/// A summary of the meal plan over a date range
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanStats {
+    pub event_count: u32,
+    /// Planned recipes, most frequent first
+    pub recipes: Vec<RecipeFrequency>,
+    /// Event labels, most frequent first
+    pub labels: Vec<LabelFrequency>,
+    /// Days (YYYY-MM-DD) with no event labelled "Dinner", or with no events at
+    /// all if there is no such label
+    pub days_without_dinner: Vec<String>,
+}
+
+/// Calories (kcal) and macronutrients (grams)
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionTotals {
+    pub calories: f64,
+    pub protein: f64,
+    pub carbohydrates: f64,
+    pub fat: f64,
+}
+
+impl NutritionTotals {
+    fn add(&mut self, other: &NutritionTotals) {
+        self.calories += other.calories;
+        self.protein += other.protein;
+        self.carbohydrates += other.carbohydrates;
+        self.fat += other.fat;
+    }
+
+    /// Divide every total, rounding to one decimal place
+    fn divided(&self, by: f64) -> NutritionTotals {
+        let round = |value: f64| (value / by * 10.0).round() / 10.0;
+        NutritionTotals {
+            calories: round(self.calories),
+            protein: round(self.protein),
+            carbohydrates: round(self.carbohydrates),
+            fat: round(self.fat),
+        }
+    }
+}
+
+/// Planned nutrition for one day
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DayNutrition {
+    /// Date (YYYY-MM-DD)
+    pub date: String,
+    /// One serving of each planned recipe with nutrition information
+    pub totals: NutritionTotals,
+    /// Number of planned recipes counted in the totals
+    pub recipe_count: u32,
+}
+
+/// Planned nutrition over a date range
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanNutrition {
+    /// Every day in the range, in order
+    pub days: Vec<DayNutrition>,
+    pub total: NutritionTotals,
+    pub daily_average: NutritionTotals,
+    /// Planned recipes whose nutrition couldn't be read, so aren't counted
+    pub recipes_without_nutrition: Vec<String>,
+}
+
+/// Output format for a meal plan digest
+#[napi(string_enum = "lowercase")]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+/// Options for suggesting a week of meals
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestMealPlanOptions {
+    /// Pick recipes from this collection (default: all recipes)
+    pub collection_id: Option<String>,
+    /// Skip recipes planned this many days before the week (default 14)
+    pub avoid_recent_days: Option<u32>,
+    /// Label for the new events (e.g. dinner); days that already have an event
+    /// with this label, or any recipe when no label is given, are skipped
+    pub label_id: Option<String>,
+}
+
+/// Date range for searching meal plan events
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMealPlanOptions {
+    /// Earliest date to search
+    pub start_date: Option<MealPlanDate>,
+    /// Latest date to search
+    pub end_date: Option<MealPlanDate>,
+}
+
+/// A label for meal plan events (e.g. "Dinner")
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanLabel {
+    pub id: String,
+    pub name: String,
+    /// Color as a hex string, e.g. "ff9500"
+    pub hex_color: Option<String>,
+}
+
+/// The account's meal plan calendar
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MealPlanCalendar {
+    pub id: String,
+    /// Labels in display order
+    pub labels: Vec<MealPlanLabel>,
+}
+
+impl From<&PbCalendarEvent> for MealPlanEvent {
+    fn from(event: &PbCalendarEvent) -> Self {
+        MealPlanEvent {
+            id: event.identifier.clone(),
+            date: event.date.clone().unwrap_or_default(),
+            title: event.title.clone(),
+            recipe_id: event.recipe_id.clone(),
+            label_id: event.label_id.clone(),
+            details: event.details.clone(),
+            sort_index: event.order_added_sort_index,
+            event_type: MealPlanEventType::of(event.recipe_id.as_deref(), event.title.as_deref()),
+        }
+    }
+}
+
+impl From<&RsMealPlanEvent> for MealPlanEvent {
+    fn from(event: &RsMealPlanEvent) -> Self {
+        MealPlanEvent {
+            id: event.id().to_string(),
+            date: event.date().to_string(),
+            title: event.title().map(|s| s.to_string()),
+            recipe_id: event.recipe_id().map(|s| s.to_string()),
+            label_id: event.label_id().map(|s| s.to_string()),
+            details: event.details().map(|s| s.to_string()),
+            sort_index: None,
+            event_type: MealPlanEventType::of(event.recipe_id(), event.title()),
+        }
+    }
+}
+
+/// iCalendar sync information
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ICalendarInfo {
+    pub enabled: bool,
+    pub url: Option<String>,
+    pub token: Option<String>,
+}
+
+/// An event read from the hosted iCalendar feed
+#[napi(object)]
+#[derive(Serialize, JsonSchema, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ICalendarEvent {
+    pub uid: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    /// Start date (YYYY-MM-DD), or date and time for timed events
+    pub start: String,
+    /// End date, exclusive for all-day events
+    pub end: Option<String>,
+}
+
+impl From<&RsICalendarInfo> for ICalendarInfo {
+    fn from(info: &RsICalendarInfo) -> Self {
+        ICalendarInfo {
+            enabled: info.enabled(),
+            url: info.url().map(|s| s.to_string()),
+            token: info.token().map(|s| s.to_string()),
+        }
+    }
+}
+
+/// A collection of recipes
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeCollection {
+    pub id: String,
+    pub name: String,
+    pub recipe_ids: Vec<String>,
+}
+
+impl From<&RsRecipeCollection> for RecipeCollection {
+    fn from(collection: &RsRecipeCollection) -> Self {
+        RecipeCollection {
+            id: collection.id().to_string(),
+            name: collection.name().to_string(),
+            recipe_ids: collection
+                .recipe_ids()
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// A prep or cook time: minutes, or an ISO 8601 duration like "PT1H30M"
+#[derive(Serialize, JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum RecipeTime {
+    Minutes(i32),
+    Duration(String),
+}
+
+impl RecipeTime {
+    fn minutes(&self) -> Result<i32> {
+        match self {
+            RecipeTime::Minutes(minutes) if *minutes >= 0 => Ok(*minutes),
+            RecipeTime::Minutes(minutes) => Err(Error::new(
+                Status::InvalidArg,
+                format!("Time must not be negative, got {}", minutes),
+            )),
+            RecipeTime::Duration(duration) => {
+                schema_org::parse_minutes(duration).ok_or_else(|| {
+                    Error::new(
+                        Status::InvalidArg,
+                        format!("Invalid duration: {:?}", duration),
+                    )
+                })
+            }
+        }
+    }
+}
+
+impl From<i32> for RecipeTime {
+    fn from(minutes: i32) -> Self {
+        RecipeTime::Minutes(minutes)
+    }
+}
+
+impl TypeName for RecipeTime {
+    fn type_name() -> &'static str {
+        "number | string"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Unknown
+    }
+}
+
+impl FromNapiValue for RecipeTime {
+    unsafe fn from_napi_value(env: sys::napi_env, value: sys::napi_value) -> Result<Self> {
+        Ok(match Either::<i32, String>::from_napi_value(env, value)? {
+            Either::A(minutes) => RecipeTime::Minutes(minutes),
+            Either::B(duration) => RecipeTime::Duration(duration),
+        })
+    }
+}
+
+impl ToNapiValue for RecipeTime {
+    unsafe fn to_napi_value(env: sys::napi_env, value: Self) -> Result<sys::napi_value> {
+        match value {
+            RecipeTime::Minutes(minutes) => i32::to_napi_value(env, minutes),
+            RecipeTime::Duration(duration) => String::to_napi_value(env, duration),
+        }
+    }
+}
+
+/// Minutes from an optional prep or cook time
+fn time_minutes(time: Option<&RecipeTime>) -> Result<Option<i32>> {
+    time.map(RecipeTime::minutes).transpose()
+}
+
+/// Options for creating a new recipe
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRecipeOptions {
+    /// Recipe name (required)
+    pub name: String,
+    /// List of ingredients
+    pub ingredients: Vec<IngredientInput>,
+    /// Preparation/cooking steps
+    pub preparation_steps: Vec<String>,
+    /// Structured steps with section headers, used instead of
+    /// `preparationSteps` when given
+    pub steps: Option<Vec<RecipeStepInput>>,
+    /// Recipe notes/description
+    pub note: Option<String>,
+    /// Source name (e.g., "Web", "Cookbook")
+    pub source_name: Option<String>,
+    /// Source URL
+    pub source_url: Option<String>,
+    /// Serving size (e.g., "4 servings")
+    pub servings: Option<String>,
+    /// Prep time in minutes, or an ISO 8601 duration (e.g. "PT15M")
+    pub prep_time: Option<RecipeTime>,
+    /// Cook time in minutes, or an ISO 8601 duration (e.g. "PT1H")
+    pub cook_time: Option<RecipeTime>,
+    /// Rating from 1-5
+    pub rating: Option<i32>,
+    /// Nutritional information
+    pub nutritional_info: Option<String>,
+    /// Photo ID (from upload_photo)
+    pub photo_id: Option<String>,
+    /// URL of an image to download and use as the photo, when `photoId`
+    /// isn't given
+    pub photo_url: Option<String>,
+}
+
+/// A meal plan date: a day (YYYY-MM-DD), an ISO 8601 date-time, a JavaScript
+/// `Date`, or milliseconds since the epoch
+/// Instants fall on the day they are in the client's time zone (see `setTimeZone`)
+#[derive(Serialize, JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum MealPlanDate {
+    Day(String),
+    /// Milliseconds since the Unix epoch
+    Timestamp(f64),
+}
+
+impl From<String> for MealPlanDate {
+    fn from(day: String) -> Self {
+        MealPlanDate::Day(day)
+    }
+}
+
+impl TypeName for MealPlanDate {
+    fn type_name() -> &'static str {
+        "Date | number | string"
+    }
+
+    fn value_type() -> ValueType {
+        ValueType::Unknown
+    }
+}
+
+impl FromNapiValue for MealPlanDate {
+    unsafe fn from_napi_value(env: sys::napi_env, value: sys::napi_value) -> Result<Self> {
+        let mut is_date = false;
+        check_status!(sys::napi_is_date(env, value, &mut is_date))?;
+        if !is_date {
+            return Ok(match Either::<f64, String>::from_napi_value(env, value)? {
+                Either::A(timestamp) => MealPlanDate::Timestamp(timestamp),
+                Either::B(day) => MealPlanDate::Day(day),
+            });
+        }
+
+        let mut timestamp = 0.0;
+        check_status!(sys::napi_get_date_value(env, value, &mut timestamp))?;
+        Ok(MealPlanDate::Timestamp(timestamp))
+    }
+}
+
+impl ToNapiValue for MealPlanDate {
+    unsafe fn to_napi_value(env: sys::napi_env, value: Self) -> Result<sys::napi_value> {
+        match value {
+            MealPlanDate::Day(day) => String::to_napi_value(env, day),
+            MealPlanDate::Timestamp(timestamp) => {
+                let mut date = std::ptr::null_mut();
+                check_status!(sys::napi_create_date(env, timestamp, &mut date))?;
+                Ok(date)
+            }
+        }
+    }
+}
+
+/// Parse a YYYY-MM-DD date
+fn parse_date(date: &str) -> Result<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        Error::new(
+            Status::InvalidArg,
+            format!("Invalid date '{}', expected YYYY-MM-DD", date),
+        )
+    })
+}
+
+/// Check that a date range doesn't end before it starts
+fn check_date_order(start: chrono::NaiveDate, end: chrono::NaiveDate) -> Result<()> {
+    if end < start {
+        return Err(Error::new(
+            Status::InvalidArg,
+            format!(
+                "End date {} must not be before the start date {}",
+                end, start
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether a meal plan event falls between two dates (inclusive)
+fn event_in_range(
+    event: &PbCalendarEvent,
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> bool {
+    event
+        .date
+        .as_deref()
+        .and_then(|date| parse_date(date).ok())
+        .is_some_and(|date| date >= start && date <= end)
+}
+
+/// Recipes with photos but no ingredients or steps are photo recipes
+fn recipe_kind(recipe: &PbRecipe) -> RecipeKind {
+    let has_photos = !recipe.photo_ids.is_empty() || !recipe.photo_urls.is_empty();
+    if has_photos && recipe.ingredients.is_empty() && recipe.preparation_steps.is_empty() {
+        RecipeKind::Photo
+    } else {
+        RecipeKind::Standard
+    }
+}
+
+/// Split preparation steps into headers and steps, attaching each step's timers
+fn recipe_steps(preparation_steps: &[String]) -> Vec<RecipeStep> {
+    let timers = timers::find_timers(preparation_steps);
+
+    preparation_steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let header = header_name(step);
+            RecipeStep {
+                text: header.unwrap_or(step).to_string(),
+                is_header: header.is_some(),
+                timers: timers
+                    .iter()
+                    .filter(|timer| timer.step_index as usize == i)
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Changes to a recipe; omitted fields are left as they are
+#[napi(object)]
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipePatch {
+    pub name: Option<String>,
+    pub ingredients: Option<Vec<IngredientInput>>,
+    pub preparation_steps: Option<Vec<String>>,
+    /// Structured steps, used instead of `preparationSteps` when given
+    pub steps: Option<Vec<RecipeStepInput>>,
+    pub note: Option<String>,
+    pub source_name: Option<String>,
+    pub source_url: Option<String>,
+    pub servings: Option<String>,
+    /// Minutes, or an ISO 8601 duration
+    pub prep_time: Option<RecipeTime>,
+    /// Minutes, or an ISO 8601 duration
+    pub cook_time: Option<RecipeTime>,
+    /// Rating from 1-5, or 0 to clear it
+    pub rating: Option<i32>,
+    pub nutritional_info: Option<String>,
+    pub photo_id: Option<String>,
+    /// URL of an image to download and use as the photo, when `photoId`
+    /// isn't given
+    pub photo_url: Option<String>,
+}
+
+impl RecipePatch {
+    fn apply(self, recipe: &mut PbRecipe) {
+        fn set<T>(field: &mut Option<T>, value: Option<T>) {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+
+        set(&mut recipe.name, self.name);
+        if let Some(ingredients) = self.ingredients {
+            recipe.ingredients = ingredients.iter().map(PbIngredient::from).collect();
+        }
+        if self.preparation_steps.is_some() || self.steps.is_some() {
+            recipe.preparation_steps =
+                step_lines(self.preparation_steps.unwrap_or_default(), self.steps);
+        }
+        set(&mut recipe.note, self.note);
+        set(&mut recipe.source_name, self.source_name);
+        set(&mut recipe.source_url, self.source_url);
+        set(&mut recipe.servings, self.servings);
+        // Times are checked by `patch_recipe` before the patch is applied
+        set(
+            &mut recipe.prep_time,
+            time_minutes(self.prep_time.as_ref()).ok().flatten(),
+        );
+        set(
+            &mut recipe.cook_time,
+            time_minutes(self.cook_time.as_ref()).ok().flatten(),
+        );
+        if let Some(rating) = self.rating {
+            recipe.rating = (rating > 0).then_some(rating);
+        }
+        set(&mut recipe.nutritional_info, self.nutritional_info);
+        if let Some(photo_id) = self.photo_id {
+            recipe.photo_ids = vec![photo_id];
+            recipe.photo_urls.clear();
+        }
+    }
+}
+
+impl From<&PbRecipe> for Recipe {
+    fn from(recipe: &PbRecipe) -> Self {
+        Recipe {
+            id: recipe.identifier.clone(),
+            name: recipe.name.clone().unwrap_or_default(),
+            ingredients: recipe
+                .ingredients
+                .iter()
+                .filter(|i| i.name.is_some())
+                .map(Ingredient::from)
+                .collect(),
+            preparation_steps: recipe.preparation_steps.clone(),
+            note: recipe.note.clone(),
+            source_name: recipe.source_name.clone(),
+            source_url: recipe.source_url.clone(),
+            servings: recipe.servings.clone(),
+            prep_time: recipe.prep_time,
+            cook_time: recipe.cook_time,
+            total_time: match (recipe.prep_time, recipe.cook_time) {
+                (Some(prep), Some(cook)) => Some(prep + cook),
+                (prep, cook) => prep.or(cook),
+            },
+            rating: recipe.rating,
+            nutritional_info: recipe.nutritional_info.clone(),
+            photo_id: recipe.photo_ids.first().cloned(),
+            photo_ids: recipe.photo_ids.clone(),
+            kind: recipe_kind(recipe),
+            timers: timers::find_timers(&recipe.preparation_steps),
+            steps: recipe_steps(&recipe.preparation_steps),
+            created_at: recipe.creation_timestamp.map(|t| t * 1000.0),
+            updated_at: recipe.timestamp.map(|t| t * 1000.0),
+            last_cooked_at: None,
+        }
+    }
+}
+
+/// The main AnyList client for interacting with the API
+#[napi]
+pub struct AnyListClient {
+    inner: Arc<RsClient>,
+    /// Items deleted through this client, newest last, keyed by list ID
+    trash: Mutex<HashMap<String, Vec<ListItem>>>,
+    /// Recipes as they were before each change made through this client,
+    /// oldest first, keyed by recipe ID
+    recipe_history: Mutex<HashMap<String, Vec<RecipeSnapshot>>>,
+    /// Meal plan templates saved through this client, oldest first
+    meal_plan_templates: Mutex<Vec<MealPlanTemplateSnapshot>>,
+    /// Serializes get-or-create calls so concurrent callers can't race each other
+    ensure_lock: tokio::sync::Mutex<()>,
+    /// Time zone that `Date`s are read in, or `None` for the system's
+    time_zone: Arc<Mutex<Option<chrono_tz::Tz>>>,
+    /// Rules that categorize items added without a category
+    category_rules: Mutex<rules::RuleSet>,
+    /// How often each favourite was added to a shopping list through this
+    /// client, keyed by favourite ID
+    favourite_usage: Mutex<HashMap<String, FavouriteUsage>>,
+    /// Lists and recipes kept for `setCacheOptions`
+    cache: Arc<Mutex<cache::Cache>>,
+    /// The tasks refreshing the cache, once `startAutoRefresh` is called
+    auto_refresh: Mutex<Vec<tokio::task::JoinHandle<()>>>,
+    /// Changes waiting to be sent, once `setOfflineQueue` is called
+    offline_queue: Mutex<queue::OperationQueue>,
+    queue_events: Mutex<Option<QueueEventCallback>>,
+    conflict_resolver: Arc<Mutex<Option<Arc<ConflictResolver>>>>,
+    /// Keeps queued changes going out one at a time, in order
+    flush_lock: tokio::sync::Mutex<()>,
+    /// The local copy of the lists, once `setOptimisticUpdates` is called
+    optimistic: Mutex<Option<optimistic::Optimistic>>,
+    /// Where changes are recorded, once `setJournalFile` is called
+    journal: Arc<Mutex<journal::Journal>>,
+    /// What `getSyncStatus` reports, shared with subscriptions
+    sync_state: Arc<Mutex<status::SyncState>>,
+    /// Where batches report drift, once `setDriftDetection` is called
+    drift_events: Arc<Mutex<Option<DriftEventCallback>>>,
+    /// Fetches in flight, shared by callers that ask for the same thing at
+    /// the same time; lists by ID are keyed by list ID
+    lists_fetch: coalesce::Coalesced<Vec<List>>,
+    list_fetch: coalesce::Coalesced<List>,
+    recipes_fetch: coalesce::Coalesced<Vec<Recipe>>,
+    /// Keeps changes to each list going out one at a time, shared with
+    /// batches and optimistic updates
+    list_locks: Arc<list_locks::ListLocks>,
+}
+
+/// How many deleted items are remembered per list
+const TRASH_LIMIT: usize = 100;
+
+/// How many earlier versions are remembered per recipe
+const RECIPE_HISTORY_LIMIT: usize = 20;
+
+/// How often a favourite has been added to a shopping list
+struct FavouriteUsage {
+    times_added: u32,
+    last_added_at: f64,
+}
+
+/// A recipe as it was before a change
+struct RecipeSnapshot {
+    id: String,
+    saved_at: f64,
+    recipe: PbRecipe,
+}
+
+/// A saved week of meal plan events, each with its day of the week
+struct MealPlanTemplateSnapshot {
+    id: String,
+    name: String,
+    events: Vec<(u32, PbCalendarEvent)>,
+}
+
+impl From<&MealPlanTemplateSnapshot> for MealPlanTemplate {
+    fn from(template: &MealPlanTemplateSnapshot) -> Self {
+        MealPlanTemplate {
+            id: template.id.clone(),
+            name: template.name.clone(),
+            events: template
+                .events
+                .iter()
+                .map(|(day, event)| MealPlanTemplateEvent {
+                    day: *day,
+                    title: event.title.clone(),
+                    recipe_id: event.recipe_id.clone(),
+                    label_id: event.label_id.clone(),
+                    details: event.details.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[napi]
+impl AnyListClient {
+    /// Login to AnyList with email and password
+    #[napi]
+    pub async fn login(email: String, password: String) -> Result<AnyListClient> {
+        let client = RsClient::login(&email, &password)
+            .await
+            .map_err(to_napi_error)?;
+
+        Ok(AnyListClient::new(client))
+    }
+
+    /// Create a client from saved tokens (for resuming sessions)
+    #[napi]
+    pub fn from_tokens(tokens: SavedTokens) -> Result<AnyListClient> {
+        let rs_tokens: RsSavedTokens = tokens.into();
+        let client = RsClient::from_tokens(rs_tokens).map_err(to_napi_error)?;
+
+        Ok(AnyListClient::new(client))
+    }
+
+    /// Get the saved tokens for this session
+    #[napi]
+    pub fn get_tokens(&self) -> Result<SavedTokens> {
+        let tokens = self.inner.export_tokens().map_err(to_napi_error)?;
+        Ok(tokens.into())
+    }
+
+    /// Get all lists
+    #[napi]
+    pub async fn get_lists(&self) -> Result<Vec<List>> {
+        let optimistic = self.optimistic();
+        if let Some(lists) = optimistic.as_ref().and_then(|o| o.lists()) {
+            return Ok(lists);
+        }
+
+        let (cached, generation) = {
+            let cache = self.cache.lock().unwrap();
+            (cache.lists(), cache.generation(CacheEntity::Lists))
+        };
+        let lists = match cached {
+            Some(lists) => lists,
+            None => {
+                // A fetch that started before the cache was last invalidated
+                // isn't shared, since it may not see the change
+                self.lists_fetch
+                    .get(&generation.to_string(), || async {
+                        let lists = self.inner.get_lists().await.map_err(to_napi_error);
+                        let lists = self.note_fetch("getLists", CacheEntity::Lists, lists)?;
+                        let lists: Vec<List> = lists.iter().map(List::from).collect();
+                        self.cache.lock().unwrap().put_lists(generation, &lists);
+                        Ok(lists)
+                    })
+                    .await?
+            }
+        };
+
+        if let Some(optimistic) = optimistic {
+            optimistic.put_lists(&lists);
+        }
+        Ok(lists)
+    }
+
+    /// Create a new list
+    #[napi]
+    pub async fn create_list(&self, name: String) -> Result<List> {
+        self.journaled("createList", Vec::new(), async move {
+            let list = self.inner.create_list(&name).await.map_err(to_napi_error)?;
+
+            Ok(List::from(&list))
+        })
+        .await
+    }
+
+    /// Get a specific list by ID
+    #[napi]
+    pub async fn get_list_by_id(
+        &self,
+        list_id: String,
+        options: Option<GetListOptions>,
+    ) -> Result<List> {
+        let mut list = self.list(&list_id).await?;
+        if options.and_then(|o| o.include_checked) == Some(false) {
+            list.items.retain(|i| !i.checked);
+        }
+
+        Ok(list)
+    }
+
+    /// Get a page of a list's items, in the order `getListById()` returns
+    /// them, so a large list doesn't have to cross into JavaScript at once
+    /// Each page reads the list afresh, so items added or removed between
+    /// pages can shift the ones after them.
+    #[napi]
+    pub async fn get_list_items(
+        &self,
+        list_id: String,
+        options: Option<ListItemsOptions>,
+    ) -> Result<ListItemsPage> {
+        let options = options.unwrap_or_default();
+        if options.limit == Some(0) {
+            return Err(Error::new(Status::InvalidArg, "Limit must be at least 1"));
+        }
+        let list = self
+            .get_list_by_id(
+                list_id,
+                Some(GetListOptions {
+                    include_checked: options.include_checked,
+                }),
+            )
+            .await?;
+
+        let total = list.items.len();
+        let offset = (options.offset.unwrap_or(0) as usize).min(total);
+        let end = match options.limit {
+            Some(limit) => offset.saturating_add(limit as usize).min(total),
+            None => total,
+        };
+        let items: Vec<ListItem> = list
+            .items
+            .into_iter()
+            .skip(offset)
+            .take(end - offset)
+            .collect();
+
+        Ok(ListItemsPage {
+            items,
+            total: total as u32,
+            next_offset: (end < total).then_some(end as u32),
+        })
+    }
+
+    /// Get a list by name
+    #[napi]
+    pub async fn get_list_by_name(&self, name: String) -> Result<List> {
+        let list = self
+            .inner
+            .get_list_by_name(&name)
+            .await
+            .map_err(to_napi_error)?;
+
+        Ok(List::from(&list))
+    }
+
+    /// Find the list with this name, or create it if there isn't one
+    /// This isn't atomic: calls on one client never create the list twice, but
+    /// calls from different clients can each create it. When several lists have
+    /// the name, the one with the lowest ID is returned, and a list this call
+    /// created is deleted if it isn't that one, but a call that finishes before
+    /// another client creates its copy can still leave a duplicate behind
+    #[napi]
+    pub async fn find_or_create_list(&self, name: String) -> Result<List> {
+        self.journaled("findOrCreateList", Vec::new(), async move {
+            let _guard = self.ensure_lock.lock().await;
+
+            // Pick the same list as the other clients that may have created one
+            let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
+            if let Some(list) = lists
+                .iter()
+                .filter(|l| l.name() == name)
+                .min_by(|a, b| a.id().cmp(b.id()))
+            {
+                return Ok(List::from(list));
+            }
+
+            let created = self.inner.create_list(&name).await.map_err(to_napi_error)?;
+
+            let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
+            let winner = lists
+                .iter()
+                .filter(|l| l.name() == name)
+                .min_by(|a, b| a.id().cmp(b.id()));
+
+            match winner {
+                Some(list) if list.id() != created.id() => {
+                    self.inner
+                        .delete_list(created.id())
+                        .await
+                        .map_err(to_napi_error)?;
+
+                    Ok(List::from(list))
+                }
+                _ => Ok(List::from(&created)),
+            }
+        })
+        .await
+    }
+
+    /// Rename a list, along with its linked favourites list if that still has
+    /// the list's old name
+    #[napi]
+    pub async fn rename_list(&self, list_id: String, new_name: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "renameList",
+            Some(list_id.clone()),
+            vec![list_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                let old_name = find_shopping_list(&data, &list_id)?
+                    .name
+                    .clone()
+                    .unwrap_or_default();
+                let favourites = linked_favourites_list(&data, &list_id)
+                    .filter(|l| l.name.as_deref() == Some(old_name.as_str()));
+
+                self.inner
+                    .rename_list(&list_id, &new_name)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                if let Some(favourites) = favourites {
+                    self.post_starter_list_rename(&favourites.identifier, &old_name, &new_name)
+                        .await?;
+                }
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Get a single item from a list by ID
+    /// AnyList has no endpoint for one item, so this reads the list the way
+    /// `getListById` does, including changes that haven't been sent yet
+    #[napi]
+    pub async fn get_item_by_id(&self, list_id: String, item_id: String) -> Result<ListItem> {
+        self.list(&list_id)
+            .await?
+            .items
+            .into_iter()
+            .find(|i| i.id == item_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Item with ID {} not found",
+                    item_id
+                )))
+            })
+    }
+
+    /// Add an item to a list
+    #[napi]
+    pub async fn add_item(&self, list_id: String, name: String) -> Result<ListItem> {
+        self.list_change(
+            "addItem",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let category = self.rule_category(&list_id, &name);
+                self.add_item_or_queue(&list_id, &name, None, None, category)
+                    .await
+            },
+        )
+        .await
+    }
+
+    /// Add an item with details to a list
+    #[napi]
+    pub async fn add_item_with_details(
+        &self,
+        list_id: String,
+        name: String,
+        quantity: Option<String>,
+        note: Option<String>,
+        category: Option<String>,
+    ) -> Result<ListItem> {
+        self.list_change(
+            "addItemWithDetails",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let category = category.or_else(|| self.rule_category(&list_id, &name));
+                self.add_item_or_queue(&list_id, &name, quantity, note, category)
+                    .await
+            },
+        )
+        .await
+    }
+
+    /// Add an item unless an item with the same name is already on the list
+    /// A matching unchecked item is returned as-is; a matching checked item is unchecked
+    #[napi]
+    pub async fn add_item_if_not_exists(
+        &self,
+        list_id: String,
+        name: String,
+        details: Option<String>,
+    ) -> Result<ListItem> {
+        self.list_change(
+            "addItemIfNotExists",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let list = self
+                    .inner
+                    .get_list_by_id(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                let normalized = normalize_name(&name);
+                let matches: Vec<&RsListItem> = list
+                    .items()
+                    .iter()
+                    .filter(|i| normalize_name(i.name()) == normalized)
+                    .collect();
+
+                if let Some(item) = matches.iter().find(|i| !i.is_checked()) {
+                    return Ok(ListItem::from(*item));
+                }
+
+                if let Some(item) = matches.first() {
+                    self.inner
+                        .uncheck_item(&list_id, item.id())
+                        .await
+                        .map_err(to_napi_error)?;
+
+                    let mut item = ListItem::from(*item);
+                    item.checked = false;
+                    return Ok(item);
+                }
+
+                let category = self.rule_category(&list_id, &name);
+                let item = self
+                    .inner
+                    .add_item_with_details(
+                        &list_id,
+                        &name,
+                        None,
+                        details.as_deref(),
+                        category.as_deref(),
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(ListItem::from(&item))
+            },
+        )
+        .await
+    }
+
+    /// Change only the supplied fields of an item
+    #[napi]
+    pub async fn patch_item(
+        &self,
+        list_id: String,
+        item_id: String,
+        patch: ItemPatch,
+    ) -> Result<ListItem> {
+        self.list_change(
+            "patchItem",
+            list_id.clone(),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                let list = self
+                    .inner
+                    .get_list_by_id(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                let existing = list
+                    .items()
+                    .iter()
+                    .find(|i| i.id() == item_id)
+                    .ok_or_else(|| Error::new(Status::GenericFailure, "Item not found"))?;
+
+                self.apply_item_patch(&list_id, existing, patch).await
+            },
+        )
+        .await
+    }
+
+    /// Update the item with a matching name, or create it if the list has none
+    /// The matched item is unchecked unless the patch says otherwise
+    #[napi]
+    pub async fn upsert_item(
+        &self,
+        list_id: String,
+        name: String,
+        patch: ItemPatch,
+    ) -> Result<UpsertItemResult> {
+        self.list_change(
+            "upsertItem",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let list = self
+                    .inner
+                    .get_list_by_id(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                let normalized = normalize_name(&name);
+                let existing = list
+                    .items()
+                    .iter()
+                    .find(|i| normalize_name(i.name()) == normalized);
+
+                let Some(existing) = existing else {
+                    let category = patch
+                        .category
+                        .or_else(|| self.rule_category(&list_id, &name));
+                    let item = self
+                        .inner
+                        .add_item_with_details(
+                            &list_id,
+                            &name,
+                            patch.quantity.as_deref(),
+                            patch.note.as_deref(),
+                            category.as_deref(),
+                        )
+                        .await
+                        .map_err(to_napi_error)?;
+
+                    return Ok(UpsertItemResult {
+                        action: UpsertAction::Created,
+                        item: ListItem::from(&item),
+                    });
+                };
+
+                let mut patch = patch;
+                patch.checked.get_or_insert(false);
+                let item = self.apply_item_patch(&list_id, existing, patch).await?;
+
+                Ok(UpsertItemResult {
+                    action: UpsertAction::Updated,
+                    item,
+                })
+            },
+        )
+        .await
+    }
+
+    /// Delete an item from a list
+    #[napi]
+    pub async fn delete_item(&self, list_id: String, item_id: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteItem",
+            Some(list_id.clone()),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                match self.remove_items(&list_id, |i| i.id() == item_id).await? {
+                    0 => Err(no_matching_items()),
+                    _ => Ok(()),
+                }
+            },
+        )
+        .await
+    }
+
+    /// Cross off (check) an item
+    #[napi]
+    pub async fn cross_off_item(
+        &self,
+        list_id: String,
+        item_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "crossOffItem",
+            Some(list_id.clone()),
+            vec![list_id.clone(), item_id.clone()],
+            async move { self.set_checked_or_queue(&list_id, &item_id, true).await },
+        )
+        .await
+    }
+
+    /// Uncheck an item
+    #[napi]
+    pub async fn uncheck_item(&self, list_id: String, item_id: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "uncheckItem",
+            Some(list_id.clone()),
+            vec![list_id.clone(), item_id.clone()],
+            async move { self.set_checked_or_queue(&list_id, &item_id, false).await },
+        )
+        .await
+    }
+
+    /// Update an existing item
+    #[napi]
+    pub async fn update_item(
+        &self,
+        list_id: String,
+        item_id: String,
+        name: String,
+        quantity: Option<String>,
+        note: Option<String>,
+        category: Option<String>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "updateItem",
+            Some(list_id.clone()),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                self.inner
+                    .update_item(
+                        &list_id,
+                        &item_id,
+                        &name,
+                        quantity.as_deref(),
+                        note.as_deref(),
+                        category.as_deref(),
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Delete multiple items at once
+    #[napi]
+    pub async fn bulk_delete_items(
+        &self,
+        list_id: String,
+        item_ids: Vec<String>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "bulkDeleteItems",
+            Some(list_id.clone()),
+            [list_id.clone()]
+                .into_iter()
+                .chain(item_ids.clone())
+                .collect(),
+            async move {
+                if item_ids.is_empty() {
+                    return Ok(());
+                }
+
+                match self
+                    .remove_items(&list_id, |i| item_ids.iter().any(|id| id == i.id()))
+                    .await?
+                {
+                    0 => Err(no_matching_items()),
+                    _ => Ok(()),
+                }
+            },
+        )
+        .await
+    }
+
+    /// Cross off (check) multiple items at once
+    #[napi]
+    pub async fn bulk_cross_off_items(
+        &self,
+        list_id: String,
+        item_ids: Vec<String>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "bulkCrossOffItems",
+            Some(list_id.clone()),
+            [list_id.clone()]
+                .into_iter()
+                .chain(item_ids.clone())
+                .collect(),
+            async move { self.set_items_checked(&list_id, &item_ids, true).await },
+        )
+        .await
+    }
+
+    /// Uncheck multiple items at once
+    #[napi]
+    pub async fn bulk_uncheck_items(
+        &self,
+        list_id: String,
+        item_ids: Vec<String>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "bulkUncheckItems",
+            Some(list_id.clone()),
+            [list_id.clone()]
+                .into_iter()
+                .chain(item_ids.clone())
+                .collect(),
+            async move { self.set_items_checked(&list_id, &item_ids, false).await },
+        )
+        .await
+    }
+
+    /// Set the category of many items in a single request, returning the
+    /// updated items
+    /// Nothing is changed if any item isn't on the list
+    #[napi]
+    pub async fn bulk_categorize_items(
+        &self,
+        list_id: String,
+        changes: Vec<ItemCategoryChange>,
+    ) -> Result<Vec<ListItem>> {
+        self.list_change(
+            "bulkCategorizeItems",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                if changes.is_empty() {
+                    return Ok(Vec::new());
+                }
+                let list = self
+                    .inner
+                    .get_list_by_id(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+                let items: HashMap<&str, &RsListItem> =
+                    list.items().iter().map(|item| (item.id(), item)).collect();
+
+                let mut ops = Vec::with_capacity(changes.len());
+                let mut updated = Vec::with_capacity(changes.len());
+                for change in changes {
+                    let item = items.get(change.item_id.as_str()).ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "Item with ID {} not found in list {}",
+                            change.item_id, list_id
+                        )))
+                    })?;
+                    ops.push(operations::set_item_field(
+                        &self.inner,
+                        "set-list-item-category-match-id",
+                        &list_id,
+                        &change.item_id,
+                        &change.category,
+                    ));
+                    updated.push(ListItem {
+                        category: Some(change.category),
+                        ..ListItem::from(*item)
+                    });
+                }
+
+                let operation_list = PbListOperationList { operations: ops };
+                transport::post_message(&self.inner, "data/shopping-lists/update", &operation_list)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(updated)
+            },
+        )
+        .await
+    }
+
+    /// Delete all crossed off (checked) items from a list
+    #[napi]
+    pub async fn delete_all_crossed_off_items(&self, list_id: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteAllCrossedOffItems",
+            Some(list_id.clone()),
+            vec![list_id.clone()],
+            async move {
+                self.remove_items(&list_id, |i| i.is_checked()).await?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Start a batch of item changes that are sent together, so they all
+    /// succeed or all fail
+    /// Changes in a batch skip category rules, the offline queue, and
+    /// optimistic updates.
+    #[napi]
+    pub fn batch(&self) -> Batch {
+        Batch {
+            client: Arc::clone(&self.inner),
+            journal: Arc::clone(&self.journal),
+            sync_state: Arc::clone(&self.sync_state),
+            cache: Arc::clone(&self.cache),
+            drift_events: Arc::clone(&self.drift_events),
+            list_locks: Arc::clone(&self.list_locks),
+            operations: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get items deleted from a list through this client, most recent first
+    /// AnyList has no server-side trash, so only deletions made by this client are remembered
+    #[napi]
+    pub fn get_deleted_items(&self, list_id: String) -> Vec<ListItem> {
+        let trash = self.trash.lock().unwrap();
+        trash
+            .get(&list_id)
+            .map(|items| items.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Re-add a deleted item to its list, returning the restored item (which has a new ID)
+    #[napi]
+    pub async fn restore_item(&self, list_id: String, item_id: String) -> Result<ListItem> {
+        self.list_change(
+            "restoreItem",
+            list_id.clone(),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                let deleted = self
+                    .trash
+                    .lock()
+                    .unwrap()
+                    .get(&list_id)
+                    .and_then(|items| items.iter().find(|i| i.id == item_id).cloned())
+                    .ok_or_else(|| Error::new(Status::GenericFailure, "Deleted item not found"))?;
+
+                let item = self
+                    .inner
+                    .add_item_with_details(
+                        &list_id,
+                        &deleted.name,
+                        deleted.quantity.as_deref(),
+                        Some(deleted.note.as_str()).filter(|n| !n.is_empty()),
+                        deleted.category.as_deref(),
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+
+                if deleted.checked {
+                    self.inner
+                        .cross_off_item(&list_id, item.id())
+                        .await
+                        .map_err(to_napi_error)?;
+                }
+
+                if let Some(items) = self.trash.lock().unwrap().get_mut(&list_id) {
+                    items.retain(|i| i.id != item_id);
+                }
+
+                let mut restored = ListItem::from(&item);
+                restored.checked = deleted.checked;
+                Ok(restored)
+            },
+        )
+        .await
+    }
+
+    /// Get all recipes, or only those matching a filter
+    #[napi]
+    pub async fn get_recipes(&self, filter: Option<RecipeFilter>) -> Result<Vec<Recipe>> {
+        let Some(filter) = filter else {
+            return self.recipes().await;
+        };
+
+        let collection_ids: Option<HashSet<String>> = match &filter.collection_id {
+            Some(collection_id) => Some(
+                self.recipe_collection(collection_id)
+                    .await?
+                    .recipe_ids()
+                    .iter()
+                    .cloned()
+                    .collect(),
+            ),
+            None => None,
+        };
+
+        let mut recipes = self.recipes().await?;
+        recipes.retain(|recipe| {
+            filter
+                .min_rating
+                .is_none_or(|min| recipe.rating.is_some_and(|rating| rating >= min))
+                && filter
+                    .max_total_time
+                    .is_none_or(|max| recipe.total_time.is_some_and(|time| time <= max))
+                && collection_ids
+                    .as_ref()
+                    .is_none_or(|ids| ids.contains(&recipe.id))
+        });
+        Ok(recipes)
+    }
+
+    /// Get a specific recipe by ID
+    #[napi]
+    pub async fn get_recipe_by_id(&self, recipe_id: String) -> Result<Recipe> {
+        self.recipes()
+            .await?
+            .into_iter()
+            .find(|recipe| recipe.id == recipe_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Recipe with ID {} not found",
+                    recipe_id
+                )))
+            })
+    }
+
+    /// Get several recipes by ID in one request, in the order given
+    /// IDs that don't match a recipe are skipped
+    #[napi]
+    pub async fn get_recipes_by_ids(&self, recipe_ids: Vec<String>) -> Result<Vec<Recipe>> {
+        let mut recipes: HashMap<String, Recipe> = self
+            .recipes()
+            .await?
+            .into_iter()
+            .map(|recipe| (recipe.id.clone(), recipe))
+            .collect();
+
+        Ok(recipe_ids
+            .iter()
+            .filter_map(|id| recipes.remove(id))
+            .collect())
+    }
+
+    /// Get a recipe by name
+    #[napi]
+    pub async fn get_recipe_by_name(&self, name: String) -> Result<Recipe> {
+        self.recipes()
+            .await?
+            .into_iter()
+            .find(|recipe| recipe.name == name)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Recipe with name '{}' not found",
+                    name
+                )))
+            })
+    }
+
+    /// Search recipes for every word of a query (case-insensitive)
+    /// Recipes whose name matches are listed first
+    #[napi]
+    pub async fn search_recipes(
+        &self,
+        query: String,
+        options: Option<SearchRecipesOptions>,
+    ) -> Result<Vec<Recipe>> {
+        let fields = options.and_then(|o| o.fields).unwrap_or_else(|| {
+            vec![
+                RecipeSearchField::Name,
+                RecipeSearchField::Ingredients,
+                RecipeSearchField::Note,
+                RecipeSearchField::Steps,
+            ]
+        });
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let recipes = self.recipes().await?;
+
+        let mut matches: Vec<(bool, Recipe)> = recipes
+            .into_iter()
+            .filter_map(|recipe| {
+                let text = |field: &RecipeSearchField| match field {
+                    RecipeSearchField::Name => recipe.name.clone(),
+                    RecipeSearchField::Ingredients => recipe
+                        .ingredients
+                        .iter()
+                        .map(export::ingredient_line)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    RecipeSearchField::Note => recipe.note.clone().unwrap_or_default(),
+                    RecipeSearchField::Steps => recipe.preparation_steps.join("\n"),
+                };
+                let haystack = fields.iter().map(text).collect::<Vec<_>>().join("\n");
+                let haystack = haystack.to_lowercase();
+
+                words
+                    .iter()
+                    .all(|word| haystack.contains(word.as_str()))
+                    .then(|| {
+                        let name = recipe.name.to_lowercase();
+                        let name_match = fields.contains(&RecipeSearchField::Name)
+                            && words.iter().all(|word| name.contains(word.as_str()));
+                        (name_match, recipe)
+                    })
+            })
+            .collect();
+
+        // Stable sort keeps AnyList's order within each group
+        matches.sort_by_key(|(name_match, _)| !name_match);
+
+        Ok(matches.into_iter().map(|(_, recipe)| recipe).collect())
+    }
+
+    /// Find recipes with an ingredient matching a name, ignoring case, plurals,
+    /// and small typos; the closest matches are listed first
+    #[napi]
+    pub async fn find_recipes_using_ingredient(&self, ingredient: String) -> Result<Vec<Recipe>> {
+        let query = fuzzy::words(&ingredient);
+        if query.is_empty() {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Ingredient name must not be empty",
+            ));
+        }
+
+        let mut matches: Vec<(u8, Recipe)> = self
+            .recipes()
+            .await?
+            .into_iter()
+            .filter_map(|recipe| {
+                let score = recipe
+                    .ingredients
+                    .iter()
+                    .filter(|i| i.is_header != Some(true))
+                    .filter_map(|i| fuzzy::match_score(&query, &i.name))
+                    .max()?;
+                Some((score, recipe))
+            })
+            .collect();
+
+        // Stable sort keeps AnyList's order within each score
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        Ok(matches.into_iter().map(|(_, recipe)| recipe).collect())
+    }
+
+    /// Get recipes newest first, by when they were added or last changed
+    #[napi]
+    pub async fn get_recent_recipes(
+        &self,
+        options: Option<RecentRecipesOptions>,
+    ) -> Result<Vec<Recipe>> {
+        let (by, limit) = match options {
+            Some(options) => (options.by, options.limit),
+            None => (None, None),
+        };
+        let mut recipes: Vec<Recipe> = self.recipes().await?;
+
+        let date = |recipe: &Recipe| match by {
+            Some(RecentRecipesOrder::Updated) => recipe.updated_at.or(recipe.created_at),
+            _ => recipe.created_at.or(recipe.updated_at),
+        };
+        recipes.sort_by(|a, b| date(b).unwrap_or(0.0).total_cmp(&date(a).unwrap_or(0.0)));
+        if let Some(limit) = limit {
+            recipes.truncate(limit as usize);
+        }
+
+        Ok(recipes)
+    }
+
+    /// Create a new recipe with full metadata support
+    #[napi]
+    pub async fn create_recipe(&self, options: CreateRecipeOptions) -> Result<Recipe> {
+        self.journaled("createRecipe", Vec::new(), self.add_recipe(options))
+            .await
+    }
+
+    /// Get a recipe with its ingredients and servings scaled, without changing anything
+    #[napi]
+    pub async fn scale_recipe(&self, recipe_id: String, factor: f64) -> Result<Recipe> {
+        check_scale_factor(factor)?;
+        let recipe = self.get_recipe_by_id(recipe_id).await?;
+
+        Ok(Recipe {
+            ingredients: scale_ingredients(recipe.ingredients, factor)?,
+            servings: recipe
+                .servings
+                .map(|servings| quantity::scale_quantity(&servings, factor)),
+            ..recipe
+        })
+    }
+
+    /// Add recipe ingredients to a list with optional scale factor
+    /// Ingredients linked to another recipe (see `Ingredient.recipeId`) and
+    /// counted in batches are replaced by that recipe's ingredients
+    #[napi]
+    pub async fn add_recipe_to_list(
+        &self,
+        recipe_id: String,
+        list_id: String,
+        scale_factor: Option<f64>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "addRecipeToList",
+            Some(list_id.clone()),
+            vec![recipe_id.clone(), list_id.clone()],
+            async move {
+                self.add_recipe_ingredients(
+                    &list_id,
+                    &[RecipeSelection {
+                        recipe_id,
+                        scale_factor,
+                    }],
+                )
+                .await
+            },
+        )
+        .await
+    }
+
+    /// Add several recipes' ingredients to a list in one request, expanding
+    /// linked recipes like `addRecipeToList`
+    #[napi]
+    pub async fn add_recipes_to_list(
+        &self,
+        list_id: String,
+        recipes: Vec<RecipeSelection>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "addRecipesToList",
+            Some(list_id.clone()),
+            vec![list_id.clone()],
+            self.add_recipe_ingredients(&list_id, &recipes),
+        )
+        .await
+    }
+
+    /// Update an existing recipe
+    /// Note: The recipe name cannot be changed (use the existing name in options)
+    #[napi]
+    pub async fn update_recipe(
+        &self,
+        recipe_id: String,
+        options: CreateRecipeOptions,
+    ) -> Result<Recipe> {
+        self.journaled("updateRecipe", vec![recipe_id.clone()], async move {
+            self.remember_recipe(self.raw_recipe(&recipe_id).await?);
+
+            // Fetch the existing recipe to use as base for the builder
+            let existing = self
+                .inner
+                .get_recipe_by_id(&recipe_id)
+                .await
+                .map_err(to_napi_error)?;
+
+            let rs_ingredients: Vec<RsIngredient> =
+                options.ingredients.iter().map(RsIngredient::from).collect();
+
+            // Start from existing recipe (preserves the ID for update)
+            let mut builder = RecipeBuilder::from(&existing)
+                .ingredients(rs_ingredients)
+                .preparation_steps(step_lines(options.preparation_steps, options.steps));
+
+            if let Some(note) = options.note {
+                builder = builder.note(note);
+            }
+            if let Some(source_name) = options.source_name {
+                builder = builder.source_name(source_name);
+            }
+            if let Some(source_url) = options.source_url {
+                builder = builder.source_url(source_url);
+            }
+            if let Some(servings) = options.servings {
+                builder = builder.servings(servings);
+            }
+            if let Some(prep_time) = time_minutes(options.prep_time.as_ref())? {
+                builder = builder.prep_time(prep_time);
+            }
+            if let Some(cook_time) = time_minutes(options.cook_time.as_ref())? {
+                builder = builder.cook_time(cook_time);
+            }
+            if let Some(rating) = options.rating {
+                builder = builder.rating(rating);
+            }
+            if let Some(nutritional_info) = options.nutritional_info {
+                builder = builder.nutritional_info(nutritional_info);
+            }
+            if let Some(photo_id) = self.photo_id(options.photo_id, options.photo_url).await? {
+                builder = builder.photo_id(photo_id);
+            }
+
+            let recipe = builder.save(&self.inner).await.map_err(to_napi_error)?;
+
+            // Re-read the saved recipe so timestamps are filled in
+            self.get_recipe_by_id(recipe.id().to_string()).await
+        })
+        .await
+    }
+
+    /// Change only the given fields of a recipe, keeping everything else as
+    /// AnyList currently has it
+    #[napi]
+    pub async fn patch_recipe(&self, recipe_id: String, mut patch: RecipePatch) -> Result<Recipe> {
+        self.journaled("patchRecipe", vec![recipe_id.clone()], async move {
+            if let Some(rating) = patch.rating {
+                if !(0..=5).contains(&rating) {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        format!("Rating must be between 0 and 5, got {}", rating),
+                    ));
+                }
+            }
+            time_minutes(patch.prep_time.as_ref())?;
+            time_minutes(patch.cook_time.as_ref())?;
+            patch.photo_id = self
+                .photo_id(patch.photo_id, patch.photo_url.take())
+                .await?;
+
+            self.update_raw_recipe(&recipe_id, |recipe| patch.apply(recipe))
+                .await
+        })
+        .await
+    }
+
+    /// Delete a recipe
+    #[napi]
+    pub async fn delete_recipe(&self, recipe_id: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteRecipe",
+            None,
+            vec![recipe_id.clone()],
+            self.remove_recipe(&recipe_id),
+        )
+        .await
+    }
+
+    /// Get earlier versions of a recipe, most recent first
+    /// AnyList keeps no history, so only changes made through this client are remembered
+    #[napi]
+    pub fn get_recipe_history(&self, recipe_id: String) -> Vec<RecipeVersion> {
+        let history = self.recipe_history.lock().unwrap();
+        history
+            .get(&recipe_id)
+            .map(|versions| {
+                versions
+                    .iter()
+                    .rev()
+                    .map(|version| RecipeVersion {
+                        id: version.id.clone(),
+                        saved_at: version.saved_at,
+                        recipe: Recipe::from(&version.recipe),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Restore an earlier version of a recipe, recreating it if it was deleted
+    #[napi]
+    pub async fn revert_recipe(&self, recipe_id: String, version_id: String) -> Result<Recipe> {
+        self.journaled(
+            "revertRecipe",
+            vec![recipe_id.clone(), version_id.clone()],
+            async move {
+                let version = self
+                    .recipe_history
+                    .lock()
+                    .unwrap()
+                    .get(&recipe_id)
+                    .and_then(|versions| versions.iter().find(|v| v.id == version_id))
+                    .map(|version| version.recipe.clone())
+                    .ok_or_else(|| {
+                        Error::new(Status::GenericFailure, "Recipe version not found")
+                    })?;
+
+                // The current version can be reverted to in turn
+                if let Ok(current) = self.raw_recipe(&recipe_id).await {
+                    self.remember_recipe(current);
+                }
+
+                self.save_raw_recipe(version).await
+            },
+        )
+        .await
+    }
+
+    /// Delete a list
+    #[napi]
+    pub async fn delete_list(&self, list_id: String) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteList",
+            Some(list_id.clone()),
+            vec![list_id.clone()],
+            async move {
+                self.inner
+                    .delete_list(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Upload a photo for use with recipes
+    /// Returns the photo ID which can be used with createRecipe
+    #[napi]
+    pub async fn upload_photo(&self, data: Buffer, filename: String) -> Result<String> {
+        self.journaled("uploadPhoto", Vec::new(), async move {
+            let photo_id = self
+                .inner
+                .upload_photo(data.to_vec(), &filename)
+                .await
+                .map_err(to_napi_error)?;
+
+            Ok(photo_id)
+        })
+        .await
+    }
+
+    /// Export a recipe as Markdown or JSON
+    #[napi]
+    pub async fn export_recipe(&self, recipe_id: String, format: ExportFormat) -> Result<String> {
+        let recipe = self.raw_recipe(&recipe_id).await?;
+        let photo_urls = &recipe.photo_urls;
+        let recipe = Recipe::from(&recipe);
+
+        match format {
+            ExportFormat::Markdown => Ok(export::to_markdown(&recipe, photo_urls)),
+            ExportFormat::Json => export::to_json(&recipe, photo_urls),
+        }
+    }
+
+    /// Render a recipe as a standalone, printable HTML document with its photo inlined
+    #[napi]
+    pub async fn render_recipe_html(
+        &self,
+        recipe_id: String,
+        options: Option<RenderRecipeHtmlOptions>,
+    ) -> Result<String> {
+        let template = options
+            .and_then(|o| o.template)
+            .unwrap_or(RecipeHtmlTemplate::Full);
+        let raw = self.raw_recipe(&recipe_id).await?;
+
+        let photo = match recipe_photo_url(&raw) {
+            // A missing or broken photo shouldn't stop the recipe rendering
+            Some(url) if matches!(template, RecipeHtmlTemplate::Full) => {
+                self.download_photo(&url).await.ok()
+            }
+            _ => None,
+        };
+
+        Ok(export::to_html(
+            &Recipe::from(&raw),
+            photo.as_ref(),
+            &template,
+        ))
+    }
+
+    /// Back up every recipe as a single JSON document
+    /// Photos are embedded as base64 when `includePhotos` is set; a photo that
+    /// can't be downloaded is left out, with the error in its recipe's
+    /// `photoError`
+    #[napi]
+    pub async fn export_all_recipes(
+        &self,
+        options: Option<ExportAllRecipesOptions>,
+    ) -> Result<String> {
+        let include_photos = options.and_then(|o| o.include_photos).unwrap_or(false);
+
+        export::to_backup(self.backup_recipes(include_photos).await?)
+    }
+
+    /// Back up every list and recipe as a single JSON snapshot: the same
+    /// document as `exportAllRecipes()`, with the lists added
+    #[napi]
+    pub async fn export_snapshot(
+        &self,
+        options: Option<ExportAllRecipesOptions>,
+    ) -> Result<String> {
+        let include_photos = options.and_then(|o| o.include_photos).unwrap_or(false);
+        let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
+        let lists: Vec<List> = lists.iter().map(List::from).collect();
+
+        export::to_snapshot(&lists, self.backup_recipes(include_photos).await?)
+    }
+
+    /// Recreate the lists and recipes in a snapshot or recipe backup,
+    /// reporting which were created and which skipped, with their old and
+    /// new IDs
+    /// Lists and recipes are matched by name, and items by name within their
+    /// list. `merge` (the default) keeps the ones that already exist and adds
+    /// the rest; `replace` empties matching lists before adding the
+    /// snapshot's items and replaces matching recipes, deleting each only
+    /// once its replacement has been created.
+    /// Lists and recipes that aren't in the snapshot are left alone.
+    #[napi]
+    pub async fn restore_from_snapshot(
+        &self,
+        snapshot: String,
+        options: Option<RestoreOptions>,
+    ) -> Result<RestoreResult> {
+        self.journaled("restoreFromSnapshot", Vec::new(), async move {
+            let snapshot = snapshot::read(&snapshot)?;
+            let replace = options.and_then(|o| o.mode) == Some(RestoreMode::Replace);
+            let mut restored = RestoreResult::default();
+
+            let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
+            for list in &snapshot.lists {
+                let existing = lists.iter().find(|l| l.name() == list.name).map(List::from);
+                self.restore_list(list, existing, replace, &mut restored)
+                    .await?;
+            }
+
+            let recipes = self.raw_recipes().await?;
+            for recipe in snapshot.recipes {
+                let existing = recipes
+                    .iter()
+                    .find(|r| r.name.as_deref() == Some(recipe.options.name.as_str()));
+                if let (Some(existing), false) = (existing, replace) {
+                    restored.skipped.push(RestoredEntity {
+                        kind: SnapshotEntity::Recipe,
+                        old_id: recipe.id,
+                        new_id: existing.identifier.clone(),
+                        name: recipe.options.name,
+                        list_id: None,
+                    });
+                    continue;
+                }
+
+                let mut options = recipe.options;
+                if let Some(photo) = recipe.photo {
+                    options.photo_id = Some(
+                        self.inner
+                            .upload_photo(photo, "photo.jpg")
+                            .await
+                            .map_err(to_napi_error)?,
+                    );
+                }
+                let name = options.name.clone();
+                let created = self.add_recipe(options).await?;
+                // Only once its replacement exists, so a failure leaves the
+                // recipe as it was
+                if let Some(existing) = existing {
+                    self.remove_recipe(&existing.identifier).await?;
+                }
+                restored.created.push(RestoredEntity {
+                    kind: SnapshotEntity::Recipe,
+                    old_id: recipe.id,
+                    new_id: created.id,
+                    name,
+                    list_id: None,
+                });
+            }
+
+            Ok(restored)
+        })
+        .await
+    }
+
+    /// Set a recipe's rating (1-5, or 0 to clear it) without changing anything else
+    #[napi]
+    pub async fn rate_recipe(&self, recipe_id: String, rating: i32) -> Result<Recipe> {
+        self.journaled("rateRecipe", vec![recipe_id.clone()], async move {
+            if !(0..=5).contains(&rating) {
+                return Err(Error::new(
+                    Status::InvalidArg,
+                    format!("Rating must be between 0 and 5, got {}", rating),
+                ));
+            }
+
+            self.update_raw_recipe(&recipe_id, |recipe| {
+                recipe.rating = (rating > 0).then_some(rating);
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Record that a recipe was cooked on a date (YYYY-MM-DD, default today)
+    /// by adding it to the meal plan
+    #[napi]
+    pub async fn mark_recipe_cooked(
+        &self,
+        recipe_id: String,
+        date: Option<MealPlanDate>,
+    ) -> Result<MealPlanEvent> {
+        self.journaled("markRecipeCooked", vec![recipe_id.clone()], async move {
+            let date = match date {
+                Some(date) => self.day(&date)?.format("%Y-%m-%d").to_string(),
+                None => self.today(),
+            };
+            self.raw_recipe(&recipe_id).await?;
+            let calendar = self.get_meal_plan_calendar().await?;
+
+            self.add_meal_plan_event(&calendar.id, &date.into(), Some(&recipe_id), None, None)
+                .await
+        })
+        .await
+    }
+
+    /// Copy a recipe, including its ingredients, steps, and photo
+    /// The copy is named "<name> (copy)" unless a new name is given
+    #[napi]
+    pub async fn duplicate_recipe(
+        &self,
+        recipe_id: String,
+        new_name: Option<String>,
+    ) -> Result<Recipe> {
+        self.journaled("duplicateRecipe", vec![recipe_id.clone()], async move {
+            let original = self.raw_recipe(&recipe_id).await?;
+            let name = new_name.unwrap_or_else(|| {
+                format!("{} (copy)", original.name.as_deref().unwrap_or_default())
+            });
+
+            self.save_raw_recipe(PbRecipe {
+                identifier: transport::generate_id(),
+                name: Some(name),
+                creation_timestamp: Some(transport::current_timestamp()),
+                photo_urls: Vec::new(),
+                paprika_identifier: None,
+                ..original
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Create a recipe that is just photos, e.g. of the pages of a cookbook
+    /// recipe, in order
+    #[napi]
+    pub async fn create_photo_recipe(
+        &self,
+        name: String,
+        photos: Vec<Buffer>,
+        source_name: Option<String>,
+    ) -> Result<Recipe> {
+        self.journaled("createPhotoRecipe", Vec::new(), async move {
+            if photos.is_empty() {
+                return Err(Error::new(
+                    Status::InvalidArg,
+                    "A photo recipe needs at least one photo",
+                ));
+            }
+
+            let mut photo_ids = Vec::with_capacity(photos.len());
+            for (i, photo) in photos.iter().enumerate() {
+                let photo_id = self
+                    .inner
+                    .upload_photo(photo.to_vec(), &format!("page-{}.jpg", i + 1))
+                    .await
+                    .map_err(to_napi_error)?;
+                photo_ids.push(photo_id);
+            }
+
+            let now = transport::current_timestamp();
+            self.save_raw_recipe(PbRecipe {
+                identifier: transport::generate_id(),
+                name: Some(name),
+                source_name,
+                photo_ids,
+                creation_timestamp: Some(now),
+                ..Default::default()
+            })
+            .await
+        })
+        .await
+    }
+
+    /// Download all of a recipe's photos, in order
+    #[napi]
+    pub async fn get_recipe_photos(&self, recipe_id: String) -> Result<Vec<RecipePhoto>> {
+        let recipe = self.raw_recipe(&recipe_id).await?;
+        let urls: Vec<String> = if recipe.photo_ids.is_empty() {
+            recipe.photo_urls
+        } else {
+            recipe
+                .photo_ids
+                .iter()
+                .map(|id| transport::photo_url(id))
+                .collect()
+        };
+
+        let mut photos = Vec::with_capacity(urls.len());
+        for url in urls {
+            let photo = self.download_photo(&url).await?;
+            photos.push(RecipePhoto {
+                data: photo.data.into(),
+                content_type: photo.content_type,
+            });
+        }
+        Ok(photos)
+    }
+
+    /// Remove a recipe's photo
+    #[napi]
+    pub async fn clear_recipe_photo(&self, recipe_id: String) -> Result<Recipe> {
+        self.journaled("clearRecipePhoto", vec![recipe_id.clone()], async move {
+            self.set_recipe_photo(&recipe_id, None).await
+        })
+        .await
+    }
+
+    /// Upload a new photo and make it the recipe's photo in one step
+    /// The previous photo is detached from the recipe but not deleted, as
+    /// AnyList has no endpoint for deleting photos
+    #[napi]
+    pub async fn replace_recipe_photo(
+        &self,
+        recipe_id: String,
+        data: Buffer,
+        filename: String,
+    ) -> Result<Recipe> {
+        self.journaled("replaceRecipePhoto", vec![recipe_id.clone()], async move {
+            // Check the recipe exists before uploading anything
+            self.raw_recipe(&recipe_id).await?;
+
+            let photo_id = self
+                .inner
+                .upload_photo(data.to_vec(), &filename)
+                .await
+                .map_err(to_napi_error)?;
+
+            self.set_recipe_photo(&recipe_id, Some(photo_id)).await
+        })
+        .await
+    }
+
+    /// Download a recipe photo by its photo ID
+    #[napi]
+    pub async fn download_recipe_photo(&self, photo_id: String) -> Result<RecipePhoto> {
+        let photo = self
+            .download_photo(&transport::photo_url(&photo_id))
+            .await?;
+
+        Ok(RecipePhoto {
+            data: photo.data.into(),
+            content_type: photo.content_type,
+        })
+    }
+
+    /// Create a recipe from a web page's schema.org (JSON-LD or microdata) markup
+    /// The page's main image is uploaded as the recipe photo when available
+    #[napi]
+    pub async fn import_recipe_from_url(&self, url: String) -> Result<Recipe> {
+        self.journaled("importRecipeFromUrl", Vec::new(), async move {
+            let page_url = reqwest::Url::parse(&url)
+                .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid URL: {}", e)))?;
+            let html = transport::fetch(&url)
+                .await
+                .map_err(to_napi_error)?
+                .text()
+                .await
+                .map_err(|e| to_napi_error(e.into()))?;
+
+            let data = schema_org::find_recipe(&html).ok_or_else(|| {
+                Error::new(
+                    Status::GenericFailure,
+                    format!("No schema.org recipe found at {}", url),
+                )
+            })?;
+            let mut options = schema_org::create_recipe_options(&data)?;
+
+            if options.source_name.is_none() {
+                options.source_name = page_url
+                    .host_str()
+                    .map(|host| host.trim_start_matches("www.").to_string());
+            }
+            options.source_url = Some(url);
+
+            self.create_schema_org_recipe(&data, options, Some(&page_url))
+                .await
+        })
+        .await
+    }
+
+    /// Create a recipe from an already-extracted schema.org JSON-LD Recipe object
+    #[napi]
+    pub async fn import_recipe_from_schema_org(&self, json: serde_json::Value) -> Result<Recipe> {
+        self.journaled("importRecipeFromSchemaOrg", Vec::new(), async move {
+            let options = schema_org::create_recipe_options(&json)?;
+
+            self.create_schema_org_recipe(&json, options, None).await
+        })
+        .await
+    }
+
+    /// Create recipes (with their photos) from a Paprika `.paprikarecipes`
+    /// export, reporting which were created and which failed
+    /// A recipe that fails doesn't stop the others being imported
+    #[napi]
+    pub async fn import_recipes_from_paprika(&self, data: Buffer) -> Result<RecipeImportResult> {
+        self.journaled("importRecipesFromPaprika", Vec::new(), async move {
+            let imported = paprika::read_export(&data)?;
+
+            let mut result = RecipeImportResult::default();
+            for paprika::ImportedRecipe { mut options, photo } in imported {
+                let name = options.name.clone();
+                let created = async {
+                    if let Some(photo) = photo {
+                        options.photo_id = Some(
+                            self.inner
+                                .upload_photo(photo, "photo.jpg")
+                                .await
+                                .map_err(to_napi_error)?,
+                        );
+                    }
+                    self.add_recipe(options).await
+                };
+                match created.await {
+                    Ok(recipe) => result.created.push(recipe),
+                    Err(e) => result.failed.push(RecipeImportFailure {
+                        name,
+                        error: e.reason.clone(),
+                    }),
+                }
+            }
+
+            Ok(result)
+        })
+        .await
+    }
+
+    /// Create recipes from a Mealie JSON export, reporting which were created
+    /// and which failed
+    /// Tags and categories are added as recipe collections, creating any that don't exist
+    /// A recipe that fails doesn't stop the others being imported
+    #[napi]
+    pub async fn import_recipes_from_mealie(
+        &self,
+        json: serde_json::Value,
+    ) -> Result<RecipeImportResult> {
+        self.journaled("importRecipesFromMealie", Vec::new(), async move {
+            let imported = mealie::read_export(&json)?;
+
+            let mut collections: HashMap<String, String> = self
+                .inner
+                .get_recipe_collections()
+                .await
+                .map_err(to_napi_error)?
+                .iter()
+                .map(|c| (normalize_name(c.name()), c.id().to_string()))
+                .collect();
+
+            let mut result = RecipeImportResult::default();
+            for mealie::ImportedRecipe { options, tags } in imported {
+                let name = options.name.clone();
+                let recipe = match self.add_recipe(options).await {
+                    Ok(recipe) => recipe,
+                    Err(e) => {
+                        result.failed.push(RecipeImportFailure {
+                            name,
+                            error: e.reason.clone(),
+                        });
+                        continue;
+                    }
+                };
+
+                for tag in tags {
+                    let key = normalize_name(&tag);
+                    let collected = async {
+                        let collection_id = match collections.get(&key) {
+                            Some(id) => id.clone(),
+                            None => {
+                                let collection = self
+                                    .inner
+                                    .create_recipe_collection(&tag)
+                                    .await
+                                    .map_err(to_napi_error)?;
+                                let id = collection.id().to_string();
+                                collections.insert(key, id.clone());
+                                id
+                            }
+                        };
+                        self.inner
+                            .add_recipe_to_collection(&collection_id, &recipe.id)
+                            .await
+                            .map_err(to_napi_error)
+                    };
+                    if let Err(e) = collected.await {
+                        result.failed.push(RecipeImportFailure {
+                            name: name.clone(),
+                            error: format!(
+                                "Created, but not added to collection \"{}\": {}",
+                                tag, e.reason
+                            ),
+                        });
+                    }
+                }
+
+                result.created.push(recipe);
+            }
+
+            Ok(result)
+        })
+        .await
+    }
+
+    // ==================== Change Methods ====================
+
+    /// Call `callback` with each item added, item checked or unchecked, and
+    /// list renamed, by anyone, until the returned subscription is
+    /// unsubscribed
+    /// Changes are picked up from AnyList's realtime channel, falling back to
+    /// polling while it can't be reached
+    #[napi]
+    pub async fn subscribe(
+        &self,
+        #[napi(ts_arg_type = "(event: ChangeEvent) => unknown")] callback: ChangeCallback,
+        options: Option<SubscribeOptions>,
+    ) -> Result<Subscription> {
+        let options = options.unwrap_or_default();
+        self.watch_lists(
+            ChangeSink::Callback(callback),
+            options.realtime,
+            options.poll_interval_ms,
+        )
+        .await
+    }
+
+    /// POST each item added, item checked or unchecked, and list renamed to a
+    /// webhook as JSON, signed when `secret` is set, until the returned
+    /// subscription is unsubscribed
+    /// Deliveries are retried a few times while the webhook can't be
+    /// reached; `onFailure` is called with events that still couldn't be
+    /// delivered.
+    #[napi]
+    pub async fn start_webhook_bridge(
+        &self,
+        options: WebhookOptions,
+        #[napi(ts_arg_type = "(failure: WebhookFailure) => unknown")] on_failure: Option<
+            WebhookFailureCallback,
+        >,
+    ) -> Result<Subscription> {
+        let url = reqwest::Url::parse(&options.url)
+            .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid URL: {}", e)))?;
+        let webhook = webhook::Webhook::new(url.to_string(), options.secret.as_deref(), on_failure);
+
+        self.watch_lists(
+            ChangeSink::Webhook(webhook),
+            options.realtime,
+            options.poll_interval_ms,
+        )
+        .await
+    }
+
+    /// Poll a list every `intervalMs` and call `callback` with what changed
+    /// since the last poll, skipping polls where nothing did
+    #[napi]
+    pub async fn watch_list(
+        &self,
+        list_id: String,
+        interval_ms: u32,
+        #[napi(ts_arg_type = "(changes: ListChanges) => unknown")] callback: ListChangesCallback,
+    ) -> Result<Subscription> {
+        let interval = poll_interval(interval_ms)?;
+        let list = self
+            .inner
+            .get_list_by_id(&list_id)
+            .await
+            .map_err(to_napi_error)?;
+
+        let stop = Arc::new(tokio::sync::Notify::new());
+        let poller = ListPoller {
+            client: Arc::clone(&self.inner),
+            list: List::from(&list),
+            poll_interval: interval,
+            stop: Arc::clone(&stop),
+            callback,
+        };
+        tokio::spawn(poller.run());
+
+        Ok(Subscription {
+            stop,
+            realtime: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Get the lists, recipes, and meal plan events changed since an earlier
+    /// call's `cursor`, or since a time in milliseconds since the epoch
+    /// Without either, everything is returned along with a cursor to start
+    /// from. Meal plan events have no modification time, so all of them are
+    /// returned when asking since a time.
+    #[napi]
+    pub async fn get_changes_since(
+        &self,
+        since: Option<Either<f64, String>>,
+    ) -> Result<AccountChanges> {
+        let since = match since {
+            None => cursor::Since::Start,
+            Some(Either::A(timestamp)) => cursor::Since::Timestamp(timestamp),
+            Some(Either::B(cursor)) => cursor::Since::Cursor(cursor::Cursor::decode(&cursor)?),
+        };
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        Ok(cursor::changes_since(&data, since))
+    }
+
+    // ==================== Cache Methods ====================
+
+    /// Cache `getLists()` and `getRecipes()` results for a while, so repeated
+    /// calls don't each fetch them; replaces earlier options and clears the
+    /// cache
+    /// Changes made through this client drop the cached results they affect;
+    /// call `invalidateCache()` to see changes made elsewhere straight away
+    #[napi]
+    pub fn set_cache_options(&self, options: CacheOptions) {
+        self.cache.lock().unwrap().configure(&options);
+    }
+
+    /// Drop cached results of one type, or all of them, so the next read
+    /// fetches them again
+    #[napi]
+    pub fn invalidate_cache(&self, entity: Option<CacheEntity>) {
+        self.cache.lock().unwrap().invalidate(entity);
+    }
+
+    /// Keep cached lists and recipes fresh by refetching them in the
+    /// background, calling `callback` after each refresh; replaces an
+    /// earlier schedule
+    /// Each type is fetched straight away and then on its interval. While a
+    /// type is refreshed, reads use the refreshed copy whatever the cache
+    /// options, until two refreshes in a row fail.
+    #[napi]
+    pub fn start_auto_refresh(
+        &self,
+        options: AutoRefreshOptions,
+        #[napi(ts_arg_type = "(event: RefreshEvent) => unknown")] callback: Option<
+            RefreshEventCallback,
+        >,
+    ) -> Result<()> {
+        let interval = |ms: Option<u32>| match ms {
+            Some(0) => Err(Error::new(
+                Status::InvalidArg,
+                "Refresh interval must be at least 1 ms",
+            )),
+            ms => Ok(ms.map(|ms| Duration::from_millis(ms.into()))),
+        };
+        let (lists, recipes) = (interval(options.lists)?, interval(options.recipes)?);
+
+        self.stop_auto_refresh();
+        self.cache.lock().unwrap().set_refresh(lists, recipes);
+        let refresher = Arc::new(refresh::Refresher {
+            client: Arc::clone(&self.inner),
+            cache: Arc::clone(&self.cache),
+            sync_state: Arc::clone(&self.sync_state),
+            time_zone: Arc::clone(&self.time_zone),
+            callback,
+        });
+        let mut tasks = self.auto_refresh.lock().unwrap();
+        for (entity, interval) in [(CacheEntity::Lists, lists), (CacheEntity::Recipes, recipes)] {
+            if let Some(interval) = interval {
+                tasks.push(napi::bindgen_prelude::spawn(
+                    Arc::clone(&refresher).run(entity, interval),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop refreshing the cache in the background
+    #[napi]
+    pub fn stop_auto_refresh(&self) {
+        for task in self.auto_refresh.lock().unwrap().drain(..) {
+            task.abort();
+        }
+        self.cache.lock().unwrap().set_refresh(None, None);
+    }
+
+    // ==================== Offline Queue Methods ====================
+
+    /// Queue changes made while AnyList can't be reached in a file, and send
+    /// them in order once it can, calling `callback` as each is queued, sent,
+    /// or refused
+    /// Changes already queued in the file are picked up. Adding items and
+    /// checking or unchecking them are queued; other changes fail as usual.
+    /// Pass `null` to stop queueing, once the queue is empty.
+    #[napi]
+    pub fn set_offline_queue(
+        &self,
+        path: Option<String>,
+        #[napi(ts_arg_type = "(event: QueueEvent) => unknown")] callback: Option<
+            QueueEventCallback,
+        >,
+    ) -> Result<()> {
+        let mut queue = self.offline_queue.lock().unwrap();
+        match path {
+            Some(path) => queue.use_file(std::path::PathBuf::from(path))?,
+            None => queue.disable()?,
+        }
+        *self.queue_events.lock().unwrap() = callback;
+        Ok(())
+    }
+
+    /// Decide what happens to queued and optimistic changes that conflict
+    /// with changes made elsewhere in the meantime (e.g. checking an item
+    /// someone deleted)
+    /// `callback` gets each conflict and returns a resolution; without one,
+    /// the other change is kept. Pass `null` to remove it.
+    #[napi]
+    pub fn set_conflict_resolver(
+        &self,
+        #[napi(ts_arg_type = "(conflict: QueueConflict) => ConflictResolution")] callback: Option<
+            ConflictResolver,
+        >,
+    ) {
+        *self.conflict_resolver.lock().unwrap() = callback.map(Arc::new);
+    }
+
+    /// Get the changes waiting in the offline queue, oldest first
+    #[napi]
+    pub fn get_queued_operations(&self) -> Vec<QueuedOperation> {
+        self.offline_queue.lock().unwrap().operations()
+    }
+
+    /// Send queued changes in order, stopping if AnyList can't be reached,
+    /// and return how many were sent
+    /// Queued changes are also sent before the next change made through this
+    /// client.
+    #[napi]
+    pub async fn flush_queue(&self) -> Result<u32> {
+        self.send_queued().await
+    }
+
+    // ==================== Optimistic Update Methods ====================
+
+    /// Apply item changes to a local copy of the lists straight away and
+    /// send them to AnyList in the background, calling `callback` as each
+    /// is confirmed or rolled back
+    /// While changes are unsent, `getLists()` and `getListById()` read the
+    /// local copy. Adding items and checking or unchecking them are
+    /// optimistic; other changes wait for AnyList as usual. Pass `false` to
+    /// stop, which lets changes already made finish sending.
+    #[napi]
+    pub fn set_optimistic_updates(
+        &self,
+        enabled: bool,
+        #[napi(ts_arg_type = "(event: OptimisticEvent) => unknown")] callback: Option<
+            OptimisticEventCallback,
+        >,
+    ) {
+        *self.optimistic.lock().unwrap() = enabled.then(|| {
+            optimistic::Optimistic::start(
+                Arc::clone(&self.inner),
+                Arc::clone(&self.list_locks),
+                Arc::clone(&self.conflict_resolver),
+                Arc::clone(&self.cache),
+                callback,
+            )
+        });
+    }
+
+    // ==================== Journal Methods ====================
+
+    /// Record every change made through this client in a file, appending a
+    /// line of JSON per change once it finishes
+    /// Clients in other processes can share the file. Pass `null` to stop
+    /// recording.
+    #[napi]
+    pub fn set_journal_file(&self, path: Option<String>) -> Result<()> {
+        let mut journal = self.journal.lock().unwrap();
+        match path {
+            Some(path) => journal.use_file(std::path::PathBuf::from(path)),
+            None => {
+                journal.disable();
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the changes recorded in the journal file, including those made
+    /// by other clients sharing it, oldest first
+    #[napi]
+    pub fn get_journal(&self, options: Option<JournalOptions>) -> Result<Vec<JournalEntry>> {
+        self.journal
+            .lock()
+            .unwrap()
+            .entries(options.unwrap_or_default().since)
+    }
+
+    // ==================== Sync Status Methods ====================
+
+    /// Report how well this client is keeping up with AnyList: when lists
+    /// and recipes were last fetched, how many changes are queued, the last
+    /// failure, and whether subscriptions are getting realtime updates
+    #[napi]
+    pub fn get_sync_status(&self) -> SyncStatus {
+        let queued = self.offline_queue.lock().unwrap().len();
+        self.sync_state.lock().unwrap().status(queued as u32)
+    }
+
+    /// Check each batch against AnyList's response once it's committed, and
+    /// call `callback` for any list that has drifted from what the batch read
+    /// (e.g. changed elsewhere in the meantime, or some changes not applied)
+    /// Cached lists are forgotten when drift is found, so they're fetched
+    /// again. Pass `null` to stop checking.
+    #[napi]
+    pub fn set_drift_detection(
+        &self,
+        #[napi(ts_arg_type = "(event: DriftEvent) => unknown")] callback: Option<
+            DriftEventCallback,
+        >,
+    ) {
+        *self.drift_events.lock().unwrap() = callback;
+    }
+
+    // ==================== List Settings Methods ====================
+
+    /// Get all of a list's settings
+    #[napi]
+    pub async fn get_list_settings(&self, list_id: String) -> Result<ListSettings> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        find_shopping_list(&data, &list_id)?;
+        let settings = self.list_settings_for(&data, &list_id);
+
+        Ok(ListSettings::new(
+            &settings,
+            linked_favourites_list_id(&data, &list_id),
+        ))
+    }
+
+    /// Change a list's settings
+    #[napi]
+    pub async fn update_list_settings(
+        &self,
+        list_id: String,
+        patch: ListSettingsPatch,
+    ) -> Result<ListSettings> {
+        self.list_change(
+            "updateListSettings",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                find_shopping_list(&data, &list_id)?;
+                let mut settings = self.list_settings_for(&data, &list_id);
+
+                patch.apply(&mut settings);
+                self.save_list_settings(&settings).await?;
+
+                Ok(ListSettings::new(
+                    &settings,
+                    linked_favourites_list_id(&data, &list_id),
+                ))
+            },
+        )
+        .await
+    }
+
+    /// Get a list's reminder and notification settings
+    #[napi]
+    pub async fn get_list_notification_settings(
+        &self,
+        list_id: String,
+    ) -> Result<ListNotificationSettings> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let list = find_shopping_list(&data, &list_id)?;
+        let settings = self.list_settings_for(&data, &list_id);
+
+        Ok(ListNotificationSettings::new(list, &settings))
+    }
+
+    /// Change a list's reminder and notification settings
+    #[napi]
+    pub async fn update_list_notification_settings(
+        &self,
+        list_id: String,
+        patch: ListNotificationSettingsPatch,
+    ) -> Result<ListNotificationSettings> {
+        self.list_change(
+            "updateListNotificationSettings",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                let list = find_shopping_list(&data, &list_id)?;
+                let mut settings = self.list_settings_for(&data, &list_id);
+
+                if let Some(enabled) = patch.location_notifications_enabled {
+                    settings.location_notifications_enabled = Some(enabled);
+                }
+                if let Some(badge_mode) = patch.badge_mode {
+                    settings.badge_mode = Some(badge_mode);
+                }
+                self.save_list_settings(&settings).await?;
+
+                Ok(ListNotificationSettings::new(list, &settings))
+            },
+        )
+        .await
+    }
+
+    // ==================== Category Methods ====================
+
+    /// Create a new category in a list
+    #[napi]
+    pub async fn create_category(
+        &self,
+        list_id: String,
+        category_group_id: String,
+        name: String,
+    ) -> Result<Category> {
+        self.list_change(
+            "createCategory",
+            list_id.clone(),
+            vec![list_id.clone(), category_group_id.clone()],
+            async move {
+                let category = self
+                    .inner
+                    .create_category(&list_id, &category_group_id, &name)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(Category::from(&category))
+            },
+        )
+        .await
+    }
+
+    /// Get the category in a group with this name (ignoring case and extra
+    /// spaces), creating it if it doesn't exist
+    #[napi]
+    pub async fn ensure_category(
+        &self,
+        list_id: String,
+        category_group_id: String,
+        name: String,
+    ) -> Result<Category> {
+        self.list_change(
+            "ensureCategory",
+            list_id.clone(),
+            vec![list_id.clone(), category_group_id.clone()],
+            async move {
+                let _guard = self.ensure_lock.lock().await;
+
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                find_shopping_list(&data, &list_id)?;
+                let group = category_groups(&data, &list_id)
+                    .find(|g| g.identifier.as_deref() == Some(category_group_id.as_str()))
+                    .ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "Category group with ID {} not found in list {}",
+                            category_group_id, list_id
+                        )))
+                    })?;
+
+                let normalized = normalize_name(&name);
+                let existing = group
+                    .categories
+                    .iter()
+                    .find(|c| normalize_name(c.name.as_deref().unwrap_or_default()) == normalized);
+                if let Some(category) = existing {
+                    return Ok(Category::from(category));
+                }
+
+                let category = self
+                    .inner
+                    .create_category(&list_id, &category_group_id, name.trim())
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(Category::from(&category))
+            },
+        )
+        .await
+    }
+
+    /// Delete a category from a list
+    #[napi]
+    pub async fn delete_category(
+        &self,
+        list_id: String,
+        category_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteCategory",
+            Some(list_id.clone()),
+            vec![list_id.clone(), category_id.clone()],
+            async move {
+                self.inner
+                    .delete_category(&list_id, &category_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Rename a category
+    #[napi]
+    pub async fn rename_category(
+        &self,
+        list_id: String,
+        category_group_id: String,
+        category_id: String,
+        new_name: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "renameCategory",
+            Some(list_id.clone()),
+            vec![
+                list_id.clone(),
+                category_group_id.clone(),
+                category_id.clone(),
+            ],
+            async move {
+                self.inner
+                    .rename_category(&list_id, &category_group_id, &category_id, &new_name)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Get a list's category groups, each with its categories
+    #[napi]
+    pub async fn get_category_groups_for_list(
+        &self,
+        list_id: String,
+    ) -> Result<Vec<CategoryGroup>> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        find_shopping_list(&data, &list_id)?;
+
+        Ok(category_groups(&data, &list_id)
+            .map(CategoryGroup::from)
+            .collect())
+    }
+
+    /// Create an empty category group (e.g. "By Meal") in a list
+    #[napi]
+    pub async fn create_category_group(
+        &self,
+        list_id: String,
+        name: String,
+    ) -> Result<CategoryGroup> {
+        self.list_change(
+            "createCategoryGroup",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let group_id = transport::generate_id();
+                let operation_list = PbListOperationList {
+                    operations: vec![operations::create_category_group(
+                        &self.inner,
+                        &list_id,
+                        &group_id,
+                        &name,
+                    )],
+                };
+                transport::post_message(
+                    &self.inner,
+                    "data/shopping-lists/update-v2",
+                    &operation_list,
+                )
+                .await
+                .map_err(to_napi_error)?;
+
+                Ok(CategoryGroup {
+                    id: group_id,
+                    name,
+                    categories: Vec::new(),
+                })
+            },
+        )
+        .await
+    }
+
+    /// Delete a category group, along with its categories, from a list
+    /// The group the list is currently grouped by can't be deleted
+    #[napi]
+    pub async fn delete_category_group(
+        &self,
+        list_id: String,
+        group_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteCategoryGroup",
+            Some(list_id.clone()),
+            vec![list_id.clone(), group_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                find_shopping_list(&data, &list_id)?;
+                if !category_groups(&data, &list_id)
+                    .any(|g| g.identifier.as_deref() == Some(group_id.as_str()))
+                {
+                    return Err(to_napi_error(AnyListError::NotFound(format!(
+                        "Category group with ID {} not found in list {}",
+                        group_id, list_id
+                    ))));
+                }
+                let settings = self.list_settings_for(&data, &list_id);
+                if settings.list_category_group_id.as_deref() == Some(group_id.as_str()) {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        format!(
+                            "Category group {} is the one list {} is grouped by; \
+                             switch to another group first",
+                            group_id, list_id
+                        ),
+                    ));
+                }
+
+                let operation_list = PbListOperationList {
+                    operations: vec![operations::remove_category_group(
+                        &self.inner,
+                        &list_id,
+                        &group_id,
+                    )],
+                };
+                transport::post_message(
+                    &self.inner,
+                    "data/shopping-lists/update-v2",
+                    &operation_list,
+                )
+                .await
+                .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Get the category group a list's items are grouped by, or none if the
+    /// list hasn't chosen one
+    #[napi]
+    pub async fn get_active_category_group(
+        &self,
+        list_id: String,
+    ) -> Result<Option<CategoryGroup>> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        find_shopping_list(&data, &list_id)?;
+        let settings = self.list_settings_for(&data, &list_id);
+
+        let Some(group_id) = settings.list_category_group_id else {
+            return Ok(None);
+        };
+        let group = category_groups(&data, &list_id)
+            .find(|g| g.identifier.as_deref() == Some(group_id.as_str()))
+            .map(CategoryGroup::from);
+
+        Ok(group)
+    }
+
+    /// Group a list's items by one of its category groups (e.g. switch from
+    /// "By Aisle" to "By Recipe")
+    #[napi]
+    pub async fn set_active_category_group(
+        &self,
+        list_id: String,
+        group_id: String,
+    ) -> Result<CategoryGroup> {
+        self.list_change(
+            "setActiveCategoryGroup",
+            list_id.clone(),
+            vec![list_id.clone(), group_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                find_shopping_list(&data, &list_id)?;
+                let group = category_groups(&data, &list_id)
+                    .find(|g| g.identifier.as_deref() == Some(group_id.as_str()))
+                    .ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "Category group with ID {} not found in list {}",
+                            group_id, list_id
+                        )))
+                    })?;
+
+                let mut settings = self.list_settings_for(&data, &list_id);
+                settings.list_category_group_id = Some(group_id);
+                self.save_list_settings(&settings).await?;
+
+                Ok(CategoryGroup::from(group))
+            },
+        )
+        .await
+    }
+
+    // ==================== Category Rule Methods ====================
+
+    /// Add a rule that categorizes items added without a category
+    /// Exact rules are tried first, then the longest matching prefix, then
+    /// regular expressions in the order they were added
+    #[napi]
+    pub fn add_category_rule(&self, rule: CategoryRuleInput) -> Result<CategoryRule> {
+        if rule.pattern.trim().is_empty() {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Category rule pattern must not be empty",
+            ));
+        }
+        let rule = CategoryRule {
+            id: transport::generate_id(),
+            pattern: rule.pattern,
+            match_type: rule.match_type.unwrap_or(CategoryRuleMatch::Exact),
+            category: rule.category,
+            list_id: rule.list_id,
+        };
+        self.category_rules.lock().unwrap().add(rule.clone())?;
+
+        Ok(rule)
+    }
+
+    /// Remove a category rule, returning whether there was one with that ID
+    #[napi]
+    pub fn remove_category_rule(&self, id: String) -> Result<bool> {
+        self.category_rules.lock().unwrap().remove(&id)
+    }
+
+    /// Get the category rules, in the order they were added
+    #[napi]
+    pub fn get_category_rules(&self) -> Vec<CategoryRule> {
+        self.category_rules.lock().unwrap().rules()
+    }
+
+    /// Keep the category rules in a JSON file, loading them from it if it
+    /// exists and saving the current rules to it otherwise
+    /// The file is rewritten whenever a rule is added or removed
+    #[napi]
+    pub fn set_category_rules_file(&self, path: String) -> Result<Vec<CategoryRule>> {
+        let mut rules = self.category_rules.lock().unwrap();
+        rules.use_file(path.into())?;
+
+        Ok(rules.rules())
+    }
+
+    /// Get the category the rules give an item name, if any
+    #[napi]
+    pub fn match_category_rule(&self, name: String, list_id: Option<String>) -> Option<String> {
+        self.category_rules
+            .lock()
+            .unwrap()
+            .category_for(&name, list_id.as_deref())
+    }
+
+    // ==================== Store Methods ====================
+
+    /// Get all stores for a list
+    #[napi]
+    pub async fn get_stores_for_list(&self, list_id: String) -> Result<Vec<Store>> {
+        let stores = self
+            .inner
+            .get_stores_for_list(&list_id)
+            .await
+            .map_err(to_napi_error)?;
+
+        Ok(stores.iter().map(Store::from).collect())
+    }
+
+    /// Create a new store for a list
+    #[napi]
+    pub async fn create_store(&self, list_id: String, name: String) -> Result<Store> {
+        self.list_change(
+            "createStore",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let store = self
+                    .inner
+                    .create_store(&list_id, &name)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(Store::from(&store))
+            },
+        )
+        .await
+    }
+
+    /// Get the store in a list with this name (ignoring case and extra
+    /// spaces), creating it if it doesn't exist
+    #[napi]
+    pub async fn ensure_store(&self, list_id: String, name: String) -> Result<Store> {
+        self.list_change(
+            "ensureStore",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let _guard = self.ensure_lock.lock().await;
+
+                let stores = self
+                    .inner
+                    .get_stores_for_list(&list_id)
+                    .await
+                    .map_err(to_napi_error)?;
+                let normalized = normalize_name(&name);
+                if let Some(store) = stores
+                    .iter()
+                    .find(|s| normalize_name(s.name()) == normalized)
+                {
+                    return Ok(Store::from(store));
+                }
+
+                let store = self
+                    .inner
+                    .create_store(&list_id, name.trim())
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(Store::from(&store))
+            },
+        )
+        .await
+    }
+
+    /// Update a store's name
+    #[napi]
+    pub async fn update_store(
+        &self,
+        list_id: String,
+        store_id: String,
+        new_name: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "updateStore",
+            Some(list_id.clone()),
+            vec![list_id.clone(), store_id.clone()],
+            async move {
+                self.inner
+                    .update_store(&list_id, &store_id, &new_name)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Get store filters for a list
+    #[napi]
+    pub async fn get_store_filters_for_list(&self, list_id: String) -> Result<Vec<StoreFilter>> {
+        let filters = self
+            .inner
+            .get_store_filters_for_list(&list_id)
+            .await
+            .map_err(to_napi_error)?;
+
+        Ok(filters.iter().map(StoreFilter::from).collect())
+    }
+
+    /// Delete a store from a list
+    #[napi]
+    pub async fn delete_store(
+        &self,
+        list_id: String,
+        store_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteStore",
+            Some(list_id.clone()),
+            vec![list_id.clone(), store_id.clone()],
+            async move {
+                self.inner
+                    .delete_store(&list_id, &store_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Get the items on a list to buy at one store, in list order
+    #[napi]
+    pub async fn get_items_by_store(
+        &self,
+        list_id: String,
+        store_id: String,
+        options: Option<ItemsByStoreOptions>,
+    ) -> Result<Vec<ListItem>> {
+        let options = options.unwrap_or_default();
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let list = find_shopping_list(&data, &list_id)?;
+        let has_store = list_response(&data, &list_id)
+            .is_some_and(|r| r.stores.iter().any(|store| store.identifier == store_id));
+        if !has_store {
+            return Err(to_napi_error(AnyListError::NotFound(format!(
+                "Store with ID {} not found in list {}",
+                store_id, list_id
+            ))));
+        }
+
+        let include_unassigned = options.include_unassigned.unwrap_or(true);
+        let include_checked = options.include_checked.unwrap_or(false);
+        Ok(list
+            .items
+            .iter()
+            .filter(|item| include_checked || item.checked != Some(true))
+            .filter(|item| {
+                item.store_ids.contains(&store_id)
+                    || (include_unassigned && item.store_ids.is_empty())
+            })
+            .map(ListItem::from)
+            .collect())
+    }
+
+    // ==================== Favourites Methods ====================
+
+    /// Get all favourite items across all lists
+    #[napi]
+    pub async fn get_favourites(&self) -> Result<Vec<FavouriteItem>> {
+        let lists = self.get_favourites_lists().await?;
+
+        Ok(lists.into_iter().flat_map(|list| list.items).collect())
+    }
+
+    /// Get all favourites lists (starter lists)
+    #[napi]
+    pub async fn get_favourites_lists(&self) -> Result<Vec<FavouritesList>> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+
+        Ok(starter_lists(&data).map(FavouritesList::from).collect())
+    }
+
+    /// Get favourites for a specific shopping list
+    #[napi]
+    pub async fn get_favourites_for_list(
+        &self,
+        shopping_list_id: String,
+    ) -> Result<FavouritesList> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let list = linked_favourites_list(&data, &shopping_list_id).ok_or_else(|| {
+            to_napi_error(AnyListError::NotFound(format!(
+                "No favourites list for shopping list {}",
+                shopping_list_id
+            )))
+        })?;
+
+        Ok(FavouritesList::from(list))
+    }
+
+    /// Rename a favourites list
+    #[napi]
+    pub async fn rename_favourites_list(
+        &self,
+        id: String,
+        new_name: String,
+    ) -> Result<FavouritesList> {
+        self.journaled("renameFavouritesList", Vec::new(), async move {
+            let list = self.favourites_list(&id).await?;
+
+            let old_name = list.name.clone().unwrap_or_default();
+            self.post_starter_list_rename(&id, &old_name, &new_name)
+                .await?;
+
+            let mut renamed = FavouritesList::from(&list);
+            renamed.name = new_name;
+            Ok(renamed)
+        })
+        .await
+    }
+
+    /// Add a favourite item to the default list
+    #[napi]
+    pub async fn add_favourite(
+        &self,
+        name: String,
+        category: Option<String>,
+    ) -> Result<FavouriteItem> {
+        self.journaled("addFavourite", Vec::new(), async move {
+            let item = self
+                .inner
+                .add_favourite(&name, category.as_deref())
+                .await
+                .map_err(to_napi_error)?;
+
+            Ok(FavouriteItem::from(&item))
+        })
+        .await
+    }
+
+    /// Add a favourite item to a specific list
+    #[napi]
+    pub async fn add_favourite_to_list(
+        &self,
+        list_id: String,
+        name: String,
+        category: Option<String>,
+    ) -> Result<FavouriteItem> {
+        self.list_change(
+            "addFavouriteToList",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                let item = self
+                    .inner
+                    .add_favourite_to_list(&list_id, &name, category.as_deref())
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(FavouriteItem::from(&item))
+            },
+        )
+        .await
+    }
+
+    /// Add several favourites to a list in a single request, in the order given
+    #[napi]
+    pub async fn add_favourites(
+        &self,
+        list_id: String,
+        items: Vec<FavouriteInput>,
+    ) -> Result<Vec<FavouriteItem>> {
+        self.list_change(
+            "addFavourites",
+            list_id.clone(),
+            vec![list_id.clone()],
+            async move {
+                if let Some(blank) = items.iter().position(|i| i.name.trim().is_empty()) {
+                    return Err(Error::new(
+                        Status::InvalidArg,
+                        format!("Favourite at index {} has no name", blank),
+                    ));
+                }
+                if items.is_empty() {
+                    return Ok(Vec::new());
+                }
+                self.favourites_list(&list_id).await?;
+
+                let ops: Vec<_> = items
+                    .into_iter()
+                    .map(|item| {
+                        operations::add_starter_item(
+                            &self.inner,
+                            &list_id,
+                            item.name.trim(),
+                            item.quantity,
+                            item.details,
+                            item.category,
+                        )
+                    })
+                    .collect();
+                let added = ops
+                    .iter()
+                    .filter_map(|op| op.list_item.as_ref())
+                    .map(|item| FavouriteItem::from_pb(item, &list_id))
+                    .collect();
+
+                let operation_list = PbStarterListOperationList { operations: ops };
+                transport::post_message(&self.inner, "data/starter-lists/update", &operation_list)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(added)
+            },
+        )
+        .await
+    }
+
+    /// Add a shopping list's crossed off items to its linked favourites list,
+    /// skipping any already there, and return the new favourites
+    #[napi]
+    pub async fn promote_checked_items_to_favourites(
+        &self,
+        shopping_list_id: String,
+    ) -> Result<Vec<FavouriteItem>> {
+        self.list_change(
+            "promoteCheckedItemsToFavourites",
+            shopping_list_id.clone(),
+            vec![shopping_list_id.clone()],
+            async move {
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                let list = find_shopping_list(&data, &shopping_list_id)?;
+                let favourites =
+                    linked_favourites_list(&data, &shopping_list_id).ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "No favourites list for shopping list {}",
+                            shopping_list_id
+                        )))
+                    })?;
+
+                let mut known: HashSet<String> = favourites
+                    .items
+                    .iter()
+                    .filter_map(|item| item.name.as_deref())
+                    .map(|name| name.trim().to_lowercase())
+                    .collect();
+                let ops: Vec<_> = list
+                    .items
+                    .iter()
+                    .filter(|item| item.checked == Some(true))
+                    .filter_map(|item| Some((item, item.name.as_deref()?.trim())))
+                    .filter(|(_, name)| !name.is_empty() && known.insert(name.to_lowercase()))
+                    .map(|(item, name)| {
+                        operations::add_starter_item(
+                            &self.inner,
+                            &favourites.identifier,
+                            name,
+                            item.quantity.clone(),
+                            item.details.clone(),
+                            item.category.clone(),
+                        )
+                    })
+                    .collect();
+                if ops.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let added = ops
+                    .iter()
+                    .filter_map(|op| op.list_item.as_ref())
+                    .map(|item| FavouriteItem::from_pb(item, &favourites.identifier))
+                    .collect();
+
+                let operation_list = PbStarterListOperationList { operations: ops };
+                transport::post_message(&self.inner, "data/starter-lists/update", &operation_list)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(added)
+            },
+        )
+        .await
+    }
+
+    /// Set the order of a favourites list (e.g. by store aisle)
+    /// Favourites that aren't listed keep their order after the listed ones
+    #[napi]
+    pub async fn reorder_favourites(
+        &self,
+        list_id: String,
+        item_ids: Vec<String>,
+    ) -> Result<Vec<FavouriteItem>> {
+        self.list_change(
+            "reorderFavourites",
+            list_id.clone(),
+            [list_id.clone()]
+                .into_iter()
+                .chain(item_ids.clone())
+                .collect(),
+            async move {
+                let list = self.favourites_list(&list_id).await?;
+                let mut items = sorted_favourites(&list);
+
+                let mut ordered = Vec::with_capacity(items.len());
+                for item_id in &item_ids {
+                    let index = items
+                        .iter()
+                        .position(|item| &item.identifier == item_id)
+                        .ok_or_else(|| {
+                            to_napi_error(AnyListError::NotFound(format!(
+                                "Favourite with ID {} not found in list {}",
+                                item_id, list_id
+                            )))
+                        })?;
+                    ordered.push(items.remove(index));
+                }
+                ordered.extend(items);
+                if ordered.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let operation_list = PbStarterListOperationList {
+                    operations: ordered
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            operations::set_starter_item_field(
+                                &self.inner,
+                                "set-starter-list-item-sort-index",
+                                &list_id,
+                                &item.identifier,
+                                &i.to_string(),
+                            )
+                        })
+                        .collect(),
+                };
+                transport::post_message(&self.inner, "data/starter-lists/update", &operation_list)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(ordered
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, item)| FavouriteItem {
+                        sort_index: Some(i as i32),
+                        ..FavouriteItem::from_pb(item, &list_id)
+                    })
+                    .collect())
+            },
+        )
+        .await
+    }
+
+    #[napi]
+    pub async fn remove_favourite(
+        &self,
+        list_id: String,
+        item_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "removeFavourite",
+            Some(list_id.clone()),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                self.inner
+                    .remove_favourite(&list_id, &item_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
+
+    /// Change only the supplied fields of a favourite, keeping its place in
+    /// the list
+    #[napi]
+    pub async fn update_favourite(
+        &self,
+        list_id: String,
+        item_id: String,
+        patch: FavouritePatch,
+    ) -> Result<FavouriteItem> {
+        self.list_change(
+            "updateFavourite",
+            list_id.clone(),
+            vec![list_id.clone(), item_id.clone()],
+            async move {
+                let list = self.favourites_list(&list_id).await?;
+                let existing = list
+                    .items
+                    .iter()
+                    .find(|f| f.identifier == item_id)
+                    .ok_or_else(|| {
+                        Error::new(Status::GenericFailure, "Favourite item not found")
+                    })?;
+
+                let mut item = FavouriteItem::from_pb(existing, &list_id);
+                let mut ops = Vec::new();
+                let mut set = |handler_id: &str, value: &str| {
+                    ops.push(operations::set_starter_item_field(
+                        &self.inner,
+                        handler_id,
+                        &list_id,
+                        &item_id,
+                        value,
+                    ))
+                };
+
+                if let Some(name) = patch.name {
+                    set("set-starter-list-item-name", &name);
+                    item.name = name;
+                }
+                if let Some(quantity) = patch.quantity {
+                    set("set-starter-list-item-quantity", &quantity);
+                    item.quantity = Some(quantity);
+                }
+                if let Some(details) = patch.details {
+                    set("set-starter-list-item-details", &details);
+                    item.details = Some(details);
+                }
+                if let Some(category) = patch.category {
+                    set("set-starter-list-item-category", &category);
+                    item.category = Some(category);
+                }
+
+                if !ops.is_empty() {
+                    let operation_list = PbStarterListOperationList { operations: ops };
+                    transport::post_message(
+                        &self.inner,
+                        "data/starter-lists/update",
+                        &operation_list,
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+                }
+
+                Ok(item)
+            },
+        )
+        .await
+    }
+
+    /// Add a favourite item to a shopping list
+    #[napi]
+    pub async fn add_favourite_to_shopping_list(
+        &self,
+        favourite_list_id: String,
+        favourite_id: String,
+        shopping_list_id: String,
+    ) -> Result<ListItem> {
+        self.list_change(
+            "addFavouriteToShoppingList",
+            shopping_list_id.clone(),
+            vec![
+                favourite_list_id.clone(),
+                favourite_id.clone(),
+                shopping_list_id.clone(),
+            ],
+            async move {
+                // First get the favourite item
+                let favourites_list = self.favourites_list(&favourite_list_id).await?;
+
+                let favourite = favourites_list
+                    .items
+                    .iter()
+                    .find(|f| f.identifier == favourite_id)
+                    .ok_or_else(|| {
+                        Error::new(Status::GenericFailure, "Favourite item not found")
+                    })?;
+
+                let item = self
+                    .inner
+                    .add_item_with_details(
+                        &shopping_list_id,
+                        favourite.name.as_deref().unwrap_or_default(),
+                        favourite.quantity.as_deref(),
+                        favourite.details.as_deref(),
+                        favourite.category.as_deref(),
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+                self.record_favourite_use(&favourite_id);
+
+                Ok(ListItem::from(&item))
+            },
+        )
+        .await
+    }
+
+    /// Add every favourite in a favourites list to a shopping list in a single
+    /// request, returning the items added or unchecked
+    #[napi]
+    pub async fn add_all_favourites_to_shopping_list(
+        &self,
+        favourites_list_id: String,
+        shopping_list_id: String,
+        options: Option<AddAllFavouritesOptions>,
+    ) -> Result<Vec<ListItem>> {
+        self.list_change(
+            "addAllFavouritesToShoppingList",
+            shopping_list_id.clone(),
+            vec![favourites_list_id.clone(), shopping_list_id.clone()],
+            async move {
+                let options = options.unwrap_or_default();
+                let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+                let list = find_shopping_list(&data, &shopping_list_id)?;
+                let favourites = starter_lists(&data)
+                    .find(|l| l.identifier == favourites_list_id)
+                    .ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "Favourites list with ID {} not found",
+                            favourites_list_id
+                        )))
+                    })?;
+
+                let skip_existing = options.skip_existing == Some(true);
+                let mut existing: HashMap<String, &PbListItem> = HashMap::new();
+                if skip_existing {
+                    // Prefer an unchecked match, so nothing is unchecked needlessly
+                    for item in &list.items {
+                        let Some(name) = item.name.as_deref() else {
+                            continue;
+                        };
+                        let key = name.trim().to_lowercase();
+                        if item.checked != Some(true) || !existing.contains_key(&key) {
+                            existing.insert(key, item);
+                        }
+                    }
+                }
+
+                let mut ops = Vec::new();
+                let mut items = Vec::new();
+                let mut used = Vec::new();
+                let mut seen = HashSet::new();
+                for favourite in sorted_favourites(favourites) {
+                    let name = favourite.name.as_deref().unwrap_or_default().trim();
+                    let key = name.to_lowercase();
+                    if skip_existing && !seen.insert(key.clone()) {
+                        continue;
+                    }
+                    match existing.get(&key) {
+                        Some(item) if item.checked == Some(true) => {
+                            ops.push(operations::set_item_checked(
+                                &self.inner,
+                                &shopping_list_id,
+                                &item.identifier,
+                                false,
+                            ));
+                            items.push(ListItem {
+                                checked: false,
+                                ..ListItem::from(*item)
+                            });
+                        }
+                        Some(_) => continue,
+                        None => {
+                            let op = operations::add_item(
+                                &self.inner,
+                                &shopping_list_id,
+                                name,
+                                favourite.quantity.clone(),
+                                favourite.details.clone(),
+                                favourite.category.clone(),
+                                None,
+                            );
+                            items.extend(op.list_item.as_ref().map(ListItem::from));
+                            ops.push(op);
+                        }
+                    }
+                    used.push(favourite.identifier.clone());
+                }
+
+                if !ops.is_empty() {
+                    let operation_list = PbListOperationList { operations: ops };
+                    transport::post_message(
+                        &self.inner,
+                        "data/shopping-lists/update",
+                        &operation_list,
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+                }
+                for favourite_id in &used {
+                    self.record_favourite_use(favourite_id);
+                }
+
+                Ok(items)
+            },
+        )
+        .await
+    }
+
+    /// Get how often each favourite in a list has been added to a shopping
+    /// list, most used first
+    /// AnyList doesn't track this, so only additions made through this client are counted
+    #[napi]
+    pub async fn get_favourite_stats(&self, list_id: String) -> Result<Vec<FavouriteStats>> {
+        let list = self.favourites_list(&list_id).await?;
+        let usage = self.favourite_usage.lock().unwrap();
+
+        let mut stats: Vec<FavouriteStats> = sorted_favourites(&list)
+            .into_iter()
+            .map(|item| {
+                let used = usage.get(&item.identifier);
+                FavouriteStats {
+                    favourite_id: item.identifier.clone(),
+                    name: item.name.clone().unwrap_or_default(),
+                    times_added: used.map_or(0, |u| u.times_added),
+                    last_added_at: used.map(|u| u.last_added_at),
+                }
+            })
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.times_added));
+
+        Ok(stats)
+    }
+
+    // ==================== Meal Planning Methods ====================
+
+    /// Set the time zone (an IANA name like "America/Vancouver") that `Date`s
+    /// passed to meal plan methods are read in, or `null` for the system's
+    /// A `Date` falls on the day it is in this time zone, so a server running
+    /// in UTC can still plan meals on its users' local days
+    #[napi]
+    pub fn set_time_zone(&self, time_zone: Option<String>) -> Result<()> {
+        let time_zone = time_zone
+            .map(|name| {
+                name.parse::<chrono_tz::Tz>().map_err(|_| {
+                    Error::new(Status::InvalidArg, format!("Unknown time zone '{}'", name))
+                })
+            })
+            .transpose()?;
+        *self.time_zone.lock().unwrap() = time_zone;
+        Ok(())
+    }
+
+    /// Get the time zone set with `setTimeZone`, if any
+    #[napi]
+    pub fn get_time_zone(&self) -> Option<String> {
+        self.time_zone
+            .lock()
+            .unwrap()
+            .map(|time_zone| time_zone.name().to_string())
+    }
+
+    /// Get the meal plan calendar, whose ID is needed to create, update, and
+    /// delete meal plan events
+    #[napi]
+    pub async fn get_meal_plan_calendar(&self) -> Result<MealPlanCalendar> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let response = data
+            .meal_planning_calendar_response
+            .ok_or_else(|| Error::new(Status::GenericFailure, "No meal plan calendar found"))?;
+
+        let mut labels = response.labels;
+        labels.sort_by_key(|label| label.sort_index.unwrap_or(i32::MAX));
+
+        Ok(MealPlanCalendar {
+            id: response.calendar_id,
+            labels: labels
+                .into_iter()
+                .map(|label| MealPlanLabel {
+                    id: label.identifier,
+                    name: label.name.unwrap_or_default(),
+                    hex_color: label.hex_color,
+                })
+                .collect(),
+        })
+    }
+
+    /// Get meal plan events for a date range
+    #[napi]
+    pub async fn get_meal_plan_events(
+        &self,
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+    ) -> Result<Vec<MealPlanEvent>> {
+        let (start, end) = self.date_range(&start_date, &end_date)?;
+        let (_, mut events) = self.raw_meal_plan_events(start, end).await?;
+        events.sort_by(|a, b| {
+            (&a.date, a.order_added_sort_index).cmp(&(&b.date, b.order_added_sort_index))
+        });
+
+        Ok(events.iter().map(MealPlanEvent::from).collect())
     }
 
-    /// Create a client from saved tokens (for resuming sessions)
+    /// Get a meal plan event by ID
     #[napi]
-    pub fn from_tokens(tokens: SavedTokens) -> Result<AnyListClient> {
-        let rs_tokens: RsSavedTokens = tokens.into();
-        let client = RsClient::from_tokens(rs_tokens).map_err(to_napi_error)?;
-
-        Ok(AnyListClient { inner: client })
+    pub async fn get_meal_plan_event_by_id(&self, event_id: String) -> Result<MealPlanEvent> {
+        let (_, event) = self.raw_meal_plan_event(&event_id).await?;
+        Ok(MealPlanEvent::from(&event))
     }
 
-    /// Get the saved tokens for this session
+    /// Create a meal plan event
     #[napi]
-    pub fn get_tokens(&self) -> Result<SavedTokens> {
-        let tokens = self.inner.export_tokens().map_err(to_napi_error)?;
-        Ok(tokens.into())
+    pub async fn create_meal_plan_event(
+        &self,
+        calendar_id: String,
+        date: MealPlanDate,
+        recipe_id: Option<String>,
+        title: Option<String>,
+        label_id: Option<String>,
+    ) -> Result<MealPlanEvent> {
+        self.journaled(
+            "createMealPlanEvent",
+            [calendar_id.clone()]
+                .into_iter()
+                .chain(recipe_id.clone())
+                .chain(label_id.clone())
+                .collect(),
+            self.add_meal_plan_event(
+                &calendar_id,
+                &date,
+                recipe_id.as_deref(),
+                title.as_deref(),
+                label_id.as_deref(),
+            ),
+        )
+        .await
     }
 
-    /// Get all lists
+    /// Add a note to a day of the meal plan (e.g. "Eating out")
     #[napi]
-    pub async fn get_lists(&self) -> Result<Vec<List>> {
-        let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
-
-        Ok(lists.iter().map(List::from).collect())
+    pub async fn create_note_event(
+        &self,
+        date: MealPlanDate,
+        text: String,
+        label_id: Option<String>,
+    ) -> Result<MealPlanEvent> {
+        self.journaled(
+            "createNoteEvent",
+            label_id.iter().cloned().collect(),
+            async move {
+                if text.trim().is_empty() {
+                    return Err(Error::new(Status::InvalidArg, "Note must not be empty"));
+                }
+                self.day(&date)?;
+                let calendar = self.get_meal_plan_calendar().await?;
+
+                self.add_meal_plan_event(
+                    &calendar.id,
+                    &date,
+                    None,
+                    Some(&text),
+                    label_id.as_deref(),
+                )
+                .await
+            },
+        )
+        .await
     }
 
-    /// Create a new list
+    /// Plan leftovers of a recipe event on another day
+    /// Leftovers are left out when adding meal plan ingredients to a list
     #[napi]
-    pub async fn create_list(&self, name: String) -> Result<List> {
-        let list = self.inner.create_list(&name).await.map_err(to_napi_error)?;
-
-        Ok(List::from(&list))
+    pub async fn create_leftovers_event(
+        &self,
+        date: MealPlanDate,
+        source_event_id: String,
+        label_id: Option<String>,
+    ) -> Result<MealPlanEvent> {
+        self.journaled(
+            "createLeftoversEvent",
+            [source_event_id.clone()]
+                .into_iter()
+                .chain(label_id.clone())
+                .collect(),
+            async move {
+                self.day(&date)?;
+                let (calendar_id, source) = self.raw_meal_plan_event(&source_event_id).await?;
+                let recipe_id = source.recipe_id.ok_or_else(|| {
+                    Error::new(
+                        Status::InvalidArg,
+                        format!("Meal plan event {} has no recipe", source_event_id),
+                    )
+                })?;
+
+                self.add_meal_plan_event(
+                    &calendar_id,
+                    &date,
+                    Some(&recipe_id),
+                    Some(LEFTOVERS_TITLE),
+                    label_id.as_deref(),
+                )
+                .await
+            },
+        )
+        .await
     }
 
-    /// Get a specific list by ID
+    /// Create several meal plan events in a single request
+    /// Events on the same day keep the order they're given in
     #[napi]
-    pub async fn get_list_by_id(&self, list_id: String) -> Result<List> {
-        let list = self
-            .inner
-            .get_list_by_id(&list_id)
-            .await
-            .map_err(to_napi_error)?;
-
-        Ok(List::from(&list))
+    pub async fn bulk_create_meal_plan_events(
+        &self,
+        events: Vec<MealPlanEventInput>,
+    ) -> Result<Vec<MealPlanEvent>> {
+        self.journaled("bulkCreateMealPlanEvents", Vec::new(), async move {
+            let days = events
+                .iter()
+                .map(|event| Ok(self.day(&event.date)?.format("%Y-%m-%d").to_string()))
+                .collect::<Result<Vec<String>>>()?;
+            let calendar = self.get_meal_plan_calendar().await?;
+
+            let mut day_counts: HashMap<String, i32> = HashMap::new();
+            let events = events
+                .into_iter()
+                .zip(days)
+                .map(|(event, date)| {
+                    let count = day_counts.entry(date.clone()).or_insert(0);
+                    *count += 1;
+                    PbCalendarEvent {
+                        date: Some(date),
+                        title: event.title,
+                        details: event.details,
+                        recipe_id: event.recipe_id,
+                        label_id: event.label_id,
+                        order_added_sort_index: Some(*count - 1),
+                        recipe_scale_factor: Some(1.0),
+                        ..Default::default()
+                    }
+                })
+                .collect();
+
+            self.add_meal_plan_events(&calendar.id, events).await
+        })
+        .await
     }
 
-    /// Get a list by name
+    /// Update a meal plan event
     #[napi]
-    pub async fn get_list_by_name(&self, name: String) -> Result<List> {
-        let list = self
-            .inner
-            .get_list_by_name(&name)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn update_meal_plan_event(
+        &self,
+        calendar_id: String,
+        event_id: String,
+        date: MealPlanDate,
+        recipe_id: Option<String>,
+        title: Option<String>,
+        label_id: Option<String>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "updateMealPlanEvent",
+            None,
+            [calendar_id.clone(), event_id.clone()]
+                .into_iter()
+                .chain(recipe_id.clone())
+                .chain(label_id.clone())
+                .collect(),
+            async move {
+                let date = self.day(&date)?.format("%Y-%m-%d").to_string();
+                self.inner
+                    .update_meal_plan_event(
+                        &calendar_id,
+                        &event_id,
+                        &date,
+                        recipe_id.as_deref(),
+                        title.as_deref(),
+                        label_id.as_deref(),
+                    )
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
 
-        Ok(List::from(&list))
+    /// Delete a meal plan event
+    #[napi]
+    pub async fn delete_meal_plan_event(
+        &self,
+        calendar_id: String,
+        event_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteMealPlanEvent",
+            None,
+            vec![calendar_id.clone(), event_id.clone()],
+            async move {
+                self.inner
+                    .delete_meal_plan_event(&calendar_id, &event_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
     }
 
-    /// Rename a list
+    /// Set the order of a day's meal plan events (e.g. breakfast, lunch, dinner)
+    /// Events on that day that aren't listed keep their order after the listed ones
     #[napi]
-    pub async fn rename_list(&self, list_id: String, new_name: String) -> Result<()> {
-        self.inner
-            .rename_list(&list_id, &new_name)
+    pub async fn reorder_meal_plan_events(
+        &self,
+        date: MealPlanDate,
+        event_ids: Vec<String>,
+    ) -> Result<Vec<MealPlanEvent>> {
+        self.journaled("reorderMealPlanEvents", event_ids.clone(), async move {
+            let day = self.day(&date)?;
+            let (calendar_id, mut events) = self.raw_meal_plan_events(day, day).await?;
+            events.sort_by_key(|event| event.order_added_sort_index);
+
+            let mut ordered = Vec::with_capacity(events.len());
+            for event_id in &event_ids {
+                let index = events
+                    .iter()
+                    .position(|event| &event.identifier == event_id)
+                    .ok_or_else(|| {
+                        to_napi_error(AnyListError::NotFound(format!(
+                            "Meal plan event with ID {} not found on {}",
+                            event_id, day
+                        )))
+                    })?;
+                ordered.push(events.remove(index));
+            }
+            ordered.extend(events);
+            if ordered.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let operations = PbCalendarOperationList {
+                operations: ordered
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(i, event)| {
+                        event.order_added_sort_index = Some(i as i32);
+                        operations::update_event(&self.inner, &calendar_id, event.clone())
+                    })
+                    .collect(),
+            };
+            transport::post_message(
+                &self.inner,
+                "data/meal-planning-calendar/update",
+                &operations,
+            )
             .await
             .map_err(to_napi_error)?;
 
-        Ok(())
+            Ok(ordered.iter().map(MealPlanEvent::from).collect())
+        })
+        .await
     }
 
-    /// Add an item to a list
+    /// Copy every meal plan event in the week starting on one date to the
+    /// week starting on another (both YYYY-MM-DD), returning the new events
     #[napi]
-    pub async fn add_item(&self, list_id: String, name: String) -> Result<ListItem> {
-        let item = self
-            .inner
-            .add_item(&list_id, &name)
-            .await
-            .map_err(to_napi_error)?;
-
-        Ok(ListItem::from(&item))
+    pub async fn copy_meal_plan_week(
+        &self,
+        from_start_date: MealPlanDate,
+        to_start_date: MealPlanDate,
+    ) -> Result<Vec<MealPlanEvent>> {
+        self.journaled("copyMealPlanWeek", Vec::new(), async move {
+            let from = self.day(&from_start_date)?;
+            let offset = self.day(&to_start_date)? - from;
+
+            let (calendar_id, events) = self
+                .raw_meal_plan_events(from, from + chrono::Days::new(6))
+                .await?;
+            let events = events
+                .into_iter()
+                .filter_map(|event| {
+                    let date = parse_date(event.date.as_deref()?).ok()? + offset;
+                    Some(PbCalendarEvent {
+                        date: Some(date.format("%Y-%m-%d").to_string()),
+                        ..event
+                    })
+                })
+                .collect();
+
+            self.add_meal_plan_events(&calendar_id, events).await
+        })
+        .await
     }
 
-    /// Add an item with details to a list
+    /// Save the meal plan events in the week starting on a date (YYYY-MM-DD)
+    /// as a named template
+    /// AnyList has nowhere to store templates, so they're kept by this client only
     #[napi]
-    pub async fn add_item_with_details(
+    pub async fn save_meal_plan_template(
         &self,
-        list_id: String,
         name: String,
-        quantity: Option<String>,
-        note: Option<String>,
-        category: Option<String>,
-    ) -> Result<ListItem> {
-        let item = self
-            .inner
-            .add_item_with_details(
-                &list_id,
-                &name,
-                quantity.as_deref(),
-                note.as_deref(),
-                category.as_deref(),
-            )
-            .await
-            .map_err(to_napi_error)?;
+        week_start: MealPlanDate,
+    ) -> Result<MealPlanTemplate> {
+        self.journaled("saveMealPlanTemplate", Vec::new(), async move {
+            let start = self.day(&week_start)?;
+            let (_, events) = self
+                .raw_meal_plan_events(start, start + chrono::Days::new(6))
+                .await?;
+
+            let mut events: Vec<(u32, PbCalendarEvent)> = events
+                .into_iter()
+                .filter_map(|event| {
+                    let date = parse_date(event.date.as_deref()?).ok()?;
+                    Some(((date - start).num_days() as u32, event))
+                })
+                .collect();
+            events.sort_by_key(|(day, event)| (*day, event.order_added_sort_index));
+
+            let template = MealPlanTemplateSnapshot {
+                id: transport::generate_id(),
+                name,
+                events,
+            };
+            let saved = MealPlanTemplate::from(&template);
+            self.meal_plan_templates.lock().unwrap().push(template);
+            Ok(saved)
+        })
+        .await
+    }
 
-        Ok(ListItem::from(&item))
+    /// Get the meal plan templates saved through this client
+    #[napi]
+    pub fn get_meal_plan_templates(&self) -> Vec<MealPlanTemplate> {
+        self.meal_plan_templates
+            .lock()
+            .unwrap()
+            .iter()
+            .map(MealPlanTemplate::from)
+            .collect()
     }
 
-    /// Delete an item from a list
+    /// Add a template's events to the week starting on a date (YYYY-MM-DD),
+    /// returning the new events
     #[napi]
-    pub async fn delete_item(&self, list_id: String, item_id: String) -> Result<()> {
-        self.inner
-            .delete_item(&list_id, &item_id)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn apply_meal_plan_template(
+        &self,
+        template_id: String,
+        week_start: MealPlanDate,
+    ) -> Result<Vec<MealPlanEvent>> {
+        self.journaled(
+            "applyMealPlanTemplate",
+            vec![template_id.clone()],
+            async move {
+                let start = self.day(&week_start)?;
+                let events: Vec<PbCalendarEvent> = {
+                    let templates = self.meal_plan_templates.lock().unwrap();
+                    let template = templates
+                        .iter()
+                        .find(|template| template.id == template_id)
+                        .ok_or_else(|| {
+                            to_napi_error(AnyListError::NotFound(format!(
+                                "Meal plan template with ID {} not found",
+                                template_id
+                            )))
+                        })?;
+                    template
+                        .events
+                        .iter()
+                        .map(|(day, event)| PbCalendarEvent {
+                            date: Some(
+                                (start + chrono::Days::new(*day as u64))
+                                    .format("%Y-%m-%d")
+                                    .to_string(),
+                            ),
+                            ..event.clone()
+                        })
+                        .collect()
+                };
+
+                let calendar = self.get_meal_plan_calendar().await?;
+                self.add_meal_plan_events(&calendar.id, events).await
+            },
+        )
+        .await
+    }
 
+    /// Forget a saved meal plan template
+    #[napi]
+    pub fn delete_meal_plan_template(&self, template_id: String) -> Result<()> {
+        let mut templates = self.meal_plan_templates.lock().unwrap();
+        let index = templates
+            .iter()
+            .position(|template| template.id == template_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Meal plan template with ID {} not found",
+                    template_id
+                )))
+            })?;
+        templates.remove(index);
         Ok(())
     }
 
-    /// Cross off (check) an item
+    /// Add the ingredients of the recipes planned between two dates (inclusive)
+    /// to a shopping list, scaled by each event's scale factor
+    /// Leftovers are skipped, since their ingredients were bought already
     #[napi]
-    pub async fn cross_off_item(&self, list_id: String, item_id: String) -> Result<()> {
-        self.inner
-            .cross_off_item(&list_id, &item_id)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn add_meal_plan_ingredients_to_list(
+        &self,
+        list_id: String,
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+        options: Option<MealPlanIngredientsOptions>,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "addMealPlanIngredientsToList",
+            Some(list_id.clone()),
+            vec![list_id.clone()],
+            async move {
+                let options = options.unwrap_or_default();
+                let exclude_recipes = options.exclude_recipe_ids.unwrap_or_default();
+                let exclude_labels = options.exclude_label_ids.unwrap_or_default();
+                let scale_factors = options.scale_factors.unwrap_or_default();
+                let today = self.today();
+
+                let (start, end) = self.date_range(&start_date, &end_date)?;
+                let (_, mut events) = self.raw_meal_plan_events(start, end).await?;
+                events.sort_by(|a, b| {
+                    (&a.date, a.order_added_sort_index).cmp(&(&b.date, b.order_added_sort_index))
+                });
+
+                let recipes: Vec<RecipeSelection> = events
+                    .into_iter()
+                    .filter(|event| {
+                        !(event
+                            .label_id
+                            .as_ref()
+                            .is_some_and(|label| exclude_labels.contains(label))
+                            || options.exclude_cooked == Some(true)
+                                && event
+                                    .date
+                                    .as_deref()
+                                    .is_some_and(|date| date < today.as_str()))
+                    })
+                    .filter_map(|event| {
+                        let event_type = MealPlanEventType::of(
+                            event.recipe_id.as_deref(),
+                            event.title.as_deref(),
+                        );
+                        if event_type == MealPlanEventType::Leftovers {
+                            return None;
+                        }
+                        let recipe_id = event.recipe_id?;
+                        if exclude_recipes.contains(&recipe_id) {
+                            return None;
+                        }
+                        let scale_factor = scale_factors
+                            .get(&event.identifier)
+                            .copied()
+                            .or(event.recipe_scale_factor)
+                            .filter(|factor| *factor != 1.0);
+                        Some(RecipeSelection {
+                            recipe_id,
+                            scale_factor,
+                        })
+                    })
+                    .collect();
+
+                self.add_recipe_ingredients(&list_id, &recipes).await
+            },
+        )
+        .await
+    }
 
-        Ok(())
+    /// Plan a recipe for each day of the week starting on a date (YYYY-MM-DD),
+    /// preferring recipes that haven't been planned for the longest
+    /// The new events are ordinary events; delete any that don't suit
+    #[napi]
+    pub async fn suggest_meal_plan(
+        &self,
+        week_start: MealPlanDate,
+        options: Option<SuggestMealPlanOptions>,
+    ) -> Result<Vec<MealPlanEvent>> {
+        self.journaled("suggestMealPlan", Vec::new(), async move {
+            let options = options.unwrap_or_default();
+            let start = self.day(&week_start)?;
+            let end = start + chrono::Days::new(6);
+            let avoid_from =
+                start - chrono::Days::new(options.avoid_recent_days.unwrap_or(14) as u64);
+
+            let mut candidates: Vec<Recipe> = match &options.collection_id {
+                Some(collection_id) => {
+                    self.get_recipes_in_collection(collection_id.clone())
+                        .await?
+                }
+                None => self.recipes().await?,
+            };
+
+            let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+            let calendar = data
+                .meal_planning_calendar_response
+                .ok_or_else(|| Error::new(Status::GenericFailure, "No meal plan calendar found"))?;
+
+            // When each recipe was last planned, and which days already have a meal
+            let mut last_planned: HashMap<&str, &str> = HashMap::new();
+            let mut taken_days: HashSet<&str> = HashSet::new();
+            for event in &calendar.events {
+                let Some(date) = event.date.as_deref() else {
+                    continue;
+                };
+                if let Some(recipe_id) = event.recipe_id.as_deref() {
+                    let latest = last_planned.entry(recipe_id).or_insert(date);
+                    if date > *latest {
+                        *latest = date;
+                    }
+                }
+                let fills_slot = match &options.label_id {
+                    Some(label_id) => event.label_id.as_ref() == Some(label_id),
+                    None => event.recipe_id.is_some(),
+                };
+                if fills_slot && event_in_range(event, start, end) {
+                    taken_days.insert(date);
+                }
+            }
+
+            candidates.retain(|recipe| {
+                last_planned
+                    .get(recipe.id.as_str())
+                    .and_then(|date| parse_date(date).ok())
+                    .is_none_or(|date| date < avoid_from || date > end)
+            });
+            candidates.sort_by(|a, b| {
+                let last = |recipe: &Recipe| last_planned.get(recipe.id.as_str()).copied();
+                last(a).cmp(&last(b)).then_with(|| a.name.cmp(&b.name))
+            });
+
+            let events: Vec<PbCalendarEvent> = start
+                .iter_days()
+                .take(7)
+                .map(|day| day.format("%Y-%m-%d").to_string())
+                .filter(|day| !taken_days.contains(day.as_str()))
+                .zip(candidates)
+                .map(|(date, recipe)| PbCalendarEvent {
+                    date: Some(date),
+                    recipe_id: Some(recipe.id),
+                    label_id: options.label_id.clone(),
+                    order_added_sort_index: Some(0),
+                    recipe_scale_factor: Some(1.0),
+                    ..Default::default()
+                })
+                .collect();
+
+            self.add_meal_plan_events(&calendar.calendar_id, events)
+                .await
+        })
+        .await
     }
 
-    /// Uncheck an item
+    /// Search meal plan event titles, recipe names, and notes for every word
+    /// of a query (case-insensitive), most recent first
     #[napi]
-    pub async fn uncheck_item(&self, list_id: String, item_id: String) -> Result<()> {
-        self.inner
-            .uncheck_item(&list_id, &item_id)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn search_meal_plan_events(
+        &self,
+        query: String,
+        options: Option<SearchMealPlanOptions>,
+    ) -> Result<Vec<MealPlanEvent>> {
+        let options = options.unwrap_or_default();
+        let start = match &options.start_date {
+            Some(date) => self.day(date)?,
+            None => chrono::NaiveDate::MIN,
+        };
+        let end = match &options.end_date {
+            Some(date) => self.day(date)?,
+            None => chrono::NaiveDate::MAX,
+        };
+        check_date_order(start, end)?;
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let recipe_names: HashMap<&str, &str> = data
+            .recipe_data_response
+            .iter()
+            .flat_map(|response| &response.recipes)
+            .filter_map(|recipe| Some((recipe.identifier.as_str(), recipe.name.as_deref()?)))
+            .collect();
 
-        Ok(())
+        let mut events: Vec<&PbCalendarEvent> = data
+            .meal_planning_calendar_response
+            .iter()
+            .flat_map(|calendar| &calendar.events)
+            .filter(|event| event_in_range(event, start, end))
+            .filter(|event| {
+                let recipe_name = event
+                    .recipe_id
+                    .as_deref()
+                    .and_then(|id| recipe_names.get(id).copied());
+                let haystack = [
+                    event.title.as_deref(),
+                    recipe_name,
+                    event.details.as_deref(),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join("\n")
+                .to_lowercase();
+                words.iter().all(|word| haystack.contains(word.as_str()))
+            })
+            .collect();
+        events.sort_by(|a, b| {
+            (&b.date, b.order_added_sort_index).cmp(&(&a.date, a.order_added_sort_index))
+        });
+
+        Ok(events.into_iter().map(MealPlanEvent::from).collect())
     }
 
-    /// Update an existing item
+    /// Summarize the meal plan between two dates (YYYY-MM-DD, inclusive): how
+    /// often each recipe and label was planned, and which days have no dinner
     #[napi]
-    pub async fn update_item(
+    pub async fn get_meal_plan_stats(
         &self,
-        list_id: String,
-        item_id: String,
-        name: String,
-        quantity: Option<String>,
-        note: Option<String>,
-        category: Option<String>,
-    ) -> Result<()> {
-        self.inner
-            .update_item(
-                &list_id,
-                &item_id,
-                &name,
-                quantity.as_deref(),
-                note.as_deref(),
-                category.as_deref(),
-            )
-            .await
-            .map_err(to_napi_error)?;
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+    ) -> Result<MealPlanStats> {
+        let (start, end) = self.date_range(&start_date, &end_date)?;
+
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let recipe_names: HashMap<&str, &str> = data
+            .recipe_data_response
+            .iter()
+            .flat_map(|response| &response.recipes)
+            .filter_map(|recipe| Some((recipe.identifier.as_str(), recipe.name.as_deref()?)))
+            .collect();
+        let labels = data
+            .meal_planning_calendar_response
+            .as_ref()
+            .map(|calendar| calendar.labels.as_slice())
+            .unwrap_or_default();
+        let dinner_label = labels
+            .iter()
+            .find(|label| {
+                label
+                    .name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case("dinner"))
+            })
+            .map(|label| label.identifier.as_str());
+        let events: Vec<&PbCalendarEvent> = data
+            .meal_planning_calendar_response
+            .iter()
+            .flat_map(|calendar| &calendar.events)
+            .filter(|event| event_in_range(event, start, end))
+            .collect();
+
+        let mut recipe_counts: HashMap<&str, u32> = HashMap::new();
+        let mut label_counts: HashMap<Option<&str>, u32> = HashMap::new();
+        let mut dinner_days: HashSet<&str> = HashSet::new();
+        for event in &events {
+            if let Some(recipe_id) = event.recipe_id.as_deref() {
+                *recipe_counts.entry(recipe_id).or_default() += 1;
+            }
+            *label_counts.entry(event.label_id.as_deref()).or_default() += 1;
+            if dinner_label.is_none() || event.label_id.as_deref() == dinner_label {
+                dinner_days.extend(event.date.as_deref());
+            }
+        }
 
-        Ok(())
+        let mut recipes: Vec<RecipeFrequency> = recipe_counts
+            .into_iter()
+            .map(|(recipe_id, count)| RecipeFrequency {
+                recipe_id: recipe_id.to_string(),
+                name: recipe_names.get(recipe_id).map(|name| name.to_string()),
+                count,
+            })
+            .collect();
+        recipes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        let mut label_frequencies: Vec<LabelFrequency> = label_counts
+            .into_iter()
+            .map(|(label_id, count)| LabelFrequency {
+                label_id: label_id.map(str::to_string),
+                name: label_id.and_then(|id| {
+                    labels
+                        .iter()
+                        .find(|label| label.identifier == id)
+                        .and_then(|label| label.name.clone())
+                }),
+                count,
+            })
+            .collect();
+        label_frequencies.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        let days_without_dinner = start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .map(|day| day.format("%Y-%m-%d").to_string())
+            .filter(|day| !dinner_days.contains(day.as_str()))
+            .collect();
+
+        Ok(MealPlanStats {
+            event_count: events.len() as u32,
+            recipes,
+            labels: label_frequencies,
+            days_without_dinner,
+        })
     }
 
-    /// Delete multiple items at once
+    /// Add up the nutrition of the recipes planned between two dates
+    /// (YYYY-MM-DD, inclusive), per day and in total
+    /// Each planned recipe or leftovers counts as one serving, read from the
+    /// recipe's nutrition text (e.g. "Calories: 320 kcal", "Protein: 12 g")
     #[napi]
-    pub async fn bulk_delete_items(&self, list_id: String, item_ids: Vec<String>) -> Result<()> {
-        let item_id_refs: Vec<&str> = item_ids.iter().map(|s| s.as_str()).collect();
-        self.inner
-            .bulk_delete_items(&list_id, &item_id_refs)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn get_meal_plan_nutrition(
+        &self,
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+    ) -> Result<MealPlanNutrition> {
+        let (start, end) = self.date_range(&start_date, &end_date)?;
+
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let nutrition: HashMap<&str, Option<NutritionTotals>> = data
+            .recipe_data_response
+            .iter()
+            .flat_map(|response| &response.recipes)
+            .map(|recipe| {
+                (
+                    recipe.identifier.as_str(),
+                    recipe
+                        .nutritional_info
+                        .as_deref()
+                        .and_then(nutrition::parse_nutrition),
+                )
+            })
+            .collect();
+        let events: Vec<&PbCalendarEvent> = data
+            .meal_planning_calendar_response
+            .iter()
+            .flat_map(|calendar| &calendar.events)
+            .filter(|event| event_in_range(event, start, end))
+            .collect();
+
+        let mut total = NutritionTotals::default();
+        let mut recipes_without_nutrition: Vec<String> = Vec::new();
+        let days: Vec<DayNutrition> = start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .map(|day| {
+                let date = day.format("%Y-%m-%d").to_string();
+                let mut totals = NutritionTotals::default();
+                let mut recipe_count = 0;
+                for event in &events {
+                    let Some(recipe_id) = event.recipe_id.as_deref() else {
+                        continue;
+                    };
+                    if event.date.as_deref() != Some(date.as_str()) {
+                        continue;
+                    }
+                    match nutrition.get(recipe_id) {
+                        Some(Some(recipe)) => {
+                            totals.add(recipe);
+                            recipe_count += 1;
+                        }
+                        _ if !recipes_without_nutrition.iter().any(|id| id == recipe_id) => {
+                            recipes_without_nutrition.push(recipe_id.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                total.add(&totals);
+                DayNutrition {
+                    date,
+                    totals: totals.divided(1.0),
+                    recipe_count,
+                }
+            })
+            .collect();
+
+        Ok(MealPlanNutrition {
+            daily_average: total.divided(days.len() as f64),
+            total: total.divided(1.0),
+            days,
+            recipes_without_nutrition,
+        })
+    }
 
-        Ok(())
+    /// Render a readable summary of the meal plan between two dates
+    /// (YYYY-MM-DD, inclusive), for posting to a chat channel
+    /// Each day lists its meals in order with their label, a link to the
+    /// recipe's source, and its prep time; days with no meals say so
+    #[napi]
+    pub async fn render_meal_plan_digest(
+        &self,
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+        format: DigestFormat,
+    ) -> Result<String> {
+        let (start, end) = self.date_range(&start_date, &end_date)?;
+
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let recipes: HashMap<&str, &PbRecipe> = data
+            .recipe_data_response
+            .iter()
+            .flat_map(|response| &response.recipes)
+            .map(|recipe| (recipe.identifier.as_str(), recipe))
+            .collect();
+        let label_names: HashMap<&str, &str> = data
+            .meal_planning_calendar_response
+            .iter()
+            .flat_map(|calendar| &calendar.labels)
+            .filter_map(|label| Some((label.identifier.as_str(), label.name.as_deref()?)))
+            .collect();
+        let mut events: Vec<&PbCalendarEvent> = data
+            .meal_planning_calendar_response
+            .iter()
+            .flat_map(|calendar| &calendar.events)
+            .filter(|event| event_in_range(event, start, end))
+            .collect();
+        events.sort_by(|a, b| {
+            (&a.date, a.order_added_sort_index).cmp(&(&b.date, b.order_added_sort_index))
+        });
+
+        let days: Vec<export::DigestDay> = start
+            .iter_days()
+            .take_while(|day| *day <= end)
+            .map(|date| {
+                let day = date.format("%Y-%m-%d").to_string();
+                let meals = events
+                    .iter()
+                    .filter(|event| event.date.as_deref() == Some(day.as_str()))
+                    .map(|event| {
+                        let recipe = event
+                            .recipe_id
+                            .as_deref()
+                            .and_then(|id| recipes.get(id).copied());
+                        let event_type = MealPlanEventType::of(
+                            event.recipe_id.as_deref(),
+                            event.title.as_deref(),
+                        );
+                        let recipe_name = recipe.and_then(|recipe| recipe.name.as_deref());
+                        export::DigestMeal {
+                            label: event
+                                .label_id
+                                .as_deref()
+                                .and_then(|id| label_names.get(id))
+                                .map(|name| name.to_string()),
+                            title: match (&event_type, recipe_name) {
+                                (MealPlanEventType::Leftovers, Some(name)) => {
+                                    format!("Leftovers: {}", name)
+                                }
+                                (_, Some(name)) => name.to_string(),
+                                (_, None) => event
+                                    .title
+                                    .clone()
+                                    .filter(|title| !title.is_empty())
+                                    .unwrap_or_else(|| "Meal".to_string()),
+                            },
+                            url: recipe
+                                .and_then(|recipe| recipe.source_url.clone())
+                                .filter(|url| !url.is_empty()),
+                            prep_time: recipe
+                                .filter(|_| event_type == MealPlanEventType::Recipe)
+                                .and_then(|recipe| recipe.prep_time)
+                                .filter(|minutes| *minutes > 0),
+                        }
+                    })
+                    .collect();
+                export::DigestDay { date, meals }
+            })
+            .collect();
+
+        Ok(match format {
+            DigestFormat::Markdown => export::digest_to_markdown(&days),
+            DigestFormat::Html => export::digest_to_html(&days),
+        })
     }
 
-    /// Delete all crossed off (checked) items from a list
+    /// Build an iCalendar (.ics) document of the meal plan between two dates
+    /// (YYYY-MM-DD, inclusive), with recipe names and event notes
+    /// Unlike `enableIcalendar`, nothing is shared; the document is built locally
     #[napi]
-    pub async fn delete_all_crossed_off_items(&self, list_id: String) -> Result<()> {
-        self.inner
-            .delete_all_crossed_off_items(&list_id)
-            .await
-            .map_err(to_napi_error)?;
+    pub async fn export_meal_plan_to_ics(
+        &self,
+        start_date: MealPlanDate,
+        end_date: MealPlanDate,
+    ) -> Result<String> {
+        let (start, end) = self.date_range(&start_date, &end_date)?;
+
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let recipe_names: HashMap<&str, &str> = data
+            .recipe_data_response
+            .iter()
+            .flat_map(|response| &response.recipes)
+            .filter_map(|recipe| Some((recipe.identifier.as_str(), recipe.name.as_deref()?)))
+            .collect();
+        let Some(calendar) = &data.meal_planning_calendar_response else {
+            return Ok(ics::to_ics(&[], chrono::Utc::now()));
+        };
+        let label_names: HashMap<&str, &str> = calendar
+            .labels
+            .iter()
+            .filter_map(|label| Some((label.identifier.as_str(), label.name.as_deref()?)))
+            .collect();
 
-        Ok(())
+        let mut events: Vec<ics::IcsEvent> = calendar
+            .events
+            .iter()
+            .filter(|event| event_in_range(event, start, end))
+            .filter_map(|event| {
+                let recipe_name = event
+                    .recipe_id
+                    .as_deref()
+                    .and_then(|id| recipe_names.get(id).copied());
+                Some(ics::IcsEvent {
+                    uid: format!("{}@anylist.com", event.identifier),
+                    date: parse_date(event.date.as_deref()?).ok()?,
+                    summary: recipe_name
+                        .or(event.title.as_deref())
+                        .unwrap_or("Meal")
+                        .to_string(),
+                    description: event.details.clone().filter(|d| !d.is_empty()),
+                    category: event
+                        .label_id
+                        .as_deref()
+                        .and_then(|id| label_names.get(id))
+                        .map(|name| name.to_string()),
+                })
+            })
+            .collect();
+        events.sort_by_key(|event| event.date);
+
+        Ok(ics::to_ics(&events, chrono::Utc::now()))
     }
 
-    /// Get all recipes
+    // ==================== iCalendar Methods ====================
+
+    /// Enable iCalendar sync and get the URL
     #[napi]
-    pub async fn get_recipes(&self) -> Result<Vec<Recipe>> {
-        let recipes = self.inner.get_recipes().await.map_err(to_napi_error)?;
+    pub async fn enable_icalendar(&self) -> Result<ICalendarInfo> {
+        self.journaled("enableIcalendar", Vec::new(), async move {
+            let info = self.inner.enable_icalendar().await.map_err(to_napi_error)?;
 
-        Ok(recipes.iter().map(Recipe::from).collect())
+            Ok(ICalendarInfo::from(&info))
+        })
+        .await
     }
 
-    /// Get a specific recipe by ID
+    /// Disable iCalendar sync
     #[napi]
-    pub async fn get_recipe_by_id(&self, recipe_id: String) -> Result<Recipe> {
-        let recipe = self
+    pub async fn disable_icalendar(&self) -> Result<OperationReceipt> {
+        self.receipted("disableIcalendar", None, Vec::new(), async move {
+            self.inner
+                .disable_icalendar()
+                .await
+                .map_err(to_napi_error)?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Get the iCalendar URL if enabled
+    #[napi]
+    pub async fn get_icalendar_url(&self) -> Result<Option<String>> {
+        let url = self
             .inner
-            .get_recipe_by_id(&recipe_id)
+            .get_icalendar_url()
             .await
             .map_err(to_napi_error)?;
 
-        Ok(Recipe::from(&recipe))
+        Ok(url)
     }
 
-    /// Get a recipe by name
+    /// Download the hosted iCalendar feed and read its events
+    /// Fails if iCalendar sync isn't enabled
     #[napi]
-    pub async fn get_recipe_by_name(&self, name: String) -> Result<Recipe> {
-        let recipe = self
+    pub async fn fetch_icalendar_events(&self) -> Result<Vec<ICalendarEvent>> {
+        let url = self.get_icalendar_url().await?.ok_or_else(|| {
+            Error::new(
+                Status::GenericFailure,
+                "iCalendar sync is not enabled (see enableIcalendar)",
+            )
+        })?;
+        let text = transport::fetch(&url)
+            .await
+            .map_err(to_napi_error)?
+            .text()
+            .await
+            .map_err(|e| to_napi_error(e.into()))?;
+
+        Ok(ics::parse_events(&text))
+    }
+
+    // ==================== Recipe Collection Methods ====================
+
+    /// Get all recipe collections
+    #[napi]
+    pub async fn get_recipe_collections(&self) -> Result<Vec<RecipeCollection>> {
+        let collections = self
             .inner
-            .get_recipe_by_name(&name)
+            .get_recipe_collections()
             .await
             .map_err(to_napi_error)?;
 
-        Ok(Recipe::from(&recipe))
+        Ok(collections.iter().map(RecipeCollection::from).collect())
     }
 
-    /// Create a new recipe with full metadata support
+    /// Get a recipe collection by ID
     #[napi]
-    pub async fn create_recipe(&self, options: CreateRecipeOptions) -> Result<Recipe> {
-        let rs_ingredients: Vec<RsIngredient> =
-            options.ingredients.iter().map(RsIngredient::from).collect();
+    pub async fn get_recipe_collection_by_id(
+        &self,
+        collection_id: String,
+    ) -> Result<RecipeCollection> {
+        let collection = self.recipe_collection(&collection_id).await?;
+        Ok(RecipeCollection::from(&collection))
+    }
 
-        let mut builder = RecipeBuilder::new(&options.name)
-            .ingredients(rs_ingredients)
-            .preparation_steps(options.preparation_steps);
+    /// Get a recipe collection by name
+    #[napi]
+    pub async fn get_recipe_collection_by_name(&self, name: String) -> Result<RecipeCollection> {
+        self.inner
+            .get_recipe_collections()
+            .await
+            .map_err(to_napi_error)?
+            .iter()
+            .find(|collection| collection.name() == name)
+            .map(RecipeCollection::from)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Collection with name '{}' not found",
+                    name
+                )))
+            })
+    }
 
-        if let Some(note) = options.note {
-            builder = builder.note(note);
-        }
-        if let Some(source_name) = options.source_name {
-            builder = builder.source_name(source_name);
-        }
-        if let Some(source_url) = options.source_url {
-            builder = builder.source_url(source_url);
-        }
-        if let Some(servings) = options.servings {
-            builder = builder.servings(servings);
-        }
-        if let Some(prep_time) = options.prep_time {
-            builder = builder.prep_time(prep_time);
-        }
-        if let Some(cook_time) = options.cook_time {
-            builder = builder.cook_time(cook_time);
-        }
-        if let Some(rating) = options.rating {
-            builder = builder.rating(rating);
-        }
-        if let Some(nutritional_info) = options.nutritional_info {
-            builder = builder.nutritional_info(nutritional_info);
-        }
-        if let Some(photo_id) = options.photo_id {
-            builder = builder.photo_id(photo_id);
-        }
+    /// Get the recipes in a collection, in the collection's order
+    #[napi]
+    pub async fn get_recipes_in_collection(&self, collection_id: String) -> Result<Vec<Recipe>> {
+        let collection = self.recipe_collection(&collection_id).await?;
+
+        let mut recipes: HashMap<String, Recipe> = self
+            .recipes()
+            .await?
+            .into_iter()
+            .map(|recipe| (recipe.id.clone(), recipe))
+            .collect();
+
+        // Collections can still list recipes that have since been deleted
+        Ok(collection
+            .recipe_ids()
+            .iter()
+            .filter_map(|id| recipes.remove(id.as_str()))
+            .collect())
+    }
 
-        let recipe = builder.save(&self.inner).await.map_err(to_napi_error)?;
+    /// Create a new recipe collection
+    #[napi]
+    pub async fn create_recipe_collection(&self, name: String) -> Result<RecipeCollection> {
+        self.journaled("createRecipeCollection", Vec::new(), async move {
+            let collection = self
+                .inner
+                .create_recipe_collection(&name)
+                .await
+                .map_err(to_napi_error)?;
+
+            Ok(RecipeCollection::from(&collection))
+        })
+        .await
+    }
 
-        Ok(Recipe::from(&recipe))
+    /// Delete a recipe collection
+    #[napi]
+    pub async fn delete_recipe_collection(
+        &self,
+        collection_id: String,
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "deleteRecipeCollection",
+            None,
+            vec![collection_id.clone()],
+            async move {
+                self.inner
+                    .delete_recipe_collection(&collection_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
     }
 
-    /// Add recipe ingredients to a list with optional scale factor
+    /// Add a recipe to a collection
     #[napi]
-    pub async fn add_recipe_to_list(
+    pub async fn add_recipe_to_collection(
         &self,
+        collection_id: String,
         recipe_id: String,
-        list_id: String,
-        scale_factor: Option<f64>,
-    ) -> Result<()> {
-        self.inner
-            .add_recipe_to_list(&recipe_id, &list_id, scale_factor)
-            .await
-            .map_err(to_napi_error)?;
-
-        Ok(())
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "addRecipeToCollection",
+            None,
+            vec![collection_id.clone(), recipe_id.clone()],
+            async move {
+                self.inner
+                    .add_recipe_to_collection(&collection_id, &recipe_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
     }
 
-    /// Update an existing recipe
-    /// Note: The recipe name cannot be changed (use the existing name in options)
+    /// Remove a recipe from a collection
     #[napi]
-    pub async fn update_recipe(
+    pub async fn remove_recipe_from_collection(
         &self,
+        collection_id: String,
         recipe_id: String,
-        options: CreateRecipeOptions,
-    ) -> Result<Recipe> {
-        // Fetch the existing recipe to use as base for the builder
-        let existing = self
-            .inner
-            .get_recipe_by_id(&recipe_id)
-            .await
-            .map_err(to_napi_error)?;
+    ) -> Result<OperationReceipt> {
+        self.receipted(
+            "removeRecipeFromCollection",
+            None,
+            vec![collection_id.clone(), recipe_id.clone()],
+            async move {
+                self.inner
+                    .remove_recipe_from_collection(&collection_id, &recipe_id)
+                    .await
+                    .map_err(to_napi_error)?;
+
+                Ok(())
+            },
+        )
+        .await
+    }
 
-        let rs_ingredients: Vec<RsIngredient> =
-            options.ingredients.iter().map(RsIngredient::from).collect();
+    // ==================== Raw Operation Methods ====================
 
-        // Start from existing recipe (preserves the ID for update)
-        let mut builder = RecipeBuilder::from(&existing)
-            .ingredients(rs_ingredients)
-            .preparation_steps(options.preparation_steps);
+    /// Encode a list operation as a `PBListOperationList`, for
+    /// `submitRawOperation()`
+    /// The operation gets a new operation ID. Encoded lists can be joined
+    /// with `Buffer.concat()` to submit several operations at once.
+    #[napi]
+    pub fn encode_operation(&self, operation: RawListOperation) -> Buffer {
+        let operations = PbListOperationList {
+            operations: vec![operations::raw(&self.inner, operation)],
+        };
+        operations.encode_to_vec().into()
+    }
 
-        if let Some(note) = options.note {
-            builder = builder.note(note);
-        }
-        if let Some(source_name) = options.source_name {
-            builder = builder.source_name(source_name);
-        }
-        if let Some(source_url) = options.source_url {
-            builder = builder.source_url(source_url);
-        }
-        if let Some(servings) = options.servings {
-            builder = builder.servings(servings);
-        }
-        if let Some(prep_time) = options.prep_time {
-            builder = builder.prep_time(prep_time);
-        }
-        if let Some(cook_time) = options.cook_time {
-            builder = builder.cook_time(cook_time);
-        }
-        if let Some(rating) = options.rating {
-            builder = builder.rating(rating);
-        }
-        if let Some(nutritional_info) = options.nutritional_info {
-            builder = builder.nutritional_info(nutritional_info);
+    /// Send an encoded `PBListOperationList` of changes to a list, for
+    /// operations this library doesn't wrap yet
+    /// Every operation in it must be for the list. AnyList's response isn't
+    /// checked beyond its status, so check the list afterwards.
+    #[napi]
+    pub async fn submit_raw_operation(
+        &self,
+        list_id: String,
+        operations: Buffer,
+    ) -> Result<OperationReceipt> {
+        let operations = PbListOperationList::decode(operations.as_ref()).map_err(|e| {
+            Error::new(Status::InvalidArg, format!("Invalid operation list: {}", e))
+        })?;
+        if operations.operations.is_empty() {
+            return Err(Error::new(
+                Status::InvalidArg,
+                "Invalid operation list: it has no operations",
+            ));
         }
-        if let Some(photo_id) = options.photo_id {
-            builder = builder.photo_id(photo_id);
+        if let Some(other) = operations
+            .operations
+            .iter()
+            .find(|o| o.list_id.as_deref() != Some(list_id.as_str()))
+        {
+            return Err(Error::new(
+                Status::InvalidArg,
+                format!(
+                    "Invalid operation list: an operation is for list {}, not {}",
+                    other.list_id.as_deref().unwrap_or_default(),
+                    list_id
+                ),
+            ));
         }
 
-        let recipe = builder.save(&self.inner).await.map_err(to_napi_error)?;
-
-        Ok(Recipe::from(&recipe))
+        let entity_ids = std::iter::once(list_id.clone())
+            .chain(
+                operations
+                    .operations
+                    .iter()
+                    .filter_map(|o| o.list_item_id.clone()),
+            )
+            .collect();
+        self.receipted(
+            "submitRawOperation",
+            Some(list_id),
+            entity_ids,
+            async move {
+                transport::post_message(&self.inner, "data/shopping-lists/update", &operations)
+                    .await
+                    .map_err(to_napi_error)?;
+                Ok(())
+            },
+        )
+        .await
     }
 
-    /// Delete a recipe
+    /// Send a request to an AnyList endpoint with the client's credentials,
+    /// for endpoints this library doesn't wrap yet
+    /// `path` is relative to https://www.anylist.com (e.g. "data/user-data/get").
+    /// `body`, usually an encoded protobuf message, is sent as the `operations`
+    /// form field, the way AnyList expects. Returns the raw response body.
+    /// Requests aren't journaled or ordered with other changes.
     #[napi]
-    pub async fn delete_recipe(&self, recipe_id: String) -> Result<()> {
-        self.inner
-            .delete_recipe(&recipe_id)
+    pub async fn request(
+        &self,
+        method: String,
+        path: String,
+        body: Option<Buffer>,
+    ) -> Result<Buffer> {
+        let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+            .map_err(|_| Error::new(Status::InvalidArg, format!("Invalid method: {}", method)))?;
+        // The credentials only ever go to AnyList
+        if path.contains("://") || path.starts_with("//") {
+            return Err(Error::new(
+                Status::InvalidArg,
+                format!("Invalid path: {} isn't relative to AnyList", path),
+            ));
+        }
+
+        let response = transport::request(&self.inner, method, &path, body.map(|b| b.to_vec()))
             .await
             .map_err(to_napi_error)?;
-
-        Ok(())
+        Ok(response.into())
     }
+}
 
-    /// Delete a list
-    #[napi]
-    pub async fn delete_list(&self, list_id: String) -> Result<()> {
-        self.inner
-            .delete_list(&list_id)
-            .await
-            .map_err(to_napi_error)?;
+/// Find a shopping list in user data
+fn find_shopping_list<'a>(
+    data: &'a PbUserDataResponse,
+    list_id: &str,
+) -> Result<&'a PbShoppingList> {
+    data.shopping_lists_response
+        .as_ref()
+        .and_then(|r| r.new_lists.iter().find(|l| l.identifier == list_id))
+        .ok_or_else(|| {
+            to_napi_error(AnyListError::NotFound(format!(
+                "List with ID {} not found",
+                list_id
+            )))
+        })
+}
 
-        Ok(())
+/// Where a recipe's photo can be downloaded from, if it has one
+fn recipe_photo_url(recipe: &PbRecipe) -> Option<String> {
+    recipe
+        .photo_urls
+        .first()
+        .cloned()
+        .or_else(|| recipe.photo_ids.first().map(|id| transport::photo_url(id)))
+}
+
+/// A recipe's ingredients for a shopping list, with each linked recipe replaced
+/// by its own ingredients, scaled by the number of batches used (e.g. "2
+/// batches")
+/// A linked ingredient measured some other way (e.g. "2 cups") is added as it
+/// is, since there's no telling how much of the recipe that is
+fn shopping_ingredients<'a>(
+    recipe: &'a Recipe,
+    factor: Option<f64>,
+    recipes: &HashMap<&str, &'a Recipe>,
+    path: &mut Vec<&'a str>,
+    out: &mut Vec<(&'a str, Ingredient)>,
+) {
+    path.push(&recipe.id);
+
+    for ingredient in &recipe.ingredients {
+        if ingredient.is_header == Some(true) {
+            continue;
+        }
+
+        // A recipe that links back to one being expanded is added as-is
+        let linked = ingredient
+            .recipe_id
+            .as_deref()
+            .filter(|id| !path.contains(id))
+            .and_then(|id| recipes.get(id))
+            .zip(batches(ingredient.quantity.as_deref()));
+        if let Some((linked, batches)) = linked {
+            let linked_factor = batches * factor.unwrap_or(1.0);
+            shopping_ingredients(
+                linked,
+                Some(linked_factor).filter(|f| (f - 1.0).abs() > f64::EPSILON),
+                recipes,
+                path,
+                out,
+            );
+            continue;
+        }
+
+        out.push((
+            &recipe.id,
+            Ingredient {
+                name: ingredient.name.clone(),
+                quantity: match (&ingredient.quantity, factor) {
+                    (Some(quantity), Some(factor)) => {
+                        Some(quantity::scale_quantity(quantity, factor))
+                    }
+                    (quantity, _) => quantity.clone(),
+                },
+                note: ingredient.note.clone(),
+                is_header: None,
+                recipe_id: None,
+            },
+        ));
     }
 
-    /// Upload a photo for use with recipes
-    /// Returns the photo ID which can be used with createRecipe
-    #[napi]
-    pub async fn upload_photo(&self, data: Buffer, filename: String) -> Result<String> {
-        let photo_id = self
-            .inner
-            .upload_photo(data.to_vec(), &filename)
-            .await
-            .map_err(to_napi_error)?;
+    path.pop();
+}
+
+/// A list's categories, stores, and rules in user data
+fn list_response<'a>(data: &'a PbUserDataResponse, list_id: &str) -> Option<&'a PbListResponse> {
+    data.shopping_lists_response
+        .as_ref()?
+        .list_responses
+        .iter()
+        .find(|l| l.list_id.as_deref() == Some(list_id))
+}
+
+/// A list's category groups in user data
+fn category_groups<'a>(
+    data: &'a PbUserDataResponse,
+    list_id: &str,
+) -> impl Iterator<Item = &'a PbListCategoryGroup> {
+    list_response(data, list_id)
+        .into_iter()
+        .flat_map(|r| &r.category_group_responses)
+        .filter_map(|r| r.category_group.as_ref())
+}
+
+/// The favourites lists in user data
+fn starter_lists(data: &PbUserDataResponse) -> impl Iterator<Item = &PbStarterList> {
+    data.starter_lists_response
+        .as_ref()
+        .and_then(|r| r.favorite_item_lists_response.as_ref())
+        .into_iter()
+        .flat_map(|r| &r.list_responses)
+        .filter_map(|r| r.starter_list.as_ref())
+}
+
+/// A favourites list's named items, arranged ones first in their order
+fn sorted_favourites(list: &PbStarterList) -> Vec<&PbListItem> {
+    let mut items: Vec<&PbListItem> = list.items.iter().filter(|i| i.name.is_some()).collect();
+    items.sort_by_key(|item| item.manual_sort_index.unwrap_or(i32::MAX));
+    items
+}
+
+/// Find the favourites list linked to a shopping list in user data
+fn linked_favourites_list<'a>(
+    data: &'a PbUserDataResponse,
+    list_id: &str,
+) -> Option<&'a PbStarterList> {
+    starter_lists(data).find(|l| l.list_id.as_deref() == Some(list_id))
+}
+
+/// Find the favourites list linked to a shopping list
+fn linked_favourites_list_id(data: &PbUserDataResponse, list_id: &str) -> Option<String> {
+    linked_favourites_list(data, list_id).map(|l| l.identifier.clone())
+}
 
-        Ok(photo_id)
+/// The cached type a change leaves out of date, or `None` for both
+/// Recipes include their last cooked dates, so meal plan changes count as
+/// recipe changes
+fn changed_entity(operation: &str, on_list: bool) -> Option<CacheEntity> {
+    if on_list {
+        Some(CacheEntity::Lists)
+    } else if ["Recipe", "MealPlan", "Photo"]
+        .iter()
+        .any(|name| operation.contains(name))
+    {
+        Some(CacheEntity::Recipes)
+    } else {
+        None
     }
+}
 
-    // ==================== Category Methods ====================
+impl AnyListClient {
+    fn new(inner: RsClient) -> Self {
+        AnyListClient {
+            inner: Arc::new(inner),
+            trash: Mutex::new(HashMap::new()),
+            recipe_history: Mutex::new(HashMap::new()),
+            meal_plan_templates: Mutex::new(Vec::new()),
+            ensure_lock: tokio::sync::Mutex::new(()),
+            time_zone: Arc::new(Mutex::new(None)),
+            category_rules: Mutex::new(rules::RuleSet::default()),
+            favourite_usage: Mutex::new(HashMap::new()),
+            cache: Arc::new(Mutex::new(cache::Cache::default())),
+            auto_refresh: Mutex::new(Vec::new()),
+            offline_queue: Mutex::new(queue::OperationQueue::default()),
+            queue_events: Mutex::new(None),
+            conflict_resolver: Arc::new(Mutex::new(None)),
+            flush_lock: tokio::sync::Mutex::new(()),
+            optimistic: Mutex::new(None),
+            journal: Arc::new(Mutex::new(journal::Journal::default())),
+            sync_state: Arc::new(Mutex::new(status::SyncState::default())),
+            drift_events: Arc::new(Mutex::new(None)),
+            lists_fetch: coalesce::Coalesced::default(),
+            list_fetch: coalesce::Coalesced::default(),
+            recipes_fetch: coalesce::Coalesced::default(),
+            list_locks: Arc::new(list_locks::ListLocks::default()),
+        }
+    }
 
-    /// Create a new category in a list
-    #[napi]
-    pub async fn create_category(
+    /// Make a change, recording it in the journal once it finishes
+    async fn journaled<T: Serialize>(
+        &self,
+        operation: &str,
+        entity_ids: Vec<String>,
+        change: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let id = transport::generate_id();
+        self.record(id, operation, None, entity_ids, change).await
+    }
+
+    /// Make a change to a list once other changes to it have finished,
+    /// recording it in the journal
+    async fn list_change<T: Serialize>(
         &self,
+        operation: &str,
         list_id: String,
-        category_group_id: String,
-        name: String,
-    ) -> Result<Category> {
-        let category = self
-            .inner
-            .create_category(&list_id, &category_group_id, &name)
+        entity_ids: Vec<String>,
+        change: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let id = transport::generate_id();
+        self.record(id, operation, Some(list_id), entity_ids, change)
             .await
-            .map_err(to_napi_error)?;
+    }
 
-        Ok(Category::from(&category))
+    /// Make a change that has nothing else to return, and return a receipt
+    /// for it, with the same ID as its journal entry
+    async fn receipted(
+        &self,
+        operation: &str,
+        list_id: Option<String>,
+        entity_ids: Vec<String>,
+        change: impl std::future::Future<Output = Result<()>>,
+    ) -> Result<OperationReceipt> {
+        let id = transport::generate_id();
+        self.record(id.clone(), operation, list_id, entity_ids, change)
+            .await?;
+        Ok(OperationReceipt {
+            id,
+            operation: operation.to_string(),
+            completed_at: transport::current_timestamp() * 1000.0,
+        })
     }
 
-    /// Delete a category from a list
-    #[napi]
-    pub async fn delete_category(&self, list_id: String, category_id: String) -> Result<()> {
-        self.inner
-            .delete_category(&list_id, &category_id)
-            .await
-            .map_err(to_napi_error)?;
+    /// Make a change, after other changes to its list if it has one, and
+    /// record it in the journal as entry `id`
+    /// Once the change is made, cached results it affects are dropped
+    async fn record<T: Serialize>(
+        &self,
+        id: String,
+        operation: &str,
+        list_id: Option<String>,
+        entity_ids: Vec<String>,
+        change: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let entity = changed_entity(operation, list_id.is_some());
+        let change = async {
+            match list_id {
+                Some(list_id) => self.list_locks.run(list_id, change).await,
+                None => change.await,
+            }
+        };
+        let result = journal::journaled(
+            &self.journal,
+            &self.sync_state,
+            id,
+            operation,
+            entity_ids,
+            change,
+        )
+        .await;
+        if result.is_ok() {
+            self.cache.lock().unwrap().invalidate(entity);
+        }
+        result
+    }
 
-        Ok(())
+    /// Record how fetching an entity type went, for `getSyncStatus()`
+    fn note_fetch<T>(&self, operation: &str, entity: CacheEntity, result: Result<T>) -> Result<T> {
+        let mut sync_state = self.sync_state.lock().unwrap();
+        match &result {
+            Ok(_) => sync_state.fetched(entity),
+            Err(e) => sync_state.failed(operation, e),
+        }
+        result
     }
 
-    /// Rename a category
-    #[napi]
-    pub async fn rename_category(
+    /// Start a `ListWatcher` reporting to `sink`
+    async fn watch_lists(
         &self,
-        list_id: String,
-        category_group_id: String,
-        category_id: String,
-        new_name: String,
-    ) -> Result<()> {
-        self.inner
-            .rename_category(&list_id, &category_group_id, &category_id, &new_name)
-            .await
-            .map_err(to_napi_error)?;
+        sink: ChangeSink,
+        realtime: Option<bool>,
+        poll_interval_ms: Option<u32>,
+    ) -> Result<Subscription> {
+        let interval = poll_interval(poll_interval_ms.unwrap_or(DEFAULT_POLL_INTERVAL_MS))?;
 
-        Ok(())
+        let lists = self.inner.get_lists().await.map_err(to_napi_error)?;
+        let lists: Vec<List> = lists.iter().map(List::from).collect();
+
+        let (notify, changed) = tokio::sync::mpsc::unbounded_channel();
+        let mut sync = None;
+        if realtime.unwrap_or(true) {
+            let mut realtime = RealtimeSync::new(Arc::clone(&self.inner), move |event| {
+                if event == SyncEvent::ShoppingListsChanged {
+                    let _ = notify.send(());
+                }
+            });
+            if realtime.connect().await.is_ok() {
+                sync = Some(realtime);
+            }
+        }
+
+        let stop = Arc::new(tokio::sync::Notify::new());
+        let realtime = Arc::new(AtomicBool::new(sync.is_some()));
+        let watcher = ListWatcher {
+            client: Arc::clone(&self.inner),
+            lists,
+            sync,
+            changed,
+            poll_interval: interval,
+            stop: Arc::clone(&stop),
+            realtime: Arc::clone(&realtime),
+            sink,
+            sync_state: Arc::clone(&self.sync_state),
+        };
+        self.sync_state.lock().unwrap().watch(&realtime);
+        tokio::spawn(watcher.run());
+
+        Ok(Subscription { stop, realtime })
     }
 
-    // ==================== Store Methods ====================
+    fn optimistic(&self) -> Option<optimistic::Optimistic> {
+        self.optimistic.lock().unwrap().clone()
+    }
 
-    /// Get all stores for a list
-    #[napi]
-    pub async fn get_stores_for_list(&self, list_id: String) -> Result<Vec<Store>> {
-        let stores = self
-            .inner
-            .get_stores_for_list(&list_id)
-            .await
-            .map_err(to_napi_error)?;
+    fn emit_queue_event(
+        &self,
+        kind: QueueEventType,
+        operation: QueuedOperation,
+        error: Option<String>,
+    ) {
+        if let Some(callback) = self.queue_events.lock().unwrap().as_ref() {
+            callback.call(
+                QueueEvent {
+                    kind,
+                    operation,
+                    error,
+                },
+                ThreadsafeFunctionCallMode::NonBlocking,
+            );
+        }
+    }
 
-        Ok(stores.iter().map(Store::from).collect())
+    /// Send queued changes in order until one can't be sent, returning how
+    /// many were. Changes AnyList refuses, and ones that lose a conflict, are
+    /// dropped so they can't hold up the rest.
+    async fn send_queued(&self) -> Result<u32> {
+        let _flushing = self.flush_lock.lock().await;
+        let mut sent = 0;
+        loop {
+            let Some((operation, endpoint, body)) =
+                self.offline_queue.lock().unwrap().front().map(|entry| {
+                    (
+                        entry.operation.clone(),
+                        entry.endpoint.clone(),
+                        entry.body(),
+                    )
+                })
+            else {
+                break;
+            };
+
+            let send = async {
+                let list = match self.inner.get_list_by_id(&operation.list_id).await {
+                    Ok(list) => Some(List::from(&list)),
+                    Err(AnyListError::NotFound(_)) => None,
+                    Err(e) if transport::is_unreachable(&e) => return Ok(None),
+                    Err(e) => return Err(to_napi_error(e)),
+                };
+                let result = match queue::check(&operation, list.as_ref()) {
+                    queue::Check::Clean => transport::post(&self.inner, &endpoint, body)
+                        .await
+                        .map(|_| QueueEventType::Flushed),
+                    queue::Check::Applied => Ok(QueueEventType::Flushed),
+                    queue::Check::Conflict(conflict) => {
+                        let resolver = self.conflict_resolver.lock().unwrap().clone();
+                        queue::settle(&self.inner, resolver, *conflict, &endpoint, body).await?
+                    }
+                };
+                Ok(Some(result))
+            };
+            // Waiting for another change to the list here could deadlock
+            // with it, since changes flush the queue first; this change is
+            // left for the next flush instead
+            let Some(result) = self
+                .list_locks
+                .try_run(operation.list_id.clone(), send)
+                .await
+                .transpose()?
+                .flatten()
+            else {
+                break;
+            };
+            if result.as_ref().is_err_and(transport::is_unreachable) {
+                break;
+            }
+
+            let Some(entry) = self.offline_queue.lock().unwrap().pop()? else {
+                break;
+            };
+            match result {
+                Ok(kind) => {
+                    if kind == QueueEventType::Flushed {
+                        sent += 1;
+                        self.cache
+                            .lock()
+                            .unwrap()
+                            .invalidate(Some(CacheEntity::Lists));
+                    }
+                    self.emit_queue_event(kind, entry.operation, None);
+                }
+                Err(e) => {
+                    self.emit_queue_event(
+                        QueueEventType::Rejected,
+                        entry.operation,
+                        Some(e.to_string()),
+                    );
+                }
+            }
+        }
+        Ok(sent)
     }
 
-    /// Create a new store for a list
-    #[napi]
-    pub async fn create_store(&self, list_id: String, name: String) -> Result<Store> {
-        let store = self
-            .inner
-            .create_store(&list_id, &name)
-            .await
-            .map_err(to_napi_error)?;
+    /// Whether a change has to wait in the offline queue behind earlier ones
+    /// that still can't be sent
+    async fn must_queue(&self) -> Result<bool> {
+        if !self.offline_queue.lock().unwrap().is_enabled() {
+            return Ok(false);
+        }
+        self.send_queued().await?;
+        Ok(!self.offline_queue.lock().unwrap().is_empty())
+    }
 
-        Ok(Store::from(&store))
+    /// Whether a failed change can be queued to send later
+    fn can_queue(&self, error: &AnyListError) -> bool {
+        self.offline_queue.lock().unwrap().is_enabled() && transport::is_unreachable(error)
     }
 
-    /// Update a store's name
-    #[napi]
-    pub async fn update_store(
+    fn enqueue(
         &self,
-        list_id: String,
-        store_id: String,
-        new_name: String,
+        change: QueuedChange,
+        list_id: &str,
+        item_id: &str,
+        name: Option<&str>,
+        operation: PbListOperation,
     ) -> Result<()> {
-        self.inner
-            .update_store(&list_id, &store_id, &new_name)
-            .await
-            .map_err(to_napi_error)?;
-
+        let queued = QueuedOperation::new(change, list_id, item_id, name, &operation);
+        let operation_list = PbListOperationList {
+            operations: vec![operation],
+        };
+        self.offline_queue.lock().unwrap().push(queue::Entry::new(
+            queued.clone(),
+            "data/shopping-lists/update",
+            &operation_list,
+        ))?;
+        self.emit_queue_event(QueueEventType::Queued, queued, None);
         Ok(())
     }
 
-    /// Get store filters for a list
-    #[napi]
-    pub async fn get_store_filters_for_list(&self, list_id: String) -> Result<Vec<StoreFilter>> {
-        let filters = self
-            .inner
-            .get_store_filters_for_list(&list_id)
-            .await
-            .map_err(to_napi_error)?;
+    /// Add an item, queueing it if AnyList can't be reached
+    async fn add_item_or_queue(
+        &self,
+        list_id: &str,
+        name: &str,
+        quantity: Option<String>,
+        note: Option<String>,
+        category: Option<String>,
+    ) -> Result<ListItem> {
+        if let Some(optimistic) = self.optimistic() {
+            if !optimistic.has_lists() {
+                self.get_lists().await?;
+            }
+            let operation =
+                operations::add_item(&self.inner, list_id, name, quantity, note, category, None);
+            let item = ListItem::from(
+                operation
+                    .list_item
+                    .as_ref()
+                    .unwrap_or(&PbListItem::default()),
+            );
+            let queued =
+                QueuedOperation::new(QueuedChange::Add, list_id, &item.id, Some(name), &operation);
+            optimistic.apply(queued, operation, item.clone(), None);
+            return Ok(item);
+        }
 
-        Ok(filters.iter().map(StoreFilter::from).collect())
+        if !self.must_queue().await? {
+            match self
+                .inner
+                .add_item_with_details(
+                    list_id,
+                    name,
+                    quantity.as_deref(),
+                    note.as_deref(),
+                    category.as_deref(),
+                )
+                .await
+            {
+                Ok(item) => return Ok(ListItem::from(&item)),
+                Err(e) if self.can_queue(&e) => {}
+                Err(e) => return Err(to_napi_error(e)),
+            }
+        }
+
+        let operation =
+            operations::add_item(&self.inner, list_id, name, quantity, note, category, None);
+        let item = ListItem::from(
+            operation
+                .list_item
+                .as_ref()
+                .unwrap_or(&PbListItem::default()),
+        );
+        self.enqueue(QueuedChange::Add, list_id, &item.id, Some(name), operation)?;
+        Ok(item)
     }
 
-    /// Delete a store from a list
-    #[napi]
-    pub async fn delete_store(&self, list_id: String, store_id: String) -> Result<()> {
-        self.inner
-            .delete_store(&list_id, &store_id)
-            .await
-            .map_err(to_napi_error)?;
+    /// Check or uncheck an item, queueing the change if AnyList can't be
+    /// reached
+    async fn set_checked_or_queue(
+        &self,
+        list_id: &str,
+        item_id: &str,
+        checked: bool,
+    ) -> Result<()> {
+        let change = if checked {
+            QueuedChange::Check
+        } else {
+            QueuedChange::Uncheck
+        };
+
+        if let Some(optimistic) = self.optimistic() {
+            // A copy with nothing unsent can be re-read for an item added
+            // elsewhere since
+            if optimistic.item(list_id, item_id).is_none() && optimistic.is_idle() {
+                self.get_lists().await?;
+            }
+            let previous = optimistic
+                .item(list_id, item_id)
+                .ok_or_else(|| Error::new(Status::GenericFailure, "Item not found"))?;
+            let operation = operations::set_item_checked(&self.inner, list_id, item_id, checked);
+            let queued =
+                QueuedOperation::new(change, list_id, item_id, Some(&previous.name), &operation);
+            let was_checked = previous.checked;
+            let item = ListItem {
+                checked,
+                ..previous
+            };
+            optimistic.apply(queued, operation, item, Some(was_checked));
+            return Ok(());
+        }
 
-        Ok(())
+        if !self.must_queue().await? {
+            let result = if checked {
+                self.inner.cross_off_item(list_id, item_id).await
+            } else {
+                self.inner.uncheck_item(list_id, item_id).await
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) if self.can_queue(&e) => {}
+                Err(e) => return Err(to_napi_error(e)),
+            }
+        }
+
+        let operation = operations::set_item_checked(&self.inner, list_id, item_id, checked);
+        // Known if the lists are cached, so a rename can be noticed later
+        let name = self.cache.lock().unwrap().lists().and_then(|lists| {
+            lists
+                .into_iter()
+                .find(|l| l.id == list_id)?
+                .items
+                .into_iter()
+                .find(|i| i.id == item_id)
+                .map(|i| i.name)
+        });
+        self.enqueue(change, list_id, item_id, name.as_deref(), operation)
     }
 
-    // ==================== Favourites Methods ====================
+    /// The day a meal plan date falls on
+    /// Date-times with an offset ("2024-01-15T02:00:00Z") are read like a
+    /// `Date`; ones without are taken to already be local
+    fn day(&self, date: &MealPlanDate) -> Result<chrono::NaiveDate> {
+        let text = match date {
+            MealPlanDate::Timestamp(timestamp) => {
+                return Some(*timestamp)
+                    .filter(|timestamp| timestamp.is_finite())
+                    .and_then(|timestamp| chrono::DateTime::from_timestamp_millis(timestamp as i64))
+                    .map(|instant| self.local_day(instant))
+                    .ok_or_else(|| Error::new(Status::InvalidArg, "Invalid Date"));
+            }
+            MealPlanDate::Day(text) => text.trim(),
+        };
+
+        if let Ok(day) = parse_date(text) {
+            return Ok(day);
+        }
+        if let Ok(instant) = chrono::DateTime::parse_from_rfc3339(text) {
+            return Ok(self.local_day(instant.to_utc()));
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+            if let Ok(local) = chrono::NaiveDateTime::parse_from_str(text, format) {
+                return Ok(local.date());
+            }
+        }
 
-    /// Get all favourite items across all lists
-    #[napi]
-    pub async fn get_favourites(&self) -> Result<Vec<FavouriteItem>> {
-        let favourites = self.inner.get_favourites().await.map_err(to_napi_error)?;
+        let message = if text.contains(['/', '.']) {
+            format!(
+                "Ambiguous date '{}': day and month order isn't clear, use YYYY-MM-DD",
+                text
+            )
+        } else {
+            format!(
+                "Invalid date '{}', expected YYYY-MM-DD, an ISO 8601 date-time, a Date, \
+                 or milliseconds since the epoch",
+                text
+            )
+        };
+        Err(Error::new(Status::InvalidArg, message))
+    }
 
-        Ok(favourites.iter().map(FavouriteItem::from).collect())
+    /// The days a pair of meal plan dates fall on, checking they're in order
+    fn date_range(
+        &self,
+        start: &MealPlanDate,
+        end: &MealPlanDate,
+    ) -> Result<(chrono::NaiveDate, chrono::NaiveDate)> {
+        let (start, end) = (self.day(start)?, self.day(end)?);
+        check_date_order(start, end)?;
+        Ok((start, end))
     }
 
-    /// Get all favourites lists (starter lists)
-    #[napi]
-    pub async fn get_favourites_lists(&self) -> Result<Vec<FavouritesList>> {
-        let lists = self
-            .inner
-            .get_favourites_lists()
+    /// The day an instant falls on in the client's time zone
+    fn local_day(&self, instant: chrono::DateTime<chrono::Utc>) -> chrono::NaiveDate {
+        local_day_in(*self.time_zone.lock().unwrap(), instant)
+    }
+
+    /// Today's date in the client's time zone (YYYY-MM-DD)
+    fn today(&self) -> String {
+        self.local_day(chrono::Utc::now())
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    /// Create a recipe mapped from schema.org data, importing its image as the photo
+    async fn create_schema_org_recipe(
+        &self,
+        data: &serde_json::Value,
+        mut options: CreateRecipeOptions,
+        page_url: Option<&reqwest::Url>,
+    ) -> Result<Recipe> {
+        let image_url = schema_org::image_url(data).and_then(|image| match page_url {
+            Some(base) => base.join(&image).ok(),
+            None => reqwest::Url::parse(&image).ok(),
+        });
+
+        if let Some(image_url) = image_url {
+            // A missing or broken image shouldn't stop the recipe being imported
+            options.photo_id = self.import_photo(&image_url).await.ok();
+        }
+
+        self.add_recipe(options).await
+    }
+
+    /// Fetch all recipes, with when each was last cooked
+    async fn recipes(&self) -> Result<Vec<Recipe>> {
+        let generation = {
+            let cache = self.cache.lock().unwrap();
+            if let Some(recipes) = cache.recipes() {
+                return Ok(recipes);
+            }
+            cache.generation(CacheEntity::Recipes)
+        };
+        // Keyed like lists, so reads after a change don't share a fetch from
+        // before it
+        self.recipes_fetch
+            .get(&generation.to_string(), || self.fetch_recipes(generation))
             .await
-            .map_err(to_napi_error)?;
+    }
+
+    /// Fetch all recipes, caching them if the cache is still at `generation`
+    async fn fetch_recipes(&self, generation: u64) -> Result<Vec<Recipe>> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error);
+        let data = self.note_fetch("getRecipes", CacheEntity::Recipes, data)?;
+        let today = self.today();
+
+        let recipes = recipes_from(&data, &today);
+        self.cache.lock().unwrap().put_recipes(generation, &recipes);
+        Ok(recipes)
+    }
+
+    /// Fetch a recipe collection by ID
+    async fn recipe_collection(&self, collection_id: &str) -> Result<RsRecipeCollection> {
+        self.inner
+            .get_recipe_collections()
+            .await
+            .map_err(to_napi_error)?
+            .into_iter()
+            .find(|collection| collection.id() == collection_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Collection with ID {} not found",
+                    collection_id
+                )))
+            })
+    }
+
+    /// The meal plan calendar's ID and its events between two dates (inclusive),
+    /// as AnyList stores them
+    async fn raw_meal_plan_events(
+        &self,
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    ) -> Result<(String, Vec<PbCalendarEvent>)> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let response = data
+            .meal_planning_calendar_response
+            .ok_or_else(|| Error::new(Status::GenericFailure, "No meal plan calendar found"))?;
+
+        let events = response
+            .events
+            .into_iter()
+            .filter(|event| event_in_range(event, start, end))
+            .collect();
+        Ok((response.calendar_id, events))
+    }
 
-        Ok(lists.iter().map(FavouritesList::from).collect())
+    /// The meal plan calendar's ID and one of its events, as AnyList stores it
+    async fn raw_meal_plan_event(&self, event_id: &str) -> Result<(String, PbCalendarEvent)> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
+        let response = data
+            .meal_planning_calendar_response
+            .ok_or_else(|| Error::new(Status::GenericFailure, "No meal plan calendar found"))?;
+
+        let event = response
+            .events
+            .into_iter()
+            .find(|event| event.identifier == event_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Meal plan event with ID {} not found",
+                    event_id
+                )))
+            })?;
+        Ok((response.calendar_id, event))
     }
 
-    /// Get favourites for a specific shopping list
-    #[napi]
-    pub async fn get_favourites_for_list(
+    /// Create a meal plan event, for `createMealPlanEvent` and the changes
+    /// that create events one at a time
+    async fn add_meal_plan_event(
         &self,
-        shopping_list_id: String,
-    ) -> Result<FavouritesList> {
-        let list = self
+        calendar_id: &str,
+        date: &MealPlanDate,
+        recipe_id: Option<&str>,
+        title: Option<&str>,
+        label_id: Option<&str>,
+    ) -> Result<MealPlanEvent> {
+        let date = self.day(date)?.format("%Y-%m-%d").to_string();
+        let event = self
             .inner
-            .get_favourites_for_list(&shopping_list_id)
+            .create_meal_plan_event(calendar_id, &date, recipe_id, title, label_id)
             .await
             .map_err(to_napi_error)?;
 
-        Ok(FavouritesList::from(&list))
+        Ok(MealPlanEvent::from(&event))
     }
 
-    /// Add a favourite item to the default list
-    #[napi]
-    pub async fn add_favourite(
+    /// Add copies of meal plan events (with new IDs) in a single request
+    async fn add_meal_plan_events(
         &self,
-        name: String,
-        category: Option<String>,
-    ) -> Result<FavouriteItem> {
-        let item = self
-            .inner
-            .add_favourite(&name, category.as_deref())
-            .await
-            .map_err(to_napi_error)?;
+        calendar_id: &str,
+        events: Vec<PbCalendarEvent>,
+    ) -> Result<Vec<MealPlanEvent>> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let events: Vec<PbCalendarEvent> = events
+            .into_iter()
+            .map(|event| PbCalendarEvent {
+                identifier: transport::generate_id(),
+                logical_timestamp: Some(1),
+                calendar_id: Some(calendar_id.to_string()),
+                ..event
+            })
+            .collect();
+        let operations = PbCalendarOperationList {
+            operations: events
+                .iter()
+                .map(|event| operations::new_event(&self.inner, calendar_id, event.clone()))
+                .collect(),
+        };
+        transport::post_message(
+            &self.inner,
+            "data/meal-planning-calendar/update",
+            &operations,
+        )
+        .await
+        .map_err(to_napi_error)?;
 
-        Ok(FavouriteItem::from(&item))
+        Ok(events.iter().map(MealPlanEvent::from).collect())
     }
 
-    /// Add a favourite item to a specific list
-    #[napi]
-    pub async fn add_favourite_to_list(
-        &self,
-        list_id: String,
-        name: String,
-        category: Option<String>,
-    ) -> Result<FavouriteItem> {
-        let item = self
-            .inner
-            .add_favourite_to_list(&list_id, &name, category.as_deref())
-            .await
-            .map_err(to_napi_error)?;
+    /// Fetch all recipes as AnyList stores them
+    async fn raw_recipes(&self) -> Result<Vec<PbRecipe>> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
 
-        Ok(FavouriteItem::from(&item))
+        Ok(data
+            .recipe_data_response
+            .into_iter()
+            .flat_map(|response| response.recipes)
+            .filter(|recipe| recipe.name.is_some())
+            .collect())
     }
 
-    /// Remove a favourite item from a list
-    #[napi]
-    pub async fn remove_favourite(&self, list_id: String, item_id: String) -> Result<()> {
-        self.inner
-            .remove_favourite(&list_id, &item_id)
+    /// Fetch a recipe as AnyList stores it
+    async fn raw_recipe(&self, recipe_id: &str) -> Result<PbRecipe> {
+        self.raw_recipes()
+            .await?
+            .into_iter()
+            .find(|recipe| recipe.identifier == recipe_id)
+            .ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "Recipe with ID {} not found",
+                    recipe_id
+                )))
+            })
+    }
+
+    /// Save a whole recipe, replacing what AnyList has stored
+    async fn save_raw_recipe(&self, mut recipe: PbRecipe) -> Result<Recipe> {
+        recipe.timestamp = Some(transport::current_timestamp());
+
+        let operations = PbRecipeOperationList {
+            operations: vec![operations::save_recipe(&self.inner, recipe)],
+        };
+        transport::post_message(&self.inner, "data/user-recipe-data/update", &operations)
             .await
             .map_err(to_napi_error)?;
 
-        Ok(())
+        let recipe_id = operations.operations[0]
+            .recipe
+            .as_ref()
+            .map(|recipe| recipe.identifier.clone())
+            .unwrap_or_default();
+        self.get_recipe_by_id(recipe_id).await
     }
 
-    /// Add a favourite item to a shopping list
-    #[napi]
-    pub async fn add_favourite_to_shopping_list(
+    /// Change a recipe as AnyList stores it, leaving everything else untouched
+    async fn update_raw_recipe(
         &self,
-        favourite_list_id: String,
-        favourite_id: String,
-        shopping_list_id: String,
-    ) -> Result<ListItem> {
-        // First get the favourite item
-        let favourites_list = self
-            .inner
-            .get_favourites_for_list(&favourite_list_id)
-            .await
-            .map_err(to_napi_error)?;
-
-        let favourite = favourites_list
-            .items()
-            .iter()
-            .find(|f| f.id() == favourite_id)
-            .ok_or_else(|| Error::new(Status::GenericFailure, "Favourite item not found"))?;
+        recipe_id: &str,
+        update: impl FnOnce(&mut PbRecipe),
+    ) -> Result<Recipe> {
+        let mut recipe = self.raw_recipe(recipe_id).await?;
+        self.remember_recipe(recipe.clone());
+        update(&mut recipe);
 
-        let item = self
-            .inner
-            .add_favourite_to_shopping_list(favourite, &shopping_list_id)
-            .await
-            .map_err(to_napi_error)?;
+        self.save_raw_recipe(recipe).await
+    }
 
-        Ok(ListItem::from(&item))
+    /// The category the rules give a new item, if any
+    fn rule_category(&self, list_id: &str, name: &str) -> Option<String> {
+        self.category_rules
+            .lock()
+            .unwrap()
+            .category_for(name, Some(list_id))
     }
 
-    // ==================== Meal Planning Methods ====================
+    /// Count a favourite being added to a shopping list
+    fn record_favourite_use(&self, favourite_id: &str) {
+        let mut usage = self.favourite_usage.lock().unwrap();
+        let entry = usage
+            .entry(favourite_id.to_string())
+            .or_insert(FavouriteUsage {
+                times_added: 0,
+                last_added_at: 0.0,
+            });
+        entry.times_added += 1;
+        entry.last_added_at = transport::current_timestamp() * 1000.0;
+    }
 
-    /// Get meal plan events for a date range
-    #[napi]
-    pub async fn get_meal_plan_events(
-        &self,
-        start_date: String,
-        end_date: String,
-    ) -> Result<Vec<MealPlanEvent>> {
-        let events = self
-            .inner
-            .get_meal_plan_events(&start_date, &end_date)
-            .await
-            .map_err(to_napi_error)?;
+    /// Remember a recipe as it was before a change, so it can be reverted
+    fn remember_recipe(&self, recipe: PbRecipe) {
+        let mut history = self.recipe_history.lock().unwrap();
+        let versions = history.entry(recipe.identifier.clone()).or_default();
+        versions.push(RecipeSnapshot {
+            id: transport::generate_id(),
+            saved_at: transport::current_timestamp() * 1000.0,
+            recipe,
+        });
+        let overflow = versions.len().saturating_sub(RECIPE_HISTORY_LIMIT);
+        versions.drain(..overflow);
+    }
 
-        Ok(events.iter().map(MealPlanEvent::from).collect())
+    /// Get a list from the optimistic copy when there is one, otherwise from
+    /// AnyList, sharing the fetch with concurrent calls for the same list
+    async fn list(&self, list_id: &str) -> Result<List> {
+        match self.optimistic().and_then(|o| o.lists()) {
+            Some(lists) => lists.into_iter().find(|l| l.id == list_id).ok_or_else(|| {
+                to_napi_error(AnyListError::NotFound(format!(
+                    "List with ID {} not found",
+                    list_id
+                )))
+            }),
+            None => {
+                self.list_fetch
+                    .get(list_id, || async {
+                        let list = self
+                            .inner
+                            .get_list_by_id(list_id)
+                            .await
+                            .map_err(to_napi_error)?;
+                        Ok(List::from(&list))
+                    })
+                    .await
+            }
+        }
     }
 
-    /// Create a meal plan event
-    #[napi]
-    pub async fn create_meal_plan_event(
-        &self,
-        calendar_id: String,
-        date: String,
-        recipe_id: Option<String>,
-        title: Option<String>,
-        label_id: Option<String>,
-    ) -> Result<MealPlanEvent> {
-        let event = self
-            .inner
-            .create_meal_plan_event(
-                &calendar_id,
-                &date,
-                recipe_id.as_deref(),
-                title.as_deref(),
-                label_id.as_deref(),
-            )
-            .await
-            .map_err(to_napi_error)?;
+    /// Create a recipe, for `createRecipe` and the changes that create recipes
+    async fn add_recipe(&self, options: CreateRecipeOptions) -> Result<Recipe> {
+        let rs_ingredients: Vec<RsIngredient> =
+            options.ingredients.iter().map(RsIngredient::from).collect();
 
-        Ok(MealPlanEvent::from(&event))
+        let mut builder = RecipeBuilder::new(&options.name)
+            .ingredients(rs_ingredients)
+            .preparation_steps(step_lines(options.preparation_steps, options.steps));
+
+        if let Some(note) = options.note {
+            builder = builder.note(note);
+        }
+        if let Some(source_name) = options.source_name {
+            builder = builder.source_name(source_name);
+        }
+        if let Some(source_url) = options.source_url {
+            builder = builder.source_url(source_url);
+        }
+        if let Some(servings) = options.servings {
+            builder = builder.servings(servings);
+        }
+        if let Some(prep_time) = time_minutes(options.prep_time.as_ref())? {
+            builder = builder.prep_time(prep_time);
+        }
+        if let Some(cook_time) = time_minutes(options.cook_time.as_ref())? {
+            builder = builder.cook_time(cook_time);
+        }
+        if let Some(rating) = options.rating {
+            builder = builder.rating(rating);
+        }
+        if let Some(nutritional_info) = options.nutritional_info {
+            builder = builder.nutritional_info(nutritional_info);
+        }
+        if let Some(photo_id) = self.photo_id(options.photo_id, options.photo_url).await? {
+            builder = builder.photo_id(photo_id);
+        }
+
+        let recipe = builder.save(&self.inner).await.map_err(to_napi_error)?;
+
+        // Re-read the saved recipe so timestamps are filled in
+        self.get_recipe_by_id(recipe.id().to_string()).await
     }
 
-    /// Update a meal plan event
-    #[napi]
-    pub async fn update_meal_plan_event(
-        &self,
-        calendar_id: String,
-        event_id: String,
-        date: String,
-        recipe_id: Option<String>,
-        title: Option<String>,
-        label_id: Option<String>,
-    ) -> Result<()> {
+    /// Delete a recipe, remembering it for `revertRecipe`
+    async fn remove_recipe(&self, recipe_id: &str) -> Result<()> {
+        if let Ok(recipe) = self.raw_recipe(recipe_id).await {
+            self.remember_recipe(recipe);
+        }
         self.inner
-            .update_meal_plan_event(
-                &calendar_id,
-                &event_id,
-                &date,
-                recipe_id.as_deref(),
-                title.as_deref(),
-                label_id.as_deref(),
-            )
+            .delete_recipe(recipe_id)
             .await
             .map_err(to_napi_error)?;
 
         Ok(())
     }
 
-    /// Delete a meal plan event
-    #[napi]
-    pub async fn delete_meal_plan_event(
+    /// Add recipes' ingredients to a list in one request, expanding linked
+    /// recipes
+    async fn add_recipe_ingredients(
         &self,
-        calendar_id: String,
-        event_id: String,
+        list_id: &str,
+        recipes: &[RecipeSelection],
     ) -> Result<()> {
-        self.inner
-            .delete_meal_plan_event(&calendar_id, &event_id)
-            .await
-            .map_err(to_napi_error)?;
+        for selection in recipes {
+            if let Some(factor) = selection.scale_factor {
+                check_scale_factor(factor)?;
+            }
+        }
+        let all_recipes = self.recipes().await?;
+        let recipes_by_id: HashMap<&str, &Recipe> = all_recipes
+            .iter()
+            .map(|recipe| (recipe.id.as_str(), recipe))
+            .collect();
+
+        let mut ops = Vec::new();
+        for selection in recipes {
+            let recipe = recipes_by_id
+                .get(selection.recipe_id.as_str())
+                .ok_or_else(|| {
+                    to_napi_error(AnyListError::NotFound(format!(
+                        "Recipe with ID {} not found",
+                        selection.recipe_id
+                    )))
+                })?;
+
+            let mut ingredients = Vec::new();
+            shopping_ingredients(
+                recipe,
+                selection.scale_factor,
+                &recipes_by_id,
+                &mut Vec::new(),
+                &mut ingredients,
+            );
+            for (recipe_id, ingredient) in ingredients {
+                ops.push(operations::add_item(
+                    &self.inner,
+                    list_id,
+                    &ingredient.name,
+                    ingredient.quantity,
+                    ingredient.note,
+                    None,
+                    Some(recipe_id),
+                ));
+            }
+        }
+
+        if !ops.is_empty() {
+            let operation_list = PbListOperationList { operations: ops };
+            transport::post_message(&self.inner, "data/shopping-lists/update", &operation_list)
+                .await
+                .map_err(to_napi_error)?;
+        }
 
         Ok(())
     }
 
-    /// Add meal plan ingredients to a shopping list
-    #[napi]
-    pub async fn add_meal_plan_ingredients_to_list(
+    /// Set or clear a recipe's photo
+    async fn set_recipe_photo(&self, recipe_id: &str, photo_id: Option<String>) -> Result<Recipe> {
+        self.update_raw_recipe(recipe_id, |recipe| {
+            recipe.photo_ids = photo_id.into_iter().collect();
+            recipe.photo_urls.clear();
+        })
+        .await
+    }
+
+    /// Every recipe as it's backed up, with its photo if `include_photos`
+    /// A photo that can't be downloaded is left out, and why is recorded in
+    /// the recipe's `photoError` rather than failing the whole backup
+    async fn backup_recipes(
         &self,
-        list_id: String,
-        start_date: String,
-        end_date: String,
+        include_photos: bool,
+    ) -> Result<Vec<(serde_json::Value, Option<export::EmbeddedPhoto>)>> {
+        let recipes = self.raw_recipes().await?;
+
+        let mut entries = Vec::with_capacity(recipes.len());
+        for recipe in &recipes {
+            let mut json = export::to_json_value(&Recipe::from(recipe), &recipe.photo_urls)?;
+            let photo = match recipe_photo_url(recipe) {
+                Some(url) if include_photos => match self.download_photo(&url).await {
+                    Ok(photo) => Some(photo),
+                    Err(e) => {
+                        if let serde_json::Value::Object(fields) = &mut json {
+                            fields.insert("photoError".to_string(), e.reason.clone().into());
+                        }
+                        None
+                    }
+                },
+                _ => None,
+            };
+            entries.push((json, photo));
+        }
+        Ok(entries)
+    }
+
+    /// Restore a list from a snapshot into `existing`, the list with its
+    /// name, or a new list if there isn't one
+    async fn restore_list(
+        &self,
+        list: &List,
+        existing: Option<List>,
+        replace: bool,
+        restored: &mut RestoreResult,
     ) -> Result<()> {
-        self.inner
-            .add_meal_plan_ingredients_to_list(&list_id, &start_date, &end_date)
-            .await
-            .map_err(to_napi_error)?;
+        let entity =
+            |kind, old_id: &str, new_id: &str, name: &str, list_id: Option<&str>| RestoredEntity {
+                kind,
+                old_id: old_id.to_string(),
+                new_id: new_id.to_string(),
+                name: name.to_string(),
+                list_id: list_id.map(str::to_string),
+            };
+        let mut target = match existing {
+            Some(existing) => {
+                restored.skipped.push(entity(
+                    SnapshotEntity::List,
+                    &list.id,
+                    &existing.id,
+                    &list.name,
+                    None,
+                ));
+                existing
+            }
+            None => {
+                let created = self
+                    .inner
+                    .create_list(&list.name)
+                    .await
+                    .map_err(to_napi_error)?;
+                let created = List::from(&created);
+                restored.created.push(entity(
+                    SnapshotEntity::List,
+                    &list.id,
+                    &created.id,
+                    &list.name,
+                    None,
+                ));
+                created
+            }
+        };
+        if replace && !target.items.is_empty() {
+            self.remove_items(&target.id, |_| true).await?;
+            target.items.clear();
+        }
+
+        let mut ops = Vec::new();
+        for item in &list.items {
+            let name = normalize_name(&item.name);
+            if let Some(current) = target
+                .items
+                .iter()
+                .find(|i| normalize_name(&i.name) == name)
+            {
+                restored.skipped.push(entity(
+                    SnapshotEntity::ListItem,
+                    &item.id,
+                    &current.id,
+                    &item.name,
+                    Some(&target.id),
+                ));
+                continue;
+            }
+
+            let op = operations::add_item(
+                &self.inner,
+                &target.id,
+                &item.name,
+                item.quantity.clone(),
+                Some(item.note.clone()).filter(|note| !note.is_empty()),
+                item.category.clone(),
+                None,
+            );
+            let new_id = op.list_item_id.clone().unwrap_or_default();
+            ops.push(op);
+            if item.checked {
+                ops.push(operations::set_item_checked(
+                    &self.inner,
+                    &target.id,
+                    &new_id,
+                    true,
+                ));
+            }
+            restored.created.push(entity(
+                SnapshotEntity::ListItem,
+                &item.id,
+                &new_id,
+                &item.name,
+                Some(&target.id),
+            ));
+        }
 
+        if !ops.is_empty() {
+            let operation_list = PbListOperationList { operations: ops };
+            transport::post_message(&self.inner, "data/shopping-lists/update", &operation_list)
+                .await
+                .map_err(to_napi_error)?;
+        }
         Ok(())
     }
 
-    // ==================== iCalendar Methods ====================
+    /// Download a photo along with its content type
+    async fn download_photo(&self, url: &str) -> Result<export::EmbeddedPhoto> {
+        let response = transport::fetch(url).await.map_err(to_napi_error)?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("image/jpeg")
+            .to_string();
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| to_napi_error(e.into()))?;
 
-    /// Enable iCalendar sync and get the URL
-    #[napi]
-    pub async fn enable_icalendar(&self) -> Result<ICalendarInfo> {
-        let info = self.inner.enable_icalendar().await.map_err(to_napi_error)?;
+        Ok(export::EmbeddedPhoto {
+            content_type,
+            data: data.to_vec(),
+        })
+    }
 
-        Ok(ICalendarInfo::from(&info))
+    /// The photo to use for a recipe: the given ID, or an image downloaded
+    /// from the given URL
+    async fn photo_id(
+        &self,
+        photo_id: Option<String>,
+        photo_url: Option<String>,
+    ) -> Result<Option<String>> {
+        match (photo_id, photo_url) {
+            (Some(photo_id), _) => Ok(Some(photo_id)),
+            (None, Some(url)) => {
+                let url = reqwest::Url::parse(&url)
+                    .map_err(|e| Error::new(Status::InvalidArg, format!("Invalid URL: {}", e)))?;
+                self.import_photo(&url).await.map(Some)
+            }
+            (None, None) => Ok(None),
+        }
     }
 
-    /// Disable iCalendar sync
-    #[napi]
-    pub async fn disable_icalendar(&self) -> Result<()> {
-        self.inner
-            .disable_icalendar()
+    /// Download an image and upload it as a recipe photo
+    async fn import_photo(&self, url: &reqwest::Url) -> Result<String> {
+        let data = transport::fetch(url.as_str())
             .await
-            .map_err(to_napi_error)?;
+            .map_err(to_napi_error)?
+            .bytes()
+            .await
+            .map_err(|e| to_napi_error(e.into()))?;
+        let filename = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("photo.jpg");
 
-        Ok(())
+        self.inner
+            .upload_photo(data.to_vec(), filename)
+            .await
+            .map_err(to_napi_error)
     }
 
-    /// Get the iCalendar URL if enabled
-    #[napi]
-    pub async fn get_icalendar_url(&self) -> Result<Option<String>> {
-        let url = self
+    /// Delete the matching items of a list in a single request, remembering them in the trash
+    /// Returns how many items were deleted
+    async fn remove_items(
+        &self,
+        list_id: &str,
+        predicate: impl Fn(&RsListItem) -> bool,
+    ) -> Result<usize> {
+        let list = self
             .inner
-            .get_icalendar_url()
+            .get_list_by_id(list_id)
             .await
             .map_err(to_napi_error)?;
 
-        Ok(url)
+        let removed: Vec<&RsListItem> = list.items().iter().filter(|i| predicate(i)).collect();
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        let params = BulkRemoveItemsParams {
+            operation_id: transport::generate_id(),
+            user_id: self.inner.user_id(),
+            list_id: list_id.to_string(),
+            items: removed
+                .iter()
+                .map(|item| ItemToRemove {
+                    item_id: item.id().to_string(),
+                    list_id: item.list_id().to_string(),
+                    name: item.name().to_string(),
+                    category: item.category().map(|s| s.to_string()),
+                    user_id: self.inner.user_id(),
+                    category_match_id: item.category().map(|s| s.to_string()),
+                    category_assignment: None,
+                })
+                .collect(),
+        };
+
+        transport::post_message(
+            &self.inner,
+            "data/shopping-lists/update",
+            &build_bulk_remove_items_operation(params),
+        )
+        .await
+        .map_err(to_napi_error)?;
+
+        let count = removed.len();
+        let mut trash = self.trash.lock().unwrap();
+        let items = trash.entry(list_id.to_string()).or_default();
+        items.extend(removed.into_iter().map(ListItem::from));
+        let overflow = items.len().saturating_sub(TRASH_LIMIT);
+        items.drain(..overflow);
+
+        Ok(count)
     }
 
-    // ==================== Recipe Collection Methods ====================
+    /// Get a list's settings from user data, or start new settings if it has none yet
+    fn list_settings_for(&self, data: &PbUserDataResponse, list_id: &str) -> PbListSettings {
+        data.list_settings_response
+            .as_ref()
+            .and_then(|r| {
+                r.settings
+                    .iter()
+                    .find(|s| s.list_id.as_deref() == Some(list_id))
+            })
+            .cloned()
+            .unwrap_or_else(|| PbListSettings {
+                identifier: transport::generate_id(),
+                user_id: Some(self.inner.user_id()),
+                list_id: Some(list_id.to_string()),
+                ..Default::default()
+            })
+    }
 
-    /// Get all recipe collections
-    #[napi]
-    pub async fn get_recipe_collections(&self) -> Result<Vec<RecipeCollection>> {
-        let collections = self
-            .inner
-            .get_recipe_collections()
-            .await
-            .map_err(to_napi_error)?;
+    /// Find a favourites list by ID
+    async fn favourites_list(&self, id: &str) -> Result<PbStarterList> {
+        let data = self.inner.get_user_data().await.map_err(to_napi_error)?;
 
-        Ok(collections.iter().map(RecipeCollection::from).collect())
+        let list = starter_lists(&data).find(|l| l.identifier == id).cloned();
+
+        list.ok_or_else(|| {
+            to_napi_error(AnyListError::NotFound(format!(
+                "Favourites list with ID {} not found",
+                id
+            )))
+        })
     }
 
-    /// Create a new recipe collection
-    #[napi]
-    pub async fn create_recipe_collection(&self, name: String) -> Result<RecipeCollection> {
-        let collection = self
-            .inner
-            .create_recipe_collection(&name)
+    /// Rename a favourites list
+    async fn post_starter_list_rename(
+        &self,
+        list_id: &str,
+        old_name: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        let operation_list = PbStarterListOperationList {
+            operations: vec![operations::rename_starter_list(
+                &self.inner,
+                list_id,
+                old_name,
+                new_name,
+            )],
+        };
+
+        transport::post_message(&self.inner, "data/starter-lists/update", &operation_list)
             .await
             .map_err(to_napi_error)?;
 
-        Ok(RecipeCollection::from(&collection))
+        Ok(())
     }
 
-    /// Delete a recipe collection
-    #[napi]
-    pub async fn delete_recipe_collection(&self, collection_id: String) -> Result<()> {
-        self.inner
-            .delete_recipe_collection(&collection_id)
+    /// Save a list's full settings
+    async fn save_list_settings(&self, settings: &PbListSettings) -> Result<()> {
+        let operation_list = PbListSettingsOperationList {
+            operations: vec![PbListSettingsOperation {
+                metadata: Some(operations::metadata(&self.inner, "save-list-settings")),
+                updated_settings: Some(settings.clone()),
+            }],
+        };
+
+        transport::post_message(&self.inner, "data/list-settings/update", &operation_list)
             .await
             .map_err(to_napi_error)?;
 
         Ok(())
     }
 
-    /// Add a recipe to a collection
-    #[napi]
-    pub async fn add_recipe_to_collection(
+    /// Send one field update per supplied patch field, in a single request
+    async fn apply_item_patch(
         &self,
-        collection_id: String,
-        recipe_id: String,
-    ) -> Result<()> {
-        self.inner
-            .add_recipe_to_collection(&collection_id, &recipe_id)
-            .await
-            .map_err(to_napi_error)?;
+        list_id: &str,
+        existing: &RsListItem,
+        patch: ItemPatch,
+    ) -> Result<ListItem> {
+        let mut item = ListItem::from(existing);
+        let mut ops = Vec::new();
+        let mut set = |handler_id: &str, value: &str| {
+            ops.push(operations::set_item_field(
+                &self.inner,
+                handler_id,
+                list_id,
+                &item.id,
+                value,
+            ))
+        };
+
+        if let Some(name) = patch.name {
+            set("set-list-item-name", &name);
+            item.name = name;
+        }
+        if let Some(quantity) = patch.quantity {
+            set("set-list-item-quantity", &quantity);
+            item.quantity = Some(quantity);
+        }
+        if let Some(note) = patch.note {
+            set("set-list-item-details", &note);
+            item.note = note;
+        }
+        if let Some(category) = patch.category {
+            set("set-list-item-category-match-id", &category);
+            item.category = Some(category);
+        }
+        if let Some(checked) = patch.checked {
+            ops.push(operations::set_item_checked(
+                &self.inner,
+                list_id,
+                &item.id,
+                checked,
+            ));
+            item.checked = checked;
+        }
 
-        Ok(())
+        if !ops.is_empty() {
+            let operation_list = PbListOperationList { operations: ops };
+            transport::post_message(&self.inner, "data/shopping-lists/update", &operation_list)
+                .await
+                .map_err(to_napi_error)?;
+        }
+
+        Ok(item)
     }
 
-    /// Remove a recipe from a collection
-    #[napi]
-    pub async fn remove_recipe_from_collection(
+    /// Check or uncheck several items in a single request
+    async fn set_items_checked(
         &self,
-        collection_id: String,
-        recipe_id: String,
+        list_id: &str,
+        item_ids: &[String],
+        checked: bool,
     ) -> Result<()> {
-        self.inner
-            .remove_recipe_from_collection(&collection_id, &recipe_id)
+        if item_ids.is_empty() {
+            return Ok(());
+        }
+
+        let operation_list = PbListOperationList {
+            operations: item_ids
+                .iter()
+                .map(|item_id| operations::set_item_checked(&self.inner, list_id, item_id, checked))
+                .collect(),
+        };
+
+        transport::post_message(&self.inner, "data/shopping-lists/update", &operation_list)
             .await
             .map_err(to_napi_error)?;
 
@@ -0,0 +1,73 @@
+//! Making the changes to each list one at a time.
+//!
+//! A change to a list waits for any other change to it made through the same
+//! client to finish, so concurrent calls can't interleave their reads and
+//! writes. Changes to different lists still run in parallel. A change that
+//! makes further changes to the same list along the way (e.g. `ensureCategory`
+//! creating the category) doesn't wait for itself. Batches, optimistic
+//! updates, and offline queue flushes share the same locks.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+tokio::task_local! {
+    /// The lists the running change holds the lock for
+    static HELD: Vec<String>;
+}
+
+type Lock = Arc<tokio::sync::Mutex<()>>;
+
+#[derive(Default)]
+pub(crate) struct ListLocks {
+    locks: Mutex<HashMap<String, Lock>>,
+}
+
+impl ListLocks {
+    fn lock_for(&self, list_id: &str) -> Lock {
+        let mut locks = self.locks.lock().unwrap();
+        // Forget locks nobody is holding or waiting for
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        Arc::clone(locks.entry(list_id.to_string()).or_default())
+    }
+
+    /// Run `change` once no other change to the list is running
+    pub async fn run<T>(&self, list_id: String, change: impl Future<Output = T>) -> T {
+        self.run_all(vec![list_id], change).await
+    }
+
+    /// Run `change` once no other change to any of the lists is running
+    pub async fn run_all<T>(
+        &self,
+        mut list_ids: Vec<String>,
+        change: impl Future<Output = T>,
+    ) -> T {
+        let mut held = HELD.try_with(Clone::clone).unwrap_or_default();
+        // Taken in order, so two changes to the same lists can't each be
+        // waiting for the other
+        list_ids.sort();
+        list_ids.dedup();
+        let mut guards = Vec::with_capacity(list_ids.len());
+        for list_id in list_ids {
+            if held.contains(&list_id) {
+                continue;
+            }
+            guards.push(self.lock_for(&list_id).lock_owned().await);
+            held.push(list_id);
+        }
+        HELD.scope(held, change).await
+    }
+
+    /// Run `change` if no other change to the list is running, or return
+    /// `None` straight away
+    pub async fn try_run<T>(&self, list_id: String, change: impl Future<Output = T>) -> Option<T> {
+        let mut held = HELD.try_with(Clone::clone).unwrap_or_default();
+        if held.contains(&list_id) {
+            return Some(change.await);
+        }
+        let lock = self.lock_for(&list_id);
+        let _guard = lock.try_lock().ok()?;
+        held.push(list_id);
+        Some(HELD.scope(held, change).await)
+    }
+}
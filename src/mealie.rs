@@ -0,0 +1,169 @@
+//! Reading Mealie recipe exports.
+//!
+//! Mealie exports recipes as JSON, either one recipe per file or as a list.
+//! Ingredients may be parsed (food, unit, quantity) or free text, so both are
+//! accepted.
+
+use napi::{Error, Result, Status};
+use serde_json::Value;
+
+use crate::schema_org::{nutrition, parse_ingredient, parse_minutes};
+use crate::{CreateRecipeOptions, IngredientInput, RecipeTime};
+
+/// A recipe read from a Mealie export, with the names of its tags
+pub(crate) struct ImportedRecipe {
+    pub options: CreateRecipeOptions,
+    pub tags: Vec<String>,
+}
+
+/// Read the recipes in a Mealie export: a recipe, a list of recipes, or an
+/// object with a `recipes` list
+pub(crate) fn read_export(json: &Value) -> Result<Vec<ImportedRecipe>> {
+    let recipes = match json {
+        Value::Array(recipes) => recipes.iter().collect(),
+        Value::Object(export) => match export.get("recipes") {
+            Some(Value::Array(recipes)) => recipes.iter().collect(),
+            _ => vec![json],
+        },
+        _ => Vec::new(),
+    };
+
+    recipes.into_iter().map(convert).collect()
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = match value? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn names(value: Option<&Value>) -> impl Iterator<Item = String> + '_ {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|v| text(v.get("name")).or_else(|| text(Some(v))))
+}
+
+fn convert(recipe: &Value) -> Result<ImportedRecipe> {
+    let name = text(recipe.get("name"))
+        .ok_or_else(|| Error::new(Status::InvalidArg, "Mealie recipe has no name"))?;
+
+    let notes: Vec<String> = recipe
+        .get("notes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|note| {
+            let body = text(note.get("text"))?;
+            Some(match text(note.get("title")) {
+                Some(title) => format!("{}: {}", title, body),
+                None => body,
+            })
+        })
+        .collect();
+    let note = text(recipe.get("description"))
+        .into_iter()
+        .chain(notes)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let prep_time = text(recipe.get("prepTime")).and_then(|t| parse_minutes(&t));
+    let cook_time = text(recipe.get("performTime"))
+        .or_else(|| text(recipe.get("cookTime")))
+        .and_then(|t| parse_minutes(&t))
+        .or_else(|| {
+            let total = parse_minutes(&text(recipe.get("totalTime"))?)?;
+            Some(total - prep_time.unwrap_or(0)).filter(|minutes| *minutes > 0)
+        });
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in names(recipe.get("tags")).chain(names(recipe.get("recipeCategory"))) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            tags.push(tag);
+        }
+    }
+
+    Ok(ImportedRecipe {
+        options: CreateRecipeOptions {
+            name,
+            ingredients: recipe
+                .get("recipeIngredient")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .flat_map(ingredients)
+                .collect(),
+            preparation_steps: recipe
+                .get("recipeInstructions")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|step| text(step.get("text")).or_else(|| text(Some(step))))
+                .collect(),
+            steps: None,
+            note: (!note.is_empty()).then_some(note),
+            source_name: None,
+            source_url: text(recipe.get("orgURL")),
+            servings: text(recipe.get("recipeYield")),
+            prep_time: prep_time.map(RecipeTime::from),
+            cook_time: cook_time.map(RecipeTime::from),
+            rating: recipe
+                .get("rating")
+                .and_then(Value::as_f64)
+                .map(|rating| rating.round() as i32)
+                .filter(|rating| (1..=5).contains(rating)),
+            nutritional_info: recipe.get("nutrition").and_then(nutrition),
+            photo_id: None,
+            photo_url: None,
+        },
+        tags,
+    })
+}
+
+/// An ingredient, preceded by a header if it starts a titled section
+fn ingredients(value: &Value) -> Vec<IngredientInput> {
+    let header = text(value.get("title")).map(|title| IngredientInput {
+        name: title,
+        quantity: None,
+        note: None,
+        is_header: Some(true),
+    });
+
+    header.into_iter().chain(ingredient(value)).collect()
+}
+
+fn ingredient(value: &Value) -> Option<IngredientInput> {
+    if let Value::String(line) = value {
+        return Some(parse_ingredient(line)).filter(|i| !i.name.is_empty());
+    }
+
+    // Parsed ingredients have a food; unparsed ones only carry their text
+    let Some(food) = value.get("food").and_then(|food| text(food.get("name"))) else {
+        let line = text(value.get("originalText"))
+            .or_else(|| text(value.get("display")))
+            .or_else(|| text(value.get("note")))?;
+        return Some(parse_ingredient(&line));
+    };
+
+    let amount = value
+        .get("quantity")
+        .and_then(Value::as_f64)
+        .filter(|quantity| *quantity > 0.0)
+        .map(|quantity| quantity.to_string());
+    let unit = value.get("unit").and_then(|unit| text(unit.get("name")));
+    let quantity = match (amount, unit) {
+        (Some(amount), Some(unit)) => Some(format!("{} {}", amount, unit)),
+        (amount, unit) => amount.or(unit),
+    };
+
+    Some(IngredientInput {
+        name: food,
+        quantity,
+        note: text(value.get("note")),
+        is_header: None,
+    })
+}
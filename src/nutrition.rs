@@ -0,0 +1,104 @@
+//! Reading calories and macronutrients from recipe nutrition text.
+//!
+//! AnyList stores nutrition as free text. Imported recipes use "Name: value"
+//! lines ("Calories: 320 kcal", "Protein: 12 g"), and hand-written ones tend to
+//! put everything on one line ("320 calories, 12g protein"), so each
+//! comma-separated part is read with the name on either side of the amount.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::NutritionTotals;
+
+fn amount_regex() -> &'static Regex {
+    static AMOUNT: OnceLock<Regex> = OnceLock::new();
+    AMOUNT.get_or_init(|| {
+        Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(kcal|kj|mg|g|cal)?\b").expect("valid regex")
+    })
+}
+
+#[derive(Clone, Copy)]
+enum Nutrient {
+    Calories,
+    Protein,
+    Carbohydrates,
+    Fat,
+}
+
+impl Nutrient {
+    fn parse(name: &str) -> Option<Nutrient> {
+        let name = name.to_lowercase();
+        // "Calories from fat" and the fat breakdowns aren't totals
+        if name.contains("from") {
+            return None;
+        }
+        if name.contains("calorie") || name.contains("energy") || name.contains("kcal") {
+            return Some(Nutrient::Calories);
+        }
+        if name.contains("protein") {
+            return Some(Nutrient::Protein);
+        }
+        if name.contains("carb") {
+            return Some(Nutrient::Carbohydrates);
+        }
+        if name.contains("fat")
+            && !["saturated", "trans", "mono", "poly"]
+                .iter()
+                .any(|kind| name.contains(kind))
+        {
+            return Some(Nutrient::Fat);
+        }
+        None
+    }
+}
+
+/// Read the calories and macronutrients in a recipe's nutrition text, or
+/// `None` if it mentions none of them
+pub(crate) fn parse_nutrition(text: &str) -> Option<NutritionTotals> {
+    let mut totals = NutritionTotals::default();
+    let mut found = false;
+
+    for part in text.split(['\n', ',', ';', '|']) {
+        let Some(captures) = amount_regex().captures(part) else {
+            continue;
+        };
+        let Some(amount) = captures.get(0) else {
+            continue;
+        };
+        let Ok(value) = captures[1].replace(',', ".").parse::<f64>() else {
+            continue;
+        };
+        let unit = captures.get(2).map(|unit| unit.as_str().to_lowercase());
+
+        // The name comes before the amount ("Protein: 12 g") or after it ("12g protein")
+        let before = &part[..amount.start()];
+        let after = &part[amount.end()..];
+        let Some(nutrient) = Nutrient::parse(before)
+            .or_else(|| Nutrient::parse(after))
+            .or_else(|| unit.as_deref().and_then(Nutrient::parse))
+        else {
+            continue;
+        };
+
+        let value = match (nutrient, unit.as_deref()) {
+            (Nutrient::Calories, Some("kj")) => value / 4.184,
+            (Nutrient::Calories, _) => value,
+            (_, Some("mg")) => value / 1000.0,
+            _ => value,
+        };
+        let slot = match nutrient {
+            Nutrient::Calories => &mut totals.calories,
+            Nutrient::Protein => &mut totals.protein,
+            Nutrient::Carbohydrates => &mut totals.carbohydrates,
+            Nutrient::Fat => &mut totals.fat,
+        };
+        // Labels sometimes list a nutrient twice (e.g. kcal and kJ); keep the first
+        if *slot == 0.0 {
+            *slot = value;
+        }
+        found = true;
+    }
+
+    found.then_some(totals)
+}
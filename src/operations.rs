@@ -0,0 +1,254 @@
+//! Builders for AnyList operations that anylist_rs doesn't provide.
+
+use anylist_rs::protobuf::anylist::{
+    pb_operation_metadata::OperationClass, PbCalendarEvent, PbCalendarOperation,
+    PbListCategoryGroup, PbListItem, PbListOperation, PbOperationMetadata, PbRecipe,
+    PbRecipeOperation, PbStarterListOperation,
+};
+use anylist_rs::AnyListClient as RsClient;
+
+use crate::transport::{current_timestamp, generate_id};
+use crate::RawListOperation;
+
+/// Build operation metadata for a handler
+pub(crate) fn metadata(client: &RsClient, handler_id: &str) -> PbOperationMetadata {
+    PbOperationMetadata {
+        operation_id: Some(generate_id()),
+        handler_id: Some(handler_id.to_string()),
+        user_id: Some(client.user_id()),
+        operation_class: Some(OperationClass::Undefined as i32),
+    }
+}
+
+/// Build a list operation that adds a new item, optionally from a recipe
+pub(crate) fn add_item(
+    client: &RsClient,
+    list_id: &str,
+    name: &str,
+    quantity: Option<String>,
+    details: Option<String>,
+    category: Option<String>,
+    recipe_id: Option<&str>,
+) -> PbListOperation {
+    let item_id = generate_id();
+
+    PbListOperation {
+        metadata: Some(metadata(client, "add-shopping-list-item")),
+        list_id: Some(list_id.to_string()),
+        list_item_id: Some(item_id.clone()),
+        list_item: Some(PbListItem {
+            identifier: item_id,
+            server_mod_time: Some(current_timestamp()),
+            list_id: Some(list_id.to_string()),
+            name: Some(name.to_string()),
+            quantity,
+            details,
+            category,
+            checked: Some(false),
+            recipe_id: recipe_id.map(|id| id.to_string()),
+            user_id: Some(client.user_id()),
+            manual_sort_index: Some(0),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Build a list operation that sets a single field of an item
+pub(crate) fn set_item_field(
+    client: &RsClient,
+    handler_id: &str,
+    list_id: &str,
+    item_id: &str,
+    value: &str,
+) -> PbListOperation {
+    PbListOperation {
+        metadata: Some(metadata(client, handler_id)),
+        list_id: Some(list_id.to_string()),
+        list_item_id: Some(item_id.to_string()),
+        updated_value: Some(value.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Build a list operation that checks or unchecks an item
+pub(crate) fn set_item_checked(
+    client: &RsClient,
+    list_id: &str,
+    item_id: &str,
+    checked: bool,
+) -> PbListOperation {
+    set_item_field(
+        client,
+        "set-list-item-checked",
+        list_id,
+        item_id,
+        if checked { "y" } else { "n" },
+    )
+}
+
+/// Build a list operation from its fields, as given to `encodeOperation()`
+pub(crate) fn raw(client: &RsClient, operation: RawListOperation) -> PbListOperation {
+    let list_item = operation.list_item.map(|item| PbListItem {
+        identifier: item.identifier.unwrap_or_else(generate_id),
+        list_id: Some(operation.list_id.clone()),
+        name: item.name,
+        quantity: item.quantity,
+        details: item.details,
+        category: item.category,
+        checked: item.checked,
+        user_id: Some(client.user_id()),
+        ..Default::default()
+    });
+
+    PbListOperation {
+        metadata: Some(metadata(client, &operation.handler_id)),
+        list_id: Some(operation.list_id),
+        list_item_id: operation.list_item_id,
+        updated_value: operation.updated_value,
+        original_value: operation.original_value,
+        list_item,
+        ..Default::default()
+    }
+}
+
+/// Build a list operation that adds an empty category group
+pub(crate) fn create_category_group(
+    client: &RsClient,
+    list_id: &str,
+    group_id: &str,
+    name: &str,
+) -> PbListOperation {
+    PbListOperation {
+        metadata: Some(PbOperationMetadata {
+            operation_class: Some(OperationClass::ListCategoryGroup as i32),
+            ..metadata(client, "create-category-group")
+        }),
+        list_id: Some(list_id.to_string()),
+        updated_category_group: Some(PbListCategoryGroup {
+            identifier: Some(group_id.to_string()),
+            logical_timestamp: Some(1),
+            list_id: Some(list_id.to_string()),
+            name: Some(name.to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Build a list operation that removes a category group and its categories
+pub(crate) fn remove_category_group(
+    client: &RsClient,
+    list_id: &str,
+    group_id: &str,
+) -> PbListOperation {
+    PbListOperation {
+        metadata: Some(PbOperationMetadata {
+            operation_class: Some(OperationClass::ListCategoryGroup as i32),
+            ..metadata(client, "remove-category-group")
+        }),
+        list_id: Some(list_id.to_string()),
+        original_value: Some(group_id.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Build a starter list operation that adds a new favourite
+pub(crate) fn add_starter_item(
+    client: &RsClient,
+    list_id: &str,
+    name: &str,
+    quantity: Option<String>,
+    details: Option<String>,
+    category: Option<String>,
+) -> PbStarterListOperation {
+    let item_id = generate_id();
+
+    PbStarterListOperation {
+        metadata: Some(metadata(client, "add-starter-list-item")),
+        list_id: Some(list_id.to_string()),
+        list_item_id: Some(item_id.clone()),
+        list_item: Some(PbListItem {
+            identifier: item_id,
+            list_id: Some(list_id.to_string()),
+            name: Some(name.to_string()),
+            quantity,
+            details,
+            category,
+            user_id: Some(client.user_id()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Build a starter list operation that renames a favourites list
+pub(crate) fn rename_starter_list(
+    client: &RsClient,
+    list_id: &str,
+    old_name: &str,
+    new_name: &str,
+) -> PbStarterListOperation {
+    PbStarterListOperation {
+        metadata: Some(metadata(client, "rename-starter-list")),
+        list_id: Some(list_id.to_string()),
+        updated_value: Some(new_name.to_string()),
+        original_value: Some(old_name.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Build a starter list operation that sets a single field of a favourite
+pub(crate) fn set_starter_item_field(
+    client: &RsClient,
+    handler_id: &str,
+    list_id: &str,
+    item_id: &str,
+    value: &str,
+) -> PbStarterListOperation {
+    PbStarterListOperation {
+        metadata: Some(metadata(client, handler_id)),
+        list_id: Some(list_id.to_string()),
+        list_item_id: Some(item_id.to_string()),
+        updated_value: Some(value.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Build a recipe operation that saves a whole recipe
+pub(crate) fn save_recipe(client: &RsClient, recipe: PbRecipe) -> PbRecipeOperation {
+    PbRecipeOperation {
+        metadata: Some(metadata(client, "save-recipe")),
+        recipe: Some(recipe),
+        is_new_recipe_from_web_import: Some(false),
+        ..Default::default()
+    }
+}
+
+/// Build a calendar operation that adds a meal plan event
+pub(crate) fn new_event(
+    client: &RsClient,
+    calendar_id: &str,
+    event: PbCalendarEvent,
+) -> PbCalendarOperation {
+    PbCalendarOperation {
+        metadata: Some(metadata(client, "new-event")),
+        calendar_id: Some(calendar_id.to_string()),
+        updated_event: Some(event),
+        ..Default::default()
+    }
+}
+
+/// Build a calendar operation that saves changes to a meal plan event
+pub(crate) fn update_event(
+    client: &RsClient,
+    calendar_id: &str,
+    event: PbCalendarEvent,
+) -> PbCalendarOperation {
+    PbCalendarOperation {
+        metadata: Some(metadata(client, "update-event")),
+        calendar_id: Some(calendar_id.to_string()),
+        updated_event: Some(event),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,263 @@
+//! Optimistic updates.
+//!
+//! Changes are applied to a local copy of the lists straight away and sent to
+//! AnyList in the background, one at a time in the order they were made.
+//! While any are unsent, lists are read from the local copy; once all are
+//! sent, reads go back to AnyList and refresh it. Each change is checked
+//! against the list first, like a queued change, and one that conflicts with
+//! changes made elsewhere goes to the conflict resolver. A change that can't
+//! be sent, or that loses a conflict, is undone in the local copy.
+
+use std::sync::{Arc, Mutex};
+
+use anylist_rs::protobuf::anylist::{PbListOperation, PbListOperationList};
+use anylist_rs::{AnyListClient as RsClient, AnyListError};
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
+use prost::Message;
+use tokio::sync::mpsc;
+
+use crate::{
+    cache::Cache, list_locks::ListLocks, queue, transport, CacheEntity, ConflictResolver, List,
+    ListItem, OptimisticEvent, OptimisticEventCallback, OptimisticEventType, QueueEventType,
+    QueuedOperation,
+};
+
+type Resolver = Arc<Mutex<Option<Arc<ConflictResolver>>>>;
+
+const ENDPOINT: &str = "data/shopping-lists/update";
+
+/// A change applied to the local copy that's waiting to be sent
+struct Change {
+    operation: QueuedOperation,
+    list_operation: PbListOperation,
+    /// Whether the item was checked before, or `None` if the change added it
+    was_checked: Option<bool>,
+}
+
+#[derive(Default)]
+struct Local {
+    lists: Option<Vec<List>>,
+    unsent: usize,
+}
+
+impl Local {
+    /// Put an item into its list, replacing the one with its ID if there is
+    /// one
+    fn put_item(&mut self, list_id: &str, item: ListItem) {
+        let Some(list) = self.lists.iter_mut().flatten().find(|l| l.id == list_id) else {
+            return;
+        };
+        match list.items.iter_mut().find(|i| i.id == item.id) {
+            Some(existing) => *existing = item,
+            None => list.items.push(item),
+        }
+    }
+
+    fn set_checked(&mut self, list_id: &str, item_id: &str, checked: bool) {
+        let item = self
+            .lists
+            .iter_mut()
+            .flatten()
+            .find(|l| l.id == list_id)
+            .and_then(|list| list.items.iter_mut().find(|i| i.id == item_id));
+        if let Some(item) = item {
+            item.checked = checked;
+        }
+    }
+
+    fn remove_item(&mut self, list_id: &str, item_id: &str) {
+        if let Some(list) = self.lists.iter_mut().flatten().find(|l| l.id == list_id) {
+            list.items.retain(|i| i.id != item_id);
+        }
+    }
+}
+
+/// The local copy of the lists and the changes to it still being sent
+#[derive(Clone)]
+pub(crate) struct Optimistic {
+    local: Arc<Mutex<Local>>,
+    changes: mpsc::UnboundedSender<Change>,
+}
+
+impl Optimistic {
+    /// Start sending changes in the background, calling `callback` as each
+    /// is confirmed or rolled back. Sending stops once every copy of the
+    /// returned value is dropped and the changes already made are sent.
+    pub fn start(
+        client: Arc<RsClient>,
+        list_locks: Arc<ListLocks>,
+        resolver: Resolver,
+        cache: Arc<Mutex<Cache>>,
+        callback: Option<OptimisticEventCallback>,
+    ) -> Self {
+        let local = Arc::new(Mutex::new(Local::default()));
+        let (changes, receiver) = mpsc::unbounded_channel();
+        napi::bindgen_prelude::spawn(send_changes(
+            client,
+            list_locks,
+            resolver,
+            cache,
+            Arc::clone(&local),
+            receiver,
+            callback,
+        ));
+        Optimistic { local, changes }
+    }
+
+    /// The local copy, while changes to it are unsent
+    pub fn lists(&self) -> Option<Vec<List>> {
+        let local = self.local.lock().unwrap();
+        if local.unsent == 0 {
+            return None;
+        }
+        local.lists.clone()
+    }
+
+    pub fn has_lists(&self) -> bool {
+        self.local.lock().unwrap().lists.is_some()
+    }
+
+    /// Refresh the local copy with lists read from AnyList, unless it has
+    /// unsent changes they'd undo
+    pub fn put_lists(&self, lists: &[List]) {
+        let mut local = self.local.lock().unwrap();
+        if local.unsent == 0 {
+            local.lists = Some(lists.to_vec());
+        }
+    }
+
+    /// An item as it is in the local copy
+    pub fn item(&self, list_id: &str, item_id: &str) -> Option<ListItem> {
+        let local = self.local.lock().unwrap();
+        local
+            .lists
+            .iter()
+            .flatten()
+            .find(|l| l.id == list_id)?
+            .items
+            .iter()
+            .find(|i| i.id == item_id)
+            .cloned()
+    }
+
+    /// Whether nothing is waiting to be sent
+    pub fn is_idle(&self) -> bool {
+        self.local.lock().unwrap().unsent == 0
+    }
+
+    /// Put an item into the local copy as a change leaves it, and send the
+    /// change in the background
+    pub fn apply(
+        &self,
+        operation: QueuedOperation,
+        list_operation: PbListOperation,
+        item: ListItem,
+        was_checked: Option<bool>,
+    ) {
+        let mut local = self.local.lock().unwrap();
+        local.put_item(&operation.list_id, item);
+        local.unsent += 1;
+        let change = Change {
+            operation,
+            list_operation,
+            was_checked,
+        };
+        if let Err(mpsc::error::SendError(change)) = self.changes.send(change) {
+            local.unsent -= 1;
+            undo(&mut local, &change);
+        }
+    }
+}
+
+/// Undo a change in the local copy, leaving anything else done to the item
+/// since (e.g. a rename) as it is
+fn undo(local: &mut Local, change: &Change) {
+    let list_id = &change.operation.list_id;
+    let item_id = change.operation.item_id.as_deref().unwrap_or_default();
+    match change.was_checked {
+        Some(checked) => local.set_checked(list_id, item_id, checked),
+        None => local.remove_item(list_id, item_id),
+    }
+}
+
+/// Check a change against its list and send it, or settle the conflict,
+/// returning why if it wasn't made
+async fn send(client: &RsClient, resolver: &Resolver, change: &Change) -> Result<(), String> {
+    let list = match client.get_list_by_id(&change.operation.list_id).await {
+        Ok(list) => Some(List::from(&list)),
+        Err(AnyListError::NotFound(_)) => None,
+        Err(e) => return Err(e.to_string()),
+    };
+    let body = PbListOperationList {
+        operations: vec![change.list_operation.clone()],
+    }
+    .encode_to_vec();
+
+    let sent = match queue::check(&change.operation, list.as_ref()) {
+        queue::Check::Clean => transport::post(client, ENDPOINT, body)
+            .await
+            .map(|_| QueueEventType::Flushed),
+        queue::Check::Applied => Ok(QueueEventType::Flushed),
+        queue::Check::Conflict(conflict) => {
+            let kind = conflict.kind;
+            let resolver = resolver.lock().unwrap().clone();
+            match queue::settle(client, resolver, *conflict, ENDPOINT, body).await {
+                Ok(Ok(QueueEventType::Discarded)) => {
+                    let kind = serde_json::to_value(kind).unwrap_or_default();
+                    return Err(format!(
+                        "Dropped for a conflicting change made elsewhere ({})",
+                        kind.as_str().unwrap_or_default()
+                    ));
+                }
+                Ok(sent) => sent,
+                Err(e) => return Err(e.reason.clone()),
+            }
+        }
+    };
+    sent.map(|_| ()).map_err(|e| e.to_string())
+}
+
+async fn send_changes(
+    client: Arc<RsClient>,
+    list_locks: Arc<ListLocks>,
+    resolver: Resolver,
+    cache: Arc<Mutex<Cache>>,
+    local: Arc<Mutex<Local>>,
+    mut changes: mpsc::UnboundedReceiver<Change>,
+    callback: Option<OptimisticEventCallback>,
+) {
+    while let Some(change) = changes.recv().await {
+        let result = list_locks
+            .run(
+                change.operation.list_id.clone(),
+                send(&client, &resolver, &change),
+            )
+            .await;
+
+        let (kind, error) = {
+            let mut local = local.lock().unwrap();
+            local.unsent -= 1;
+            match result {
+                Ok(()) => {
+                    // Cached lists from before the change would undo it
+                    cache.lock().unwrap().invalidate(Some(CacheEntity::Lists));
+                    (OptimisticEventType::Confirmed, None)
+                }
+                Err(e) => {
+                    undo(&mut local, &change);
+                    (OptimisticEventType::RolledBack, Some(e))
+                }
+            }
+        };
+        if let Some(callback) = &callback {
+            callback.call(
+                OptimisticEvent {
+                    kind,
+                    operation: change.operation,
+                    error,
+                },
+                ThreadsafeFunctionCallMode::NonBlocking,
+            );
+        }
+    }
+}
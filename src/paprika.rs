@@ -0,0 +1,127 @@
+//! Reading Paprika recipe exports.
+//!
+//! A `.paprikarecipes` file is a zip archive with one `.paprikarecipe` entry
+//! per recipe. Each entry is a gzip-compressed JSON document, with the photo
+//! embedded as base64.
+
+use std::io::{Cursor, Read};
+
+use base64::Engine;
+use flate2::read::GzDecoder;
+use napi::{Error, Result, Status};
+use serde::Deserialize;
+
+use crate::schema_org::{parse_ingredient, parse_minutes};
+use crate::{CreateRecipeOptions, RecipeTime};
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PaprikaRecipe {
+    name: String,
+    ingredients: Option<String>,
+    directions: Option<String>,
+    description: Option<String>,
+    notes: Option<String>,
+    source: Option<String>,
+    source_url: Option<String>,
+    servings: Option<String>,
+    prep_time: Option<String>,
+    cook_time: Option<String>,
+    total_time: Option<String>,
+    rating: Option<i32>,
+    nutritional_info: Option<String>,
+    photo_data: Option<String>,
+}
+
+/// A recipe read from a Paprika export, with its photo if it had one
+pub(crate) struct ImportedRecipe {
+    pub options: CreateRecipeOptions,
+    pub photo: Option<Vec<u8>>,
+}
+
+fn import_error(message: impl std::fmt::Display) -> Error {
+    Error::new(
+        Status::InvalidArg,
+        format!("Invalid Paprika export: {}", message),
+    )
+}
+
+/// Read every recipe in a `.paprikarecipes` archive
+pub(crate) fn read_export(data: &[u8]) -> Result<Vec<ImportedRecipe>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(import_error)?;
+    let mut recipes = Vec::with_capacity(archive.len());
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(import_error)?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let mut compressed = Vec::new();
+        entry.read_to_end(&mut compressed).map_err(import_error)?;
+        let mut json = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut json)
+            .map_err(import_error)?;
+
+        let recipe: PaprikaRecipe = serde_json::from_slice(&json).map_err(import_error)?;
+        recipes.push(convert(recipe));
+    }
+
+    Ok(recipes)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn lines(text: Option<&str>) -> impl Iterator<Item = &str> {
+    text.unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+fn convert(recipe: PaprikaRecipe) -> ImportedRecipe {
+    let note = match (non_empty(recipe.description), non_empty(recipe.notes)) {
+        (Some(description), Some(notes)) => Some(format!("{}\n\n{}", description, notes)),
+        (description, notes) => description.or(notes),
+    };
+    let prep_time = recipe.prep_time.as_deref().and_then(parse_minutes);
+    let cook_time = recipe
+        .cook_time
+        .as_deref()
+        .and_then(parse_minutes)
+        .or_else(|| {
+            let total = parse_minutes(recipe.total_time.as_deref()?)?;
+            Some(total - prep_time.unwrap_or(0)).filter(|minutes| *minutes > 0)
+        });
+    let photo = non_empty(recipe.photo_data)
+        .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok());
+
+    ImportedRecipe {
+        options: CreateRecipeOptions {
+            name: recipe.name.trim().to_string(),
+            ingredients: lines(recipe.ingredients.as_deref())
+                .map(parse_ingredient)
+                .collect(),
+            preparation_steps: lines(recipe.directions.as_deref())
+                .map(str::to_string)
+                .collect(),
+            steps: None,
+            note,
+            source_name: non_empty(recipe.source),
+            source_url: non_empty(recipe.source_url),
+            servings: non_empty(recipe.servings),
+            prep_time: prep_time.map(RecipeTime::from),
+            cook_time: cook_time.map(RecipeTime::from),
+            rating: recipe.rating.filter(|rating| (1..=5).contains(rating)),
+            nutritional_info: non_empty(recipe.nutritional_info),
+            photo_id: None,
+            photo_url: None,
+        },
+        photo,
+    }
+}
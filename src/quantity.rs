@@ -0,0 +1,496 @@
+//! Parsing, scaling, and formatting ingredient quantities.
+//!
+//! Quantities are free text ("1 1/2 cups", "2-3 cloves", "½ tsp"). The leading
+//! amount is parsed as a number (or a range), scaled, and written back as a
+//! kitchen-friendly fraction, with the unit adjusted to match.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::UnitSystem;
+
+const FRACTION_CHARS: &str = "¼½¾⅐⅑⅒⅓⅔⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞";
+
+fn amount_regex() -> &'static Regex {
+    static AMOUNT: OnceLock<Regex> = OnceLock::new();
+    AMOUNT.get_or_init(|| {
+        let number = format!(
+            r"\d+\s+\d+/\d+|\d+/\d+|\d+(?:[.,]\d+)?(?:\s*[{f}])?|[{f}]",
+            f = FRACTION_CHARS
+        );
+        Regex::new(&format!(r"^\s*({n})(?:\s*(?:-|–|to)\s*({n}))?", n = number))
+            .expect("valid regex")
+    })
+}
+
+fn vulgar_fraction(c: char) -> Option<f64> {
+    Some(match c {
+        '¼' => 1.0 / 4.0,
+        '½' => 1.0 / 2.0,
+        '¾' => 3.0 / 4.0,
+        '⅐' => 1.0 / 7.0,
+        '⅑' => 1.0 / 9.0,
+        '⅒' => 1.0 / 10.0,
+        '⅓' => 1.0 / 3.0,
+        '⅔' => 2.0 / 3.0,
+        '⅕' => 1.0 / 5.0,
+        '⅖' => 2.0 / 5.0,
+        '⅗' => 3.0 / 5.0,
+        '⅘' => 4.0 / 5.0,
+        '⅙' => 1.0 / 6.0,
+        '⅚' => 5.0 / 6.0,
+        '⅛' => 1.0 / 8.0,
+        '⅜' => 3.0 / 8.0,
+        '⅝' => 5.0 / 8.0,
+        '⅞' => 7.0 / 8.0,
+        _ => return None,
+    })
+}
+
+/// Parse a number like "2", "1.5", "1/2", "1 1/2", "1½", or "½"
+fn parse_number(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+    for part in text.split_whitespace() {
+        let (digits, fraction) = match part.chars().last().and_then(vulgar_fraction) {
+            Some(fraction) => (
+                &part[..part.len() - part.chars().last()?.len_utf8()],
+                fraction,
+            ),
+            None => (part, 0.0),
+        };
+        total += fraction;
+        if digits.is_empty() {
+            continue;
+        }
+        total += match digits.split_once('/') {
+            Some((n, d)) => {
+                let d: f64 = d.parse().ok()?;
+                if d == 0.0 {
+                    return None;
+                }
+                n.parse::<f64>().ok()? / d
+            }
+            None => digits.replace(',', ".").parse::<f64>().ok()?,
+        };
+    }
+    Some(total)
+}
+
+/// The amount at the start of a quantity: a number or a range of numbers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Amount {
+    pub low: f64,
+    pub high: Option<f64>,
+}
+
+impl Amount {
+    fn map(self, f: impl Fn(f64) -> f64) -> Amount {
+        Amount {
+            low: f(self.low),
+            high: self.high.map(f),
+        }
+    }
+
+    fn is_plural(&self) -> bool {
+        self.high.unwrap_or(self.low) > 1.0
+    }
+}
+
+/// Split a quantity into its leading amount and the rest (usually the unit)
+pub(crate) fn parse_amount(quantity: &str) -> Option<(Amount, &str)> {
+    let captures = amount_regex().captures(quantity)?;
+    let low = parse_number(&captures[1])?;
+    let high = match captures.get(2) {
+        Some(high) => Some(parse_number(high.as_str())?),
+        None => None,
+    };
+    let rest = &quantity[captures.get(0)?.end()..];
+
+    Some((Amount { low, high }, rest.trim()))
+}
+
+/// Format a number as a whole number, a mixed fraction, or a short decimal
+pub(crate) fn format_number(value: f64) -> String {
+    const DENOMINATORS: [u32; 4] = [2, 3, 4, 8];
+    const TOLERANCE: f64 = 0.02;
+
+    let mut whole = value.trunc();
+    let fraction = value - whole;
+
+    if fraction < TOLERANCE {
+        return format!("{}", whole);
+    }
+    if 1.0 - fraction < TOLERANCE {
+        whole += 1.0;
+        return format!("{}", whole);
+    }
+
+    for d in DENOMINATORS {
+        let n = (fraction * d as f64).round();
+        if (fraction - n / d as f64).abs() < TOLERANCE && n > 0.0 {
+            return if whole == 0.0 {
+                format!("{}/{}", n, d)
+            } else {
+                format!("{} {}/{}", whole, n, d)
+            };
+        }
+    }
+
+    format_decimal(value)
+}
+
+/// Format a number with at most two decimal places
+fn format_decimal(value: f64) -> String {
+    let decimal = format!("{:.2}", value);
+    decimal
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn format_amount(amount: Amount) -> String {
+    match amount.high {
+        Some(high) => format!("{}-{}", format_number(amount.low), format_number(high)),
+        None => format_number(amount.low),
+    }
+}
+
+/// Units with singular and plural spellings
+const PLURALS: &[(&str, &str)] = &[
+    ("bunch", "bunches"),
+    ("can", "cans"),
+    ("clove", "cloves"),
+    ("cup", "cups"),
+    ("gallon", "gallons"),
+    ("gram", "grams"),
+    ("handful", "handfuls"),
+    ("head", "heads"),
+    ("jar", "jars"),
+    ("kilogram", "kilograms"),
+    ("liter", "liters"),
+    ("litre", "litres"),
+    ("ounce", "ounces"),
+    ("package", "packages"),
+    ("piece", "pieces"),
+    ("pinch", "pinches"),
+    ("pint", "pints"),
+    ("pound", "pounds"),
+    ("quart", "quarts"),
+    ("slice", "slices"),
+    ("sprig", "sprigs"),
+    ("stick", "sticks"),
+    ("tablespoon", "tablespoons"),
+    ("teaspoon", "teaspoons"),
+];
+
+/// Spell a unit in the singular or plural, keeping its capitalisation
+fn inflect(unit: &str, plural: bool) -> String {
+    let lower = unit.to_lowercase();
+    let Some(&(singular, plural_form)) = PLURALS.iter().find(|(s, p)| *s == lower || *p == lower)
+    else {
+        return unit.to_string();
+    };
+
+    let word = if plural { plural_form } else { singular };
+    if unit.chars().next().is_some_and(char::is_uppercase) {
+        let mut chars = word.chars();
+        chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        word.to_string()
+    }
+}
+
+/// US volume measures that scaled amounts move between
+#[derive(Clone, Copy, PartialEq)]
+enum Spoon {
+    Teaspoon,
+    Tablespoon,
+    Cup,
+}
+
+impl Spoon {
+    fn parse(unit: &str) -> Option<(Spoon, bool)> {
+        let unit = unit.trim_end_matches('.');
+        Some(match unit.to_lowercase().as_str() {
+            "tsp" => (Spoon::Teaspoon, true),
+            "teaspoon" | "teaspoons" => (Spoon::Teaspoon, false),
+            "tbsp" | "tbs" => (Spoon::Tablespoon, true),
+            "tablespoon" | "tablespoons" => (Spoon::Tablespoon, false),
+            "c" => (Spoon::Cup, true),
+            "cup" | "cups" => (Spoon::Cup, false),
+            _ => return None,
+        })
+    }
+
+    fn name(self, abbreviated: bool, plural: bool) -> String {
+        let name = match (self, abbreviated) {
+            (Spoon::Teaspoon, true) => return "tsp".to_string(),
+            (Spoon::Tablespoon, true) => return "tbsp".to_string(),
+            (Spoon::Cup, true) => return "c".to_string(),
+            (Spoon::Teaspoon, false) => "teaspoon",
+            (Spoon::Tablespoon, false) => "tablespoon",
+            (Spoon::Cup, false) => "cup",
+        };
+        inflect(name, plural)
+    }
+
+    /// Move to a larger or smaller measure when the amount is awkward
+    fn normalize(self, value: f64) -> (Spoon, f64) {
+        // Only move up to a larger measure if the amount stays easy to measure
+        let tidy = |value: f64| ((value * 4.0).round() - value * 4.0).abs() < 0.01;
+
+        match self {
+            Spoon::Teaspoon if value >= 3.0 && tidy(value / 3.0) => {
+                Spoon::Tablespoon.normalize(value / 3.0)
+            }
+            Spoon::Tablespoon if value >= 4.0 && tidy(value / 16.0) => (Spoon::Cup, value / 16.0),
+            Spoon::Tablespoon if value < 1.0 => (Spoon::Teaspoon, value * 3.0),
+            Spoon::Cup if value < 0.25 => Spoon::Tablespoon.normalize(value * 16.0),
+            spoon => (spoon, value),
+        }
+    }
+}
+
+/// Scale a free-text quantity, leaving it untouched if it has no amount
+pub(crate) fn scale_quantity(quantity: &str, factor: f64) -> String {
+    let Some((amount, rest)) = parse_amount(quantity) else {
+        return quantity.to_string();
+    };
+    let mut amount = amount.map(|value| value * factor);
+
+    let (unit, tail) = match rest.split_once(char::is_whitespace) {
+        Some((unit, tail)) => (unit, Some(tail)),
+        None => (rest, None),
+    };
+    if unit.is_empty() {
+        return format_amount(amount);
+    }
+
+    let unit = match Spoon::parse(unit) {
+        Some((spoon, abbreviated)) if amount.low > 0.0 => {
+            let (spoon, low) = spoon.normalize(amount.low);
+            let ratio = low / amount.low;
+            amount = amount.map(|value| value * ratio);
+            spoon.name(abbreviated, amount.is_plural())
+        }
+        _ => inflect(unit, amount.is_plural()),
+    };
+
+    match tail {
+        Some(tail) => format!("{} {} {}", format_amount(amount), unit, tail),
+        None => format!("{} {}", format_amount(amount), unit),
+    }
+}
+
+/// Units that convert between metric and US measures
+#[derive(Clone, Copy, PartialEq)]
+enum Unit {
+    Millilitre,
+    Litre,
+    Gram,
+    Kilogram,
+    Teaspoon,
+    Tablespoon,
+    Cup,
+    FluidOunce,
+    Pint,
+    Quart,
+    Gallon,
+    Ounce,
+    Pound,
+}
+
+/// Spellings of each unit, longest first so "fl oz" wins over "fl"
+const UNIT_NAMES: &[(&str, Unit)] = &[
+    ("fluid ounces", Unit::FluidOunce),
+    ("fluid ounce", Unit::FluidOunce),
+    ("millilitres", Unit::Millilitre),
+    ("milliliters", Unit::Millilitre),
+    ("tablespoons", Unit::Tablespoon),
+    ("millilitre", Unit::Millilitre),
+    ("milliliter", Unit::Millilitre),
+    ("tablespoon", Unit::Tablespoon),
+    ("kilograms", Unit::Kilogram),
+    ("teaspoons", Unit::Teaspoon),
+    ("fl. oz.", Unit::FluidOunce),
+    ("kilogram", Unit::Kilogram),
+    ("teaspoon", Unit::Teaspoon),
+    ("gallons", Unit::Gallon),
+    ("fl. oz", Unit::FluidOunce),
+    ("gallon", Unit::Gallon),
+    ("litres", Unit::Litre),
+    ("liters", Unit::Litre),
+    ("ounces", Unit::Ounce),
+    ("pounds", Unit::Pound),
+    ("quarts", Unit::Quart),
+    ("fl oz", Unit::FluidOunce),
+    ("grams", Unit::Gram),
+    ("litre", Unit::Litre),
+    ("liter", Unit::Litre),
+    ("ounce", Unit::Ounce),
+    ("pints", Unit::Pint),
+    ("pound", Unit::Pound),
+    ("quart", Unit::Quart),
+    ("cups", Unit::Cup),
+    ("gram", Unit::Gram),
+    ("pint", Unit::Pint),
+    ("tbsp", Unit::Tablespoon),
+    ("cup", Unit::Cup),
+    ("gal", Unit::Gallon),
+    ("lbs", Unit::Pound),
+    ("tbs", Unit::Tablespoon),
+    ("tsp", Unit::Teaspoon),
+    ("kg", Unit::Kilogram),
+    ("lb", Unit::Pound),
+    ("ml", Unit::Millilitre),
+    ("oz", Unit::Ounce),
+    ("pt", Unit::Pint),
+    ("qt", Unit::Quart),
+    ("c", Unit::Cup),
+    ("g", Unit::Gram),
+    ("l", Unit::Litre),
+];
+
+impl Unit {
+    /// Split a unit off the start of some text, returning the rest
+    fn parse(text: &str) -> Option<(Unit, &str)> {
+        UNIT_NAMES.iter().find_map(|&(name, unit)| {
+            // Unit names are ASCII, so this can't split a character
+            if !text.get(..name.len())?.eq_ignore_ascii_case(name) {
+                return None;
+            }
+            let rest = &text[name.len()..];
+            let rest = rest.strip_prefix('.').unwrap_or(rest);
+            if rest.starts_with(|c: char| c.is_alphanumeric()) {
+                return None;
+            }
+            Some((unit, rest.trim_start()))
+        })
+    }
+
+    /// Size in millilitres for volumes or grams for weights
+    fn size(self) -> f64 {
+        match self {
+            Unit::Millilitre | Unit::Gram => 1.0,
+            Unit::Litre | Unit::Kilogram => 1000.0,
+            Unit::Teaspoon => 4.929,
+            Unit::Tablespoon => 14.787,
+            Unit::Cup => 236.588,
+            Unit::FluidOunce => 29.574,
+            Unit::Pint => 473.176,
+            Unit::Quart => 946.353,
+            Unit::Gallon => 3785.41,
+            Unit::Ounce => 28.3495,
+            Unit::Pound => 453.592,
+        }
+    }
+
+    fn is_metric(self) -> bool {
+        matches!(
+            self,
+            Unit::Millilitre | Unit::Litre | Unit::Gram | Unit::Kilogram
+        )
+    }
+
+    fn is_weight(self) -> bool {
+        matches!(
+            self,
+            Unit::Gram | Unit::Kilogram | Unit::Ounce | Unit::Pound
+        )
+    }
+}
+
+/// Round to the nearest multiple of a step
+fn round_to(value: f64, step: f64) -> f64 {
+    (value / step).round() * step
+}
+
+/// Round a metric amount to what a scale or jug can measure
+fn round_metric(value: f64) -> f64 {
+    match value {
+        v if v < 10.0 => round_to(v, 0.5),
+        v if v < 100.0 => round_to(v, 5.0),
+        v => round_to(v, 10.0),
+    }
+}
+
+/// Convert a free-text quantity to metric or US measures, leaving it
+/// untouched if it has no amount or no convertible unit
+pub(crate) fn convert_quantity(quantity: &str, system: &UnitSystem) -> String {
+    let Some((amount, rest)) = parse_amount(quantity) else {
+        return quantity.to_string();
+    };
+    let Some((unit, tail)) = Unit::parse(rest) else {
+        return quantity.to_string();
+    };
+    let base = amount.map(|value| value * unit.size());
+
+    let (amount, unit_name) = match system {
+        UnitSystem::Metric => {
+            // Spoons are measured the same way in metric kitchens
+            if unit.is_metric() || matches!(unit, Unit::Teaspoon | Unit::Tablespoon) {
+                return quantity.to_string();
+            }
+            let (small, large) = if unit.is_weight() {
+                ("g", "kg")
+            } else {
+                ("ml", "L")
+            };
+            if base.low >= 1000.0 {
+                let amount = base.map(|value| round_to(value / 1000.0, 0.05));
+                let text = match amount.high {
+                    Some(high) => {
+                        format!("{}-{}", format_decimal(amount.low), format_decimal(high))
+                    }
+                    None => format_decimal(amount.low),
+                };
+                return join_quantity(&text, large, tail);
+            }
+            (base.map(round_metric), small.to_string())
+        }
+        UnitSystem::Imperial => {
+            if !unit.is_metric() {
+                return quantity.to_string();
+            }
+            let (target, step) = if unit.is_weight() {
+                if base.low >= Unit::Pound.size() {
+                    (Unit::Pound, 0.25)
+                } else {
+                    (Unit::Ounce, 0.5)
+                }
+            } else if base.low >= Unit::Cup.size() / 4.0 {
+                (Unit::Cup, 0.25)
+            } else if base.low >= Unit::Tablespoon.size() {
+                (Unit::Tablespoon, 0.5)
+            } else {
+                (Unit::Teaspoon, 0.25)
+            };
+            let amount = base.map(|value| round_to(value / target.size(), step));
+            if amount.low <= 0.0 {
+                return quantity.to_string();
+            }
+            let name = match target {
+                Unit::Pound => "lb".to_string(),
+                Unit::Ounce => "oz".to_string(),
+                Unit::Tablespoon => "tbsp".to_string(),
+                Unit::Teaspoon => "tsp".to_string(),
+                _ => inflect("cup", amount.is_plural()),
+            };
+            (amount, name)
+        }
+    };
+
+    join_quantity(&format_amount(amount), &unit_name, tail)
+}
+
+fn join_quantity(amount: &str, unit: &str, tail: &str) -> String {
+    if tail.is_empty() {
+        format!("{} {}", amount, unit)
+    } else {
+        format!("{} {} {}", amount, unit, tail)
+    }
+}
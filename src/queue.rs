@@ -0,0 +1,252 @@
+//! Changes waiting to be sent to AnyList while it can't be reached.
+//!
+//! Each entry is an encoded operation and the endpoint it goes to, so
+//! replaying it sends exactly what would have been sent at the time,
+//! operation IDs included. The queue is rewritten to its file after every
+//! change, replacing it whole, so nothing is lost if the process exits
+//! before it's flushed.
+//!
+//! Before a change is sent, it's checked against the list as it is then, so
+//! one that conflicts with changes made elsewhere in the meantime isn't sent
+//! blindly. Optimistic changes are checked the same way.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anylist_rs::protobuf::anylist::PbListOperationList;
+use anylist_rs::{AnyListClient as RsClient, AnyListError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use napi::{Error, Result, Status};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    normalize_name, operations, transport, ConflictResolution, ConflictResolver, ConflictType,
+    List, ListItem, QueueConflict, QueueEventType, QueuedChange, QueuedOperation,
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Entry {
+    #[serde(flatten)]
+    pub operation: QueuedOperation,
+    pub endpoint: String,
+    /// The encoded operation, base64 encoded
+    body: String,
+}
+
+impl Entry {
+    pub fn new(operation: QueuedOperation, endpoint: &str, message: &impl Message) -> Self {
+        Entry {
+            operation,
+            endpoint: endpoint.to_string(),
+            body: STANDARD.encode(message.encode_to_vec()),
+        }
+    }
+
+    pub fn body(&self) -> Vec<u8> {
+        STANDARD.decode(&self.body).unwrap_or_default()
+    }
+}
+
+/// The operations waiting to be sent, oldest first
+#[derive(Default)]
+pub(crate) struct OperationQueue {
+    entries: Vec<Entry>,
+    path: Option<PathBuf>,
+}
+
+fn file_error(action: &str, path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::new(
+        Status::GenericFailure,
+        format!("Couldn't {} {}: {}", action, path.display(), message),
+    )
+}
+
+impl OperationQueue {
+    /// Whether changes are queued while offline, which needs a file to keep
+    /// them in
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn operations(&self) -> Vec<QueuedOperation> {
+        self.entries.iter().map(|e| e.operation.clone()).collect()
+    }
+
+    pub fn front(&self) -> Option<&Entry> {
+        self.entries.first()
+    }
+
+    pub fn push(&mut self, entry: Entry) -> Result<()> {
+        self.entries.push(entry);
+        // A change that couldn't be kept isn't queued, so it can't be sent
+        // later after the caller was told it failed
+        self.save().inspect_err(|_| {
+            self.entries.pop();
+        })
+    }
+
+    /// Remove the oldest operation, once it's been sent or rejected
+    pub fn pop(&mut self) -> Result<Option<Entry>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        let entry = self.entries.remove(0);
+        self.save()?;
+        Ok(Some(entry))
+    }
+
+    /// Keep the queue in a file, reading operations already queued there
+    pub fn use_file(&mut self, path: PathBuf) -> Result<()> {
+        match std::fs::read_to_string(&path) {
+            Ok(json) => {
+                self.entries = serde_json::from_str(&json)
+                    .map_err(|e| file_error("read the offline queue from", &path, e))?;
+                self.path = Some(path);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.path = Some(path);
+                self.save()
+            }
+            Err(e) => Err(file_error("read the offline queue from", &path, e)),
+        }
+    }
+
+    /// Stop queueing changes, which needs the queue to be empty so none are
+    /// left behind
+    pub fn disable(&mut self) -> Result<()> {
+        if !self.entries.is_empty() {
+            return Err(Error::new(
+                Status::GenericFailure,
+                format!(
+                    "{} changes are still queued; flush them first",
+                    self.entries.len()
+                ),
+            ));
+        }
+        self.path = None;
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.entries)
+            .map_err(|e| file_error("write the offline queue to", path, e))?;
+        // Written alongside and then moved over the queue, so a crash part
+        // way through can't leave it truncated
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        File::create(&temp)
+            .and_then(|mut file| {
+                file.write_all(json.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|()| std::fs::rename(&temp, path))
+            .map_err(|e| file_error("write the offline queue to", path, e))
+    }
+}
+
+/// How a queued change stands against the list as it is now
+pub(crate) enum Check {
+    /// It can be sent as it is
+    Clean,
+    /// AnyList already has it, from an earlier attempt whose response was lost
+    Applied,
+    Conflict(Box<QueueConflict>),
+}
+
+/// Check a queued change against its list, or `None` if the list is gone
+pub(crate) fn check(operation: &QueuedOperation, list: Option<&List>) -> Check {
+    let conflict = |kind, remote_item: Option<&ListItem>| {
+        Check::Conflict(Box::new(QueueConflict {
+            kind,
+            operation: operation.clone(),
+            remote_item: remote_item.cloned(),
+        }))
+    };
+    let Some(list) = list else {
+        return conflict(ConflictType::ListDeleted, None);
+    };
+    let item_id = operation.item_id.as_deref().unwrap_or_default();
+    let item = list.items.iter().find(|i| i.id == item_id);
+
+    match (operation.change, item) {
+        (QueuedChange::Add, Some(_)) => Check::Applied,
+        (QueuedChange::Add, None) => {
+            let name = normalize_name(operation.name.as_deref().unwrap_or_default());
+            match list.items.iter().find(|i| normalize_name(&i.name) == name) {
+                Some(remote) => conflict(ConflictType::DuplicateItem, Some(remote)),
+                None => Check::Clean,
+            }
+        }
+        (QueuedChange::Check | QueuedChange::Uncheck, Some(item)) => {
+            match operation.name.as_deref() {
+                Some(name) if normalize_name(name) != normalize_name(&item.name) => {
+                    conflict(ConflictType::ItemRenamed, Some(item))
+                }
+                _ => Check::Clean,
+            }
+        }
+        (QueuedChange::Check | QueuedChange::Uncheck, None) => {
+            conflict(ConflictType::ItemDeleted, None)
+        }
+    }
+}
+
+/// Settle a conflict as the resolver says, keeping the remote change if
+/// there isn't one. The outer error is the resolver's; the inner one is
+/// from sending the change.
+pub(crate) async fn settle(
+    client: &RsClient,
+    resolver: Option<Arc<ConflictResolver>>,
+    conflict: QueueConflict,
+    endpoint: &str,
+    body: Vec<u8>,
+) -> Result<std::result::Result<QueueEventType, AnyListError>> {
+    let kind = conflict.kind;
+    let list_id = conflict.operation.list_id.clone();
+    let remote_item = conflict.remote_item.clone();
+    let resolution = match resolver {
+        Some(resolver) => resolver.call_async(conflict).await?,
+        None => ConflictResolution::KeepRemote,
+    };
+
+    let sent = match (resolution, kind, remote_item) {
+        // A renamed item is still the same item, so merging sends the change
+        (ConflictResolution::KeepLocal, _, _)
+        | (ConflictResolution::Merge, ConflictType::ItemRenamed, _) => {
+            transport::post(client, endpoint, body).await
+        }
+        // A duplicate item is merged by putting the one already on the list
+        // back if it was crossed off
+        (ConflictResolution::Merge, ConflictType::DuplicateItem, Some(remote))
+            if remote.checked =>
+        {
+            let uncheck = PbListOperationList {
+                operations: vec![operations::set_item_checked(
+                    client, &list_id, &remote.id, false,
+                )],
+            };
+            transport::post_message(client, endpoint, &uncheck).await
+        }
+        (ConflictResolution::Merge, ConflictType::DuplicateItem, Some(_)) => Ok(Vec::new()),
+        _ => return Ok(Ok(QueueEventType::Discarded)),
+    };
+    Ok(sent.map(|_| QueueEventType::Flushed))
+}
@@ -0,0 +1,82 @@
+//! Refreshing cached lists and recipes in the background.
+//!
+//! Each type is fetched straight away and then on its own interval, so the
+//! cache is warm before the first read. A fetch that fails is reported and
+//! tried again at the next interval.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anylist_rs::AnyListClient as RsClient;
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
+use napi::Result;
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    cache, local_day_in, recipes_from, status, to_napi_error, CacheEntity, List, RefreshEvent,
+    RefreshEventCallback, RefreshEventType,
+};
+
+pub(crate) struct Refresher {
+    pub client: Arc<RsClient>,
+    pub cache: Arc<Mutex<cache::Cache>>,
+    pub sync_state: Arc<Mutex<status::SyncState>>,
+    pub time_zone: Arc<Mutex<Option<chrono_tz::Tz>>>,
+    pub callback: Option<RefreshEventCallback>,
+}
+
+impl Refresher {
+    async fn refresh(&self, entity: CacheEntity) -> Result<()> {
+        let generation = self.cache.lock().unwrap().generation(entity);
+        match entity {
+            CacheEntity::Lists => {
+                let lists = self.client.get_lists().await.map_err(to_napi_error)?;
+                let lists: Vec<List> = lists.iter().map(List::from).collect();
+                self.cache.lock().unwrap().put_lists(generation, &lists);
+            }
+            CacheEntity::Recipes => {
+                let data = self.client.get_user_data().await.map_err(to_napi_error)?;
+                let time_zone = *self.time_zone.lock().unwrap();
+                let today = local_day_in(time_zone, chrono::Utc::now())
+                    .format("%Y-%m-%d")
+                    .to_string();
+                let recipes = recipes_from(&data, &today);
+                self.cache.lock().unwrap().put_recipes(generation, &recipes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Refresh one type every `interval`, until the task is aborted
+    pub async fn run(self: Arc<Self>, entity: CacheEntity, interval: Duration) {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            let result = self.refresh(entity).await;
+
+            let mut sync_state = self.sync_state.lock().unwrap();
+            let (kind, error) = match result {
+                Ok(()) => {
+                    sync_state.fetched(entity);
+                    (RefreshEventType::Refreshed, None)
+                }
+                Err(e) => {
+                    sync_state.failed("startAutoRefresh", &e);
+                    (RefreshEventType::Failed, Some(e.reason.clone()))
+                }
+            };
+            drop(sync_state);
+            if let Some(callback) = &self.callback {
+                callback.call(
+                    RefreshEvent {
+                        kind,
+                        entity,
+                        error,
+                    },
+                    ThreadsafeFunctionCallMode::NonBlocking,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Rules that pick a category for an item from its name.
+//!
+//! Names are compared ignoring case and surrounding whitespace. Exact rules
+//! are tried first, then the longest matching prefix, then regular
+//! expressions in the order they were added. When the rules have a file, it
+//! is rewritten after every change so they survive restarts.
+
+use std::path::{Path, PathBuf};
+
+use napi::{Error, Result, Status};
+use regex::{Regex, RegexBuilder};
+
+use crate::{CategoryRule, CategoryRuleMatch};
+
+struct CompiledRule {
+    rule: CategoryRule,
+    regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    fn new(rule: CategoryRule) -> Result<Self> {
+        let regex = match rule.match_type {
+            CategoryRuleMatch::Regex => Some(
+                RegexBuilder::new(&rule.pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| {
+                        Error::new(
+                            Status::InvalidArg,
+                            format!("Invalid regex {:?}: {}", rule.pattern, e),
+                        )
+                    })?,
+            ),
+            _ => None,
+        };
+        Ok(CompiledRule { rule, regex })
+    }
+
+    fn applies_to(&self, list_id: Option<&str>) -> bool {
+        match (&self.rule.list_id, list_id) {
+            (None, _) => true,
+            (Some(rule_list), Some(list_id)) => rule_list == list_id,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// The category rules a client applies to new items
+#[derive(Default)]
+pub(crate) struct RuleSet {
+    rules: Vec<CompiledRule>,
+    path: Option<PathBuf>,
+}
+
+fn file_error(action: &str, path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::new(
+        Status::GenericFailure,
+        format!("Couldn't {} {}: {}", action, path.display(), message),
+    )
+}
+
+impl RuleSet {
+    pub fn rules(&self) -> Vec<CategoryRule> {
+        self.rules.iter().map(|r| r.rule.clone()).collect()
+    }
+
+    pub fn add(&mut self, rule: CategoryRule) -> Result<()> {
+        self.rules.push(CompiledRule::new(rule)?);
+        self.save()
+    }
+
+    /// Remove a rule, returning whether there was one with that ID
+    pub fn remove(&mut self, id: &str) -> Result<bool> {
+        let before = self.rules.len();
+        self.rules.retain(|r| r.rule.id != id);
+        if self.rules.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Keep the rules in a file, reading them from it if it exists and
+    /// writing the current rules to it otherwise
+    pub fn use_file(&mut self, path: PathBuf) -> Result<()> {
+        match std::fs::read_to_string(&path) {
+            Ok(json) => {
+                let rules: Vec<CategoryRule> = serde_json::from_str(&json)
+                    .map_err(|e| file_error("read category rules from", &path, e))?;
+                self.rules = rules
+                    .into_iter()
+                    .map(CompiledRule::new)
+                    .collect::<Result<_>>()?;
+                self.path = Some(path);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.path = Some(path);
+                self.save()
+            }
+            Err(e) => Err(file_error("read category rules from", &path, e)),
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.rules())
+            .map_err(|e| file_error("write category rules to", path, e))?;
+        std::fs::write(path, json).map_err(|e| file_error("write category rules to", path, e))
+    }
+
+    /// The category for an item name, if any rule matches it
+    pub fn category_for(&self, name: &str, list_id: Option<&str>) -> Option<String> {
+        let name = name.trim();
+        let lower = name.to_lowercase();
+        let rules = || self.rules.iter().filter(|r| r.applies_to(list_id));
+        let pattern = |r: &CompiledRule| r.rule.pattern.trim().to_lowercase();
+
+        let exact = || {
+            rules().find(|r| r.rule.match_type == CategoryRuleMatch::Exact && pattern(r) == lower)
+        };
+        // The longest prefix wins, and the earliest of equally long ones
+        let prefix = || {
+            let mut best: Option<(&CompiledRule, usize)> = None;
+            for r in rules().filter(|r| r.rule.match_type == CategoryRuleMatch::Prefix) {
+                let prefix = pattern(r);
+                if lower.starts_with(&prefix) && best.is_none_or(|(_, len)| prefix.len() > len) {
+                    best = Some((r, prefix.len()));
+                }
+            }
+            best.map(|(r, _)| r)
+        };
+        let regex = || rules().find(|r| r.regex.as_ref().is_some_and(|re| re.is_match(name)));
+
+        exact()
+            .or_else(prefix)
+            .or_else(regex)
+            .map(|r| r.rule.category.clone())
+    }
+}
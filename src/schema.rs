@@ -0,0 +1,118 @@
+//! JSON Schemas for the object types exported by the binding.
+//!
+//! Schemas are derived from the Rust definitions with `schemars`, so they
+//! always describe exactly what the binding produces (camelCase fields,
+//! optional fields omitted or `null`).
+
+use std::collections::BTreeMap;
+
+use napi_derive::napi;
+use schemars::{schema_for, JsonSchema};
+use serde_json::Value;
+
+use crate::*;
+
+fn schema_of<T: JsonSchema>() -> Value {
+    schema_for!(T).to_value()
+}
+
+macro_rules! schemas {
+    ($($ty:ident),* $(,)?) => {
+        BTreeMap::from([$((stringify!($ty).to_string(), schema_of::<$ty>())),*])
+    };
+}
+
+/// Get JSON Schemas for all exported types, keyed by type name
+#[napi]
+pub fn get_schemas() -> BTreeMap<String, Value> {
+    schemas![
+        AccountChanges,
+        AddAllFavouritesOptions,
+        AutoRefreshOptions,
+        CacheOptions,
+        Category,
+        CategoryGroup,
+        CategoryRule,
+        CategoryRuleInput,
+        ChangeEvent,
+        CreateRecipeOptions,
+        DayNutrition,
+        DriftEvent,
+        ExportAllRecipesOptions,
+        FavouriteInput,
+        FavouriteItem,
+        FavouritePatch,
+        FavouriteStats,
+        FavouritesList,
+        GetListOptions,
+        ICalendarEvent,
+        ICalendarInfo,
+        Ingredient,
+        IngredientInput,
+        ItemCategoryChange,
+        ItemPatch,
+        ItemsByStoreOptions,
+        JournalEntry,
+        JournalOptions,
+        LabelFrequency,
+        List,
+        ListChanges,
+        ListItem,
+        ListItemsOptions,
+        ListItemsPage,
+        ListNotificationSettings,
+        ListNotificationSettingsPatch,
+        ListSettings,
+        ListSettingsPatch,
+        MealPlanCalendar,
+        MealPlanEvent,
+        MealPlanEventInput,
+        MealPlanIngredientsOptions,
+        MealPlanLabel,
+        MealPlanNutrition,
+        MealPlanStats,
+        MealPlanTemplate,
+        MealPlanTemplateEvent,
+        NotificationLocation,
+        NutritionTotals,
+        OperationReceipt,
+        OptimisticEvent,
+        QueueConflict,
+        QueuedOperation,
+        QueueEvent,
+        RawListItem,
+        RawListOperation,
+        RecentRecipesOptions,
+        Recipe,
+        RecipeChanges,
+        RecipeCollection,
+        RecipeFilter,
+        RecipeFrequency,
+        RecipeImportFailure,
+        RecipeImportResult,
+        RecipePatch,
+        RecipeSelection,
+        RecipeStep,
+        RecipeStepInput,
+        RecipeTimer,
+        RecipeVersion,
+        RefreshEvent,
+        RenderRecipeHtmlOptions,
+        RestoredEntity,
+        RestoreOptions,
+        RestoreResult,
+        SavedTokens,
+        SearchMealPlanOptions,
+        SearchRecipesOptions,
+        SnapshotDiff,
+        Store,
+        StoreFilter,
+        SubscribeOptions,
+        SuggestMealPlanOptions,
+        SyncError,
+        SyncStatus,
+        UpsertItemResult,
+        WebhookFailure,
+        WebhookOptions,
+    ]
+}
@@ -0,0 +1,546 @@
+//! Reading recipes from schema.org markup.
+//!
+//! Recipe sites describe their recipes with a schema.org `Recipe`, either as
+//! JSON-LD in a `<script type="application/ld+json">` tag or as microdata
+//! attributes on the page. Both are read into the JSON-LD shape, which is then
+//! mapped onto `CreateRecipeOptions`.
+
+use std::sync::OnceLock;
+
+use napi::{Error, Result, Status};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::quantity::parse_amount;
+use crate::{CreateRecipeOptions, IngredientInput, RecipeTime};
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+/// Find the schema.org Recipe on an HTML page
+pub(crate) fn find_recipe(html: &str) -> Option<Value> {
+    json_ld_recipe(html).or_else(|| microdata_recipe(html))
+}
+
+fn json_ld_recipe(html: &str) -> Option<Value> {
+    static SCRIPT: OnceLock<Regex> = OnceLock::new();
+    let script = regex(
+        &SCRIPT,
+        r#"(?is)<script[^>]*type\s*=\s*["']application/ld\+json["'][^>]*>(.*?)</script>"#,
+    );
+
+    script.captures_iter(html).find_map(|captures| {
+        let json: Value = serde_json::from_str(captures[1].trim()).ok()?;
+        find_recipe_node(&json)
+    })
+}
+
+/// Search a JSON-LD document (object, array, or `@graph`) for a Recipe node
+fn find_recipe_node(json: &Value) -> Option<Value> {
+    match json {
+        Value::Array(nodes) => nodes.iter().find_map(find_recipe_node),
+        Value::Object(node) => {
+            if is_recipe(node) {
+                return Some(json.clone());
+            }
+            node.get("@graph")
+                .or_else(|| node.get("mainEntity"))
+                .and_then(find_recipe_node)
+        }
+        _ => None,
+    }
+}
+
+fn is_recipe(node: &Map<String, Value>) -> bool {
+    match node.get("@type") {
+        Some(Value::String(t)) => t == "Recipe",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "Recipe"),
+        _ => false,
+    }
+}
+
+/// Properties that hold a list of values rather than a single value
+const LIST_PROPERTIES: &[&str] = &["recipeIngredient", "ingredients", "recipeInstructions"];
+
+fn microdata_recipe(html: &str) -> Option<Value> {
+    static RECIPE: OnceLock<Regex> = OnceLock::new();
+    static PROPERTY: OnceLock<Regex> = OnceLock::new();
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let recipe = regex(
+        &RECIPE,
+        r#"(?i)itemtype\s*=\s*["']https?://schema\.org/Recipe["']"#,
+    );
+    let property = regex(
+        &PROPERTY,
+        r#"(?is)<(\w+)([^>]*?\bitemprop\s*=\s*["']([^"']+)["'][^>]*)>"#,
+    );
+    let attribute = regex(
+        &ATTRIBUTE,
+        r#"(?is)\b(content|href|src|datetime)\s*=\s*["']([^"']*)["']"#,
+    );
+
+    let start = recipe.find(html)?.end();
+    let html = &html[start..];
+    let mut node = Map::new();
+    node.insert("@type".to_string(), Value::from("Recipe"));
+
+    for captures in property.captures_iter(html) {
+        let tag = captures[1].to_ascii_lowercase();
+        let attributes = &captures[2];
+        let name = &captures[3];
+
+        let value = match attribute.captures(attributes) {
+            Some(attr) => attr[2].to_string(),
+            None => {
+                let rest = &html[captures.get(0).map_or(0, |m| m.end())..];
+                let end = rest
+                    .to_ascii_lowercase()
+                    .find(&format!("</{}", tag))
+                    .unwrap_or(rest.len());
+                rest[..end].to_string()
+            }
+        };
+        if LIST_PROPERTIES.contains(&name) {
+            if let Value::Array(values) = node
+                .entry(name.to_string())
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                values.extend(clean_lines(&value).into_iter().map(Value::from));
+            }
+        } else {
+            let value = clean_text(&value);
+            if !value.is_empty() {
+                node.entry(name.to_string())
+                    .or_insert_with(|| Value::from(value));
+            }
+        }
+    }
+
+    node.contains_key("name").then_some(Value::Object(node))
+}
+
+/// Map a schema.org Recipe onto the options for creating a recipe
+pub(crate) fn create_recipe_options(recipe: &Value) -> Result<CreateRecipeOptions> {
+    let name = text(recipe.get("name"))
+        .ok_or_else(|| Error::new(Status::GenericFailure, "Recipe has no name"))?;
+
+    let ingredients = strings(
+        recipe
+            .get("recipeIngredient")
+            .or_else(|| recipe.get("ingredients")),
+    )
+    .iter()
+    .map(|line| parse_ingredient(line))
+    .collect();
+
+    let mut preparation_steps = Vec::new();
+    if let Some(instructions) = recipe.get("recipeInstructions") {
+        collect_steps(instructions, &mut preparation_steps);
+    }
+
+    let prep_time = recipe
+        .get("prepTime")
+        .and_then(Value::as_str)
+        .and_then(parse_minutes);
+    let cook_time = recipe
+        .get("cookTime")
+        .and_then(Value::as_str)
+        .and_then(parse_minutes)
+        .or_else(|| {
+            let total = parse_minutes(recipe.get("totalTime")?.as_str()?)?;
+            Some(total - prep_time.unwrap_or(0)).filter(|minutes| *minutes > 0)
+        });
+
+    Ok(CreateRecipeOptions {
+        name,
+        ingredients,
+        preparation_steps,
+        steps: None,
+        note: text(recipe.get("description")),
+        source_name: recipe
+            .get("publisher")
+            .and_then(|p| text(p.get("name")).or_else(|| text(Some(p)))),
+        source_url: text(recipe.get("url")),
+        servings: servings(recipe.get("recipeYield")),
+        prep_time: prep_time.map(RecipeTime::from),
+        cook_time: cook_time.map(RecipeTime::from),
+        rating: None,
+        nutritional_info: recipe.get("nutrition").and_then(nutrition),
+        photo_id: None,
+        photo_url: None,
+    })
+}
+
+/// The URL of a schema.org Recipe's main image
+pub(crate) fn image_url(recipe: &Value) -> Option<String> {
+    fn url(image: &Value) -> Option<String> {
+        match image {
+            Value::String(url) => Some(url.clone()),
+            Value::Array(images) => images.iter().find_map(url),
+            Value::Object(image) => image
+                .get("url")
+                .or_else(|| image.get("contentUrl"))
+                .and_then(url),
+            _ => None,
+        }
+    }
+
+    recipe.get("image").and_then(url).filter(|u| !u.is_empty())
+}
+
+/// Read a value that may be a single string or a list of strings
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(values)) => values.iter().filter_map(|v| text(Some(v))).collect(),
+        Some(Value::String(s)) => clean_lines(s),
+        value => text(value).into_iter().collect(),
+    }
+}
+
+/// Flatten recipeInstructions, which may be text, HowToSteps, or HowToSections
+fn collect_steps(instructions: &Value, steps: &mut Vec<String>) {
+    match instructions {
+        Value::String(text) => steps.extend(clean_lines(text)),
+        Value::Array(items) => items.iter().for_each(|item| collect_steps(item, steps)),
+        Value::Object(item) => {
+            if let Some(elements) = item.get("itemListElement") {
+                collect_steps(elements, steps);
+            } else if let Some(step) = text(item.get("text").or_else(|| item.get("name"))) {
+                steps.push(step);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn servings(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Number(n) => Some(format!("{} servings", n)),
+        Value::Array(values) => values
+            .iter()
+            .filter_map(|v| servings(Some(v)))
+            .max_by_key(|s| s.len()),
+        value => text(Some(value)),
+    }
+}
+
+/// Format a schema.org NutritionInformation object as "Name: value" lines
+pub(crate) fn nutrition(value: &Value) -> Option<String> {
+    let lines: Vec<String> = value
+        .as_object()?
+        .iter()
+        .filter(|(key, _)| !key.starts_with('@'))
+        .filter_map(|(key, value)| Some(format!("{}: {}", nutrient_name(key), text(Some(value))?)))
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Turn a property like `saturatedFatContent` into "Saturated fat"
+fn nutrient_name(key: &str) -> String {
+    let key = key.strip_suffix("Content").unwrap_or(key);
+    let mut name = String::new();
+    for (i, c) in key.chars().enumerate() {
+        if i == 0 {
+            name.extend(c.to_uppercase());
+        } else if c.is_uppercase() {
+            name.push(' ');
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Read a string or number as cleaned-up text
+fn text(value: Option<&Value>) -> Option<String> {
+    let text = match value? {
+        Value::String(s) => clean_text(s),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+/// Strip tags, decode entities, and collapse whitespace
+fn clean_text(text: &str) -> String {
+    clean_lines(text).join(" ").trim().to_string()
+}
+
+/// Like `clean_text`, but keeping line and paragraph breaks as separate lines
+fn clean_lines(text: &str) -> Vec<String> {
+    static BREAK: OnceLock<Regex> = OnceLock::new();
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    let line_break = regex(&BREAK, r"(?i)<br\s*/?>|</p>|</li>");
+    let tag = regex(&TAG, r"<[^>]*>");
+
+    let text = line_break.replace_all(text, "\n");
+    let text = tag.replace_all(&text, "");
+
+    decode_entities(&text)
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    let entity = regex(&ENTITY, r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);");
+
+    entity
+        .replace_all(text, |captures: &regex::Captures| {
+            let name = &captures[1];
+            let decoded = if let Some(hex) = name.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "frac12" => Some('½'),
+                    "frac14" => Some('¼'),
+                    "frac34" => Some('¾'),
+                    "deg" => Some('°'),
+                    _ => None,
+                }
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Parse an ISO 8601 duration such as `PT1H30M` into whole minutes
+fn parse_duration(duration: &str) -> Option<i32> {
+    let duration = duration.trim();
+    if let Ok(minutes) = duration.parse::<u32>() {
+        return i32::try_from(minutes).ok();
+    }
+
+    let rest = duration.strip_prefix(['P', 'p'])?;
+    let mut minutes = 0.0;
+    let mut number = String::new();
+    let mut in_time = false;
+    let mut matched = false;
+
+    for c in rest.chars() {
+        match c.to_ascii_uppercase() {
+            'T' => in_time = true,
+            c if c.is_ascii_digit() || c == '.' || c == ',' => {
+                number.push(if c == ',' { '.' } else { c })
+            }
+            unit => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                minutes += value
+                    * match (unit, in_time) {
+                        ('W', false) => 7.0 * 24.0 * 60.0,
+                        ('D', false) => 24.0 * 60.0,
+                        ('H', true) => 60.0,
+                        ('M', true) => 1.0,
+                        ('S', true) => 1.0 / 60.0,
+                        _ => return None,
+                    };
+                matched = true;
+            }
+        }
+    }
+
+    // "P" or "PT" on its own isn't a duration
+    (matched && number.is_empty()).then_some(minutes.round() as i32)
+}
+
+/// Parse a free-text duration like "1 hr 30 mins" or "45 minutes" into minutes
+pub(crate) fn parse_minutes(text: &str) -> Option<i32> {
+    let text = text.trim().to_lowercase();
+    if let Some(minutes) = parse_duration(&text) {
+        return Some(minutes);
+    }
+
+    let mut minutes = 0.0;
+    let mut number: Option<f64> = None;
+    let mut matched = false;
+
+    for token in text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+    {
+        // Split tokens like "30min" into the number and the unit
+        let split = token
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(token.len());
+        let (value, unit) = token.split_at(split);
+
+        if !value.is_empty() {
+            number = Some(value.parse().ok()?);
+        }
+        if unit.is_empty() {
+            continue;
+        }
+
+        let factor = if unit.starts_with('h') {
+            60.0
+        } else if unit.starts_with('m') {
+            1.0
+        } else if unit.starts_with('d') {
+            24.0 * 60.0
+        } else if unit == "and" {
+            continue;
+        } else {
+            return None;
+        };
+        minutes += number.take()? * factor;
+        matched = true;
+    }
+
+    // A bare number is taken to be minutes
+    if let Some(rest) = number {
+        minutes += rest;
+        matched = true;
+    }
+
+    matched.then_some(minutes.round() as i32)
+}
+
+/// Units recognised after an ingredient's amount
+const UNITS: &[&str] = &[
+    "c",
+    "can",
+    "cans",
+    "clove",
+    "cloves",
+    "cup",
+    "cups",
+    "dash",
+    "g",
+    "gal",
+    "gallon",
+    "gallons",
+    "gram",
+    "grams",
+    "handful",
+    "kg",
+    "kilogram",
+    "kilograms",
+    "l",
+    "lb",
+    "lbs",
+    "liter",
+    "liters",
+    "litre",
+    "litres",
+    "ml",
+    "oz",
+    "ounce",
+    "ounces",
+    "package",
+    "packages",
+    "pinch",
+    "pint",
+    "pints",
+    "pkg",
+    "pound",
+    "pounds",
+    "qt",
+    "quart",
+    "quarts",
+    "slice",
+    "slices",
+    "stick",
+    "sticks",
+    "t",
+    "tbs",
+    "tbsp",
+    "tablespoon",
+    "tablespoons",
+    "tsp",
+    "teaspoon",
+    "teaspoons",
+];
+
+fn is_amount(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .any(|c| c.is_ascii_digit() || is_vulgar_fraction(c))
+        && token
+            .chars()
+            .all(|c| c.is_ascii_digit() || is_vulgar_fraction(c) || "./,-–".contains(c))
+}
+
+fn is_vulgar_fraction(c: char) -> bool {
+    ('\u{00BC}'..='\u{00BE}').contains(&c) || ('\u{2150}'..='\u{215E}').contains(&c)
+}
+
+/// Split an ingredient line like "2 cups flour, sifted" into its parts
+pub(crate) fn parse_ingredient(line: &str) -> IngredientInput {
+    let line = clean_text(line);
+
+    // "For the sauce:" starts a new section
+    if let Some(header) = line
+        .strip_suffix(':')
+        .filter(|header| !header.is_empty() && parse_amount(header).is_none())
+    {
+        return IngredientInput {
+            name: header.trim().to_string(),
+            quantity: None,
+            note: None,
+            is_header: Some(true),
+        };
+    }
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    let mut amount_end = 0;
+    while amount_end < tokens.len() {
+        let token = tokens[amount_end];
+        let is_range_word = matches!(token, "to" | "-" | "–")
+            && amount_end > 0
+            && tokens.get(amount_end + 1).is_some_and(|t| is_amount(t));
+        if is_amount(token) || is_range_word {
+            amount_end += 1;
+        } else {
+            break;
+        }
+    }
+
+    let mut quantity_end = amount_end;
+    if amount_end > 0 {
+        if let Some(unit) = tokens.get(amount_end) {
+            let unit = unit.trim_end_matches('.').to_lowercase();
+            if UNITS.contains(&unit.as_str()) && amount_end + 1 < tokens.len() {
+                quantity_end += 1;
+            }
+        }
+    }
+
+    let quantity = (quantity_end > 0).then(|| tokens[..quantity_end].join(" "));
+    let rest = tokens[quantity_end..].join(" ");
+    let rest = rest.strip_prefix("of ").unwrap_or(&rest);
+    let (name, note) = match rest.split_once(", ") {
+        Some((name, note)) => (name.trim(), Some(note.trim().to_string())),
+        None => (rest.trim(), None),
+    };
+
+    if name.is_empty() {
+        return IngredientInput {
+            name: line,
+            quantity: None,
+            note: None,
+            is_header: None,
+        };
+    }
+
+    IngredientInput {
+        name: name.to_string(),
+        quantity,
+        note: note.filter(|n| !n.is_empty()),
+        is_header: None,
+    }
+}
@@ -0,0 +1,243 @@
+//! Reading and comparing account snapshots.
+//!
+//! A snapshot is what `exportSnapshot()` writes: the lists, and every recipe
+//! in the same form as an `exportAllRecipes()` backup. A recipe backup is read
+//! as a snapshot without lists. Fields this version doesn't know are ignored,
+//! so snapshots written by later versions can still be read if their format
+//! version hasn't changed.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use napi::{Error, Result, Status};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::changes::diff_list;
+use crate::export::BACKUP_VERSION;
+use crate::{CreateRecipeOptions, IngredientInput, List, RecipeChanges, RecipeTime, SnapshotDiff};
+
+/// Recipe fields worked out from others, or that change without the recipe
+/// being edited, which aren't reported as changes
+const DERIVED_RECIPE_FIELDS: [&str; 8] = [
+    "photo",
+    "photoError",
+    "photoUrls",
+    "steps",
+    "timers",
+    "totalTime",
+    "updatedAt",
+    "lastCookedAt",
+];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotIngredient {
+    name: String,
+    quantity: Option<String>,
+    note: Option<String>,
+    is_header: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotPhoto {
+    data: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotRecipe {
+    id: String,
+    name: String,
+    #[serde(default)]
+    ingredients: Vec<SnapshotIngredient>,
+    #[serde(default)]
+    preparation_steps: Vec<String>,
+    note: Option<String>,
+    source_name: Option<String>,
+    source_url: Option<String>,
+    servings: Option<String>,
+    prep_time: Option<i32>,
+    cook_time: Option<i32>,
+    rating: Option<i32>,
+    nutritional_info: Option<String>,
+    photo_id: Option<String>,
+    photo: Option<SnapshotPhoto>,
+}
+
+#[derive(Deserialize)]
+struct SnapshotFile {
+    version: u32,
+    #[serde(default)]
+    lists: Vec<List>,
+    #[serde(default)]
+    recipes: Vec<Value>,
+}
+
+/// A recipe read from a snapshot
+pub(crate) struct Recipe {
+    /// The recipe's ID when the snapshot was taken
+    pub id: String,
+    pub options: CreateRecipeOptions,
+    /// The photo embedded in the snapshot, which takes the place of
+    /// `options.photo_id` when there is one
+    pub photo: Option<Vec<u8>>,
+}
+
+/// A snapshot's lists and recipes
+pub(crate) struct Snapshot {
+    pub lists: Vec<List>,
+    pub recipes: Vec<Recipe>,
+}
+
+fn invalid(message: impl std::fmt::Display) -> Error {
+    Error::new(Status::InvalidArg, format!("Invalid snapshot: {}", message))
+}
+
+fn parse(json: &str) -> Result<SnapshotFile> {
+    let file: SnapshotFile = serde_json::from_str(json).map_err(invalid)?;
+    if file.version > BACKUP_VERSION {
+        return Err(invalid(format!(
+            "version {} is newer than this library supports ({})",
+            file.version, BACKUP_VERSION
+        )));
+    }
+    Ok(file)
+}
+
+/// Read a snapshot or recipe backup
+pub(crate) fn read(json: &str) -> Result<Snapshot> {
+    let file = parse(json)?;
+
+    let recipes = file
+        .recipes
+        .into_iter()
+        .map(|recipe| {
+            let recipe: SnapshotRecipe = serde_json::from_value(recipe).map_err(invalid)?;
+            let photo = match recipe.photo {
+                Some(photo) => Some(STANDARD.decode(photo.data).map_err(invalid)?),
+                None => None,
+            };
+            Ok(Recipe {
+                id: recipe.id,
+                options: CreateRecipeOptions {
+                    name: recipe.name,
+                    ingredients: recipe
+                        .ingredients
+                        .into_iter()
+                        .map(|i| IngredientInput {
+                            name: i.name,
+                            quantity: i.quantity,
+                            note: i.note,
+                            is_header: i.is_header,
+                        })
+                        .collect(),
+                    preparation_steps: recipe.preparation_steps,
+                    steps: None,
+                    note: recipe.note,
+                    source_name: recipe.source_name,
+                    source_url: recipe.source_url,
+                    servings: recipe.servings,
+                    prep_time: recipe.prep_time.map(RecipeTime::Minutes),
+                    cook_time: recipe.cook_time.map(RecipeTime::Minutes),
+                    rating: recipe.rating,
+                    nutritional_info: recipe.nutritional_info,
+                    photo_id: recipe.photo_id,
+                    photo_url: None,
+                },
+                photo,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Snapshot {
+        lists: file.lists,
+        recipes,
+    })
+}
+
+fn recipe_field<'a>(recipe: &'a Value, field: &str) -> &'a str {
+    recipe
+        .get(field)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+fn recipe_changes(recipe: &Value) -> RecipeChanges {
+    RecipeChanges {
+        recipe_id: recipe_field(recipe, "id").to_string(),
+        name: recipe_field(recipe, "name").to_string(),
+        old_name: None,
+        changed_fields: Vec::new(),
+    }
+}
+
+/// The fields that differ between two versions of a recipe, sorted
+fn changed_fields(before: &Value, after: &Value) -> Vec<String> {
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        return Vec::new();
+    };
+    let mut fields: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|field| !DERIVED_RECIPE_FIELDS.contains(&field.as_str()))
+        .filter(|field| before.get(*field) != after.get(*field))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// What changed from snapshot `a` to snapshot `b`, matching lists, items,
+/// and recipes by ID
+pub(crate) fn diff(a: &str, b: &str) -> Result<SnapshotDiff> {
+    let (a, b) = (parse(a)?, parse(b)?);
+    let id = |recipe: &Value| recipe_field(recipe, "id").to_string();
+    let mut diff = SnapshotDiff {
+        lists_added: Vec::new(),
+        lists_removed: Vec::new(),
+        lists_changed: Vec::new(),
+        recipes_added: Vec::new(),
+        recipes_removed: Vec::new(),
+        recipes_changed: Vec::new(),
+    };
+
+    for list in &b.lists {
+        match a.lists.iter().find(|l| l.id == list.id) {
+            Some(before) => diff.lists_changed.extend(diff_list(before, list)),
+            None => diff.lists_added.push(list.clone()),
+        }
+    }
+    diff.lists_removed = a
+        .lists
+        .iter()
+        .filter(|list| !b.lists.iter().any(|l| l.id == list.id))
+        .cloned()
+        .collect();
+
+    for recipe in &b.recipes {
+        let Some(before) = a.recipes.iter().find(|r| id(r) == id(recipe)) else {
+            diff.recipes_added.push(recipe_changes(recipe));
+            continue;
+        };
+        let fields = changed_fields(before, recipe);
+        if fields.is_empty() {
+            continue;
+        }
+        let old_name = recipe_field(before, "name");
+        diff.recipes_changed.push(RecipeChanges {
+            old_name: (old_name != recipe_field(recipe, "name")).then(|| old_name.to_string()),
+            changed_fields: fields,
+            ..recipe_changes(recipe)
+        });
+    }
+    diff.recipes_removed = a
+        .recipes
+        .iter()
+        .filter(|recipe| !b.recipes.iter().any(|r| id(r) == id(recipe)))
+        .map(recipe_changes)
+        .collect();
+
+    Ok(diff)
+}
@@ -0,0 +1,59 @@
+//! How well the client is keeping up with AnyList, for `getSyncStatus()`.
+//!
+//! Fetches, failures, and subscriptions report here as they happen. Only
+//! reads that actually reach AnyList count as fetches; cached results don't.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+
+use crate::{transport, CacheEntity, SyncError, SyncStatus};
+
+#[derive(Default)]
+pub(crate) struct SyncState {
+    lists_fetched_at: Option<f64>,
+    recipes_fetched_at: Option<f64>,
+    last_error: Option<SyncError>,
+    /// Each subscription's realtime flag, for as long as it's running
+    realtime: Vec<Weak<AtomicBool>>,
+}
+
+fn now_ms() -> f64 {
+    transport::current_timestamp() * 1000.0
+}
+
+impl SyncState {
+    pub fn fetched(&mut self, entity: CacheEntity) {
+        let at = Some(now_ms());
+        match entity {
+            CacheEntity::Lists => self.lists_fetched_at = at,
+            CacheEntity::Recipes => self.recipes_fetched_at = at,
+        }
+    }
+
+    pub fn failed(&mut self, operation: &str, error: &napi::Error) {
+        self.last_error = Some(SyncError {
+            operation: operation.to_string(),
+            message: error.reason.clone(),
+            at: now_ms(),
+        });
+    }
+
+    pub fn watch(&mut self, realtime: &Arc<AtomicBool>) {
+        self.realtime.retain(|flag| flag.strong_count() > 0);
+        self.realtime.push(Arc::downgrade(realtime));
+    }
+
+    pub fn status(&self, queued_operations: u32) -> SyncStatus {
+        SyncStatus {
+            lists_fetched_at: self.lists_fetched_at,
+            recipes_fetched_at: self.recipes_fetched_at,
+            queued_operations,
+            last_error: self.last_error.clone(),
+            realtime_connected: self
+                .realtime
+                .iter()
+                .filter_map(Weak::upgrade)
+                .any(|flag| flag.load(Ordering::Relaxed)),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Finding cooking timers in recipe steps.
+//!
+//! Steps like "Bake for 25-30 minutes" or "Simmer 1 hour 15 minutes" mention
+//! how long something takes; each mention becomes a timer. Ranges use their
+//! lower bound so the cook checks early rather than late.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::RecipeTimer;
+
+fn duration_regex() -> &'static Regex {
+    static DURATION: OnceLock<Regex> = OnceLock::new();
+    DURATION.get_or_init(|| {
+        Regex::new(
+            r"(?i)\b(\d+(?:[.,]\d+)?|an?|one)\s*(?:(?:-|–|to)\s*\d+(?:[.,]\d+)?\s*)?(hours?|hrs?|minutes?|mins?|seconds?|secs?)\b",
+        )
+        .expect("valid regex")
+    })
+}
+
+fn seconds(amount: &str, unit: &str) -> Option<u32> {
+    let amount: f64 = match amount.to_lowercase().as_str() {
+        "a" | "an" | "one" => 1.0,
+        amount => amount.replace(',', ".").parse().ok()?,
+    };
+    let unit_seconds = match unit.to_lowercase().chars().next()? {
+        'h' => 3600.0,
+        'm' => 60.0,
+        _ => 1.0,
+    };
+    Some((amount * unit_seconds).round() as u32)
+}
+
+/// Find the timers mentioned in a recipe's steps
+pub(crate) fn find_timers(steps: &[String]) -> Vec<RecipeTimer> {
+    let mut timers = Vec::new();
+
+    for (step_index, step) in steps.iter().enumerate() {
+        let mut current: Option<(usize, usize, u32)> = None;
+
+        for captures in duration_regex().captures_iter(step) {
+            let whole = captures.get(0).expect("match");
+            let Some(secs) = seconds(&captures[1], &captures[2]) else {
+                continue;
+            };
+
+            // "1 hour 30 minutes" and "1 hour and 30 minutes" are one timer
+            if let Some((start, end, total)) = current {
+                let between = step[end..whole.start()].trim().trim_matches(',');
+                if between.is_empty() || between.eq_ignore_ascii_case("and") {
+                    current = Some((start, whole.end(), total + secs));
+                    continue;
+                }
+                timers.push(timer(step, step_index, start, end, total));
+            }
+            current = Some((whole.start(), whole.end(), secs));
+        }
+
+        if let Some((start, end, total)) = current {
+            timers.push(timer(step, step_index, start, end, total));
+        }
+    }
+
+    timers.retain(|t| t.seconds > 0);
+    timers
+}
+
+fn timer(step: &str, step_index: usize, start: usize, end: usize, seconds: u32) -> RecipeTimer {
+    RecipeTimer {
+        step_index: step_index as u32,
+        seconds,
+        label: step[start..end].to_string(),
+    }
+}
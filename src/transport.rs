@@ -0,0 +1,183 @@
+//! Authenticated requests for AnyList endpoints and operations that
+//! anylist_rs doesn't wrap yet.
+//!
+//! Requests mirror the shape anylist_rs uses: protobuf payloads are posted as
+//! the `operations` multipart field with bearer auth, and a 401 triggers a
+//! single token refresh and retry.
+//!
+//! Pages outside AnyList (e.g. recipe sites) are fetched with the same HTTP
+//! client but without AnyList credentials.
+
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anylist_rs::{AnyListClient as RsClient, AnyListError};
+use prost::Message;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+
+const API_BASE_URL: &str = "https://www.anylist.com";
+const PHOTO_BASE_URL: &str = "https://photos.anylist.com";
+const USER_AGENT: &str = concat!("anylist-napi/", env!("CARGO_PKG_VERSION"));
+
+fn http() -> &'static reqwest::Client {
+    static HTTP: OnceLock<reqwest::Client> = OnceLock::new();
+    HTTP.get_or_init(reqwest::Client::new)
+}
+
+/// The URL a photo uploaded to AnyList is served from
+pub(crate) fn photo_url(photo_id: &str) -> String {
+    format!("{}/{}.jpg", PHOTO_BASE_URL, photo_id)
+}
+
+/// Generate an identifier in the format AnyList uses
+pub(crate) fn generate_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// The current time as AnyList timestamps it, in seconds since the epoch
+pub(crate) fn current_timestamp() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or_default()
+}
+
+fn headers(client: &RsClient) -> Result<HeaderMap, AnyListError> {
+    let tokens = client.export_tokens()?;
+    let invalid = |e: reqwest::header::InvalidHeaderValue| AnyListError::Other(e.to_string());
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", tokens.access_token())).map_err(invalid)?,
+    );
+    headers.insert("X-AnyLeaf-API-Version", HeaderValue::from_static("3"));
+    headers.insert(
+        "X-AnyLeaf-Client-Identifier",
+        HeaderValue::from_str(client.client_identifier()).map_err(invalid)?,
+    );
+
+    Ok(headers)
+}
+
+async fn send(
+    client: &RsClient,
+    method: Method,
+    endpoint: &str,
+    body: Option<Vec<u8>>,
+) -> Result<reqwest::Response, AnyListError> {
+    let mut request = http()
+        .request(
+            method,
+            format!("{}/{}", API_BASE_URL, endpoint.trim_start_matches('/')),
+        )
+        .headers(headers(client)?);
+    if let Some(body) = body {
+        request = request.multipart(
+            reqwest::multipart::Form::new()
+                .part("operations", reqwest::multipart::Part::bytes(body)),
+        );
+    }
+
+    Ok(request.send().await?)
+}
+
+/// Send a request to an AnyList endpoint, with `body` (if any) as the
+/// `operations` field, and return the response body
+pub(crate) async fn request(
+    client: &RsClient,
+    method: Method,
+    endpoint: &str,
+    body: Option<Vec<u8>>,
+) -> Result<Vec<u8>, AnyListError> {
+    let mut response = send(client, method.clone(), endpoint, body.clone()).await?;
+
+    if response.status() == StatusCode::UNAUTHORIZED {
+        client.refresh_tokens().await?;
+        response = send(client, method, endpoint, body).await?;
+    }
+
+    if !response.status().is_success() {
+        return Err(AnyListError::NetworkError(format!(
+            "Request failed with status: {}",
+            response.status()
+        )));
+    }
+
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// POST a raw protobuf payload to an AnyList endpoint
+pub(crate) async fn post(
+    client: &RsClient,
+    endpoint: &str,
+    body: Vec<u8>,
+) -> Result<Vec<u8>, AnyListError> {
+    request(client, Method::POST, endpoint, Some(body)).await
+}
+
+/// Encode a protobuf message and POST it to an AnyList endpoint
+pub(crate) async fn post_message(
+    client: &RsClient,
+    endpoint: &str,
+    message: &impl Message,
+) -> Result<Vec<u8>, AnyListError> {
+    post(client, endpoint, message.encode_to_vec()).await
+}
+
+/// Whether an error means AnyList couldn't be reached or couldn't answer,
+/// so the request can be tried again later, rather than that it was rejected
+pub(crate) fn is_unreachable(error: &AnyListError) -> bool {
+    match error {
+        AnyListError::NetworkError(message) => match message.split_once("with status: ") {
+            Some((_, status)) => status.starts_with('5'),
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+/// POST JSON to a URL outside AnyList
+pub(crate) async fn post_json(
+    url: &str,
+    headers: HeaderMap,
+    body: Vec<u8>,
+) -> Result<(), AnyListError> {
+    let response = http()
+        .post(url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .headers(headers)
+        .body(body)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AnyListError::NetworkError(format!(
+            "Request to {} failed with status: {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(())
+}
+
+/// GET a URL outside AnyList
+pub(crate) async fn fetch(url: &str) -> Result<reqwest::Response, AnyListError> {
+    let response = http()
+        .get(url)
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(AnyListError::NetworkError(format!(
+            "Request to {} failed with status: {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(response)
+}
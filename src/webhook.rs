@@ -0,0 +1,93 @@
+//! Delivering change events to a webhook.
+//!
+//! Each event is POSTed as JSON. With a secret, requests are signed the way
+//! most webhook providers do it: `X-AnyList-Signature` is `sha256=` and the
+//! hex HMAC-SHA256 of the `X-AnyList-Timestamp` header, a `.`, and the body,
+//! so receivers can check both where a request came from and that it isn't a
+//! replay of an old one.
+
+use std::time::Duration;
+
+use napi::threadsafe_function::ThreadsafeFunctionCallMode;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use ring::hmac;
+
+use crate::{transport, ChangeEvent, WebhookFailure, WebhookFailureCallback};
+
+/// How many times a delivery is tried while the webhook can't be reached
+const ATTEMPTS: u32 = 3;
+
+pub(crate) struct Webhook {
+    url: String,
+    key: Option<hmac::Key>,
+    on_failure: Option<WebhookFailureCallback>,
+}
+
+impl Webhook {
+    pub fn new(
+        url: String,
+        secret: Option<&str>,
+        on_failure: Option<WebhookFailureCallback>,
+    ) -> Self {
+        Webhook {
+            url,
+            key: secret.map(|secret| hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes())),
+            on_failure,
+        }
+    }
+
+    fn headers(&self, body: &[u8]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let Some(key) = &self.key else {
+            return headers;
+        };
+
+        let timestamp = ((transport::current_timestamp() * 1000.0) as u64).to_string();
+        let mut signed = format!("{}.", timestamp).into_bytes();
+        signed.extend_from_slice(body);
+        let signature: String = hmac::sign(key, &signed)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        if let Ok(timestamp) = HeaderValue::from_str(&timestamp) {
+            headers.insert("X-AnyList-Timestamp", timestamp);
+        }
+        if let Ok(signature) = HeaderValue::from_str(&format!("sha256={}", signature)) {
+            headers.insert("X-AnyList-Signature", signature);
+        }
+        headers
+    }
+
+    /// POST an event, retrying while the webhook can't be reached, and report
+    /// it if it still couldn't be delivered
+    pub async fn deliver(&self, event: ChangeEvent) {
+        let body = serde_json::to_vec(&event).unwrap_or_default();
+
+        let mut attempt = 1;
+        let error = loop {
+            // Signed afresh each time so the timestamp stays current
+            let headers = self.headers(&body);
+            match transport::post_json(&self.url, headers, body.clone()).await {
+                Ok(()) => return,
+                Err(e) if attempt < ATTEMPTS && transport::is_unreachable(&e) => {
+                    tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+                    attempt += 1;
+                }
+                Err(e) => break e,
+            }
+        };
+
+        if let Some(on_failure) = &self.on_failure {
+            on_failure.call(
+                WebhookFailure {
+                    event,
+                    error: error.to_string(),
+                },
+                ThreadsafeFunctionCallMode::NonBlocking,
+            );
+        }
+    }
+}
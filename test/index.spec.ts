@@ -1,7 +1,58 @@
+import {
+  mkdtempSync,
+  readdirSync,
+  readFileSync,
+  rmSync,
+  writeFileSync,
+} from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
 import { test, expect, describe, beforeAll, afterAll } from "vitest";
 
-import { AnyListClient, type SavedTokens, type List } from "../index.js";
-import { shortId, dateStamp, testListName } from "./utils.js";
+import {
+  AnyListClient,
+  Batch,
+  CacheEntity,
+  CategoryRuleMatch,
+  ChangeEventType,
+  ConflictResolution,
+  ConflictType,
+  convertIngredientUnits,
+  diffSnapshots,
+  DigestFormat,
+  DriftReason,
+  ExportFormat,
+  getSchemas,
+  humanizeMinutes,
+  MealPlanEventType,
+  OptimisticEventType,
+  parseRecipeHtml,
+  QueuedChange,
+  QueueEventType,
+  RecipeHtmlTemplate,
+  RecipeKind,
+  RecipeSearchField,
+  RefreshEventType,
+  RestoreMode,
+  scaleIngredients,
+  SnapshotEntity,
+  UnitSystem,
+  type SavedTokens,
+  type List,
+  type ChangeEvent,
+  type DriftEvent,
+  type ListChanges,
+  type OptimisticEvent,
+  type QueueConflict,
+  type QueueEvent,
+  type RefreshEvent,
+} from "../index.js";
+import {
+  shortId,
+  dateStamp,
+  paprikaExport,
+  testListName,
+} from "./utils.js";
 
 const TEST_EMAIL = process.env.ANYLIST_EMAIL;
 const TEST_PASSWORD = process.env.ANYLIST_PASSWORD;
@@ -19,6 +70,211 @@ describe("AnyListClient API", () => {
     expect(typeof AnyListClient.fromTokens).toBe("function");
   });
 
+  test("getSchemas returns JSON Schemas for exported types", () => {
+    const schemas = getSchemas();
+
+    expect(schemas.List.title).toBe("List");
+    expect(schemas.ListItem.properties).toHaveProperty("checked");
+    expect(schemas.Recipe.properties).toHaveProperty("preparationSteps");
+    expect(schemas.MealPlanEvent.required).toContain("date");
+  });
+
+  test("scaleIngredients scales fractions, ranges, and units", () => {
+    const scaled = scaleIngredients(
+      [
+        { name: "Flour", quantity: "1/2 cup" },
+        { name: "Garlic", quantity: "2-3 cloves" },
+        { name: "Salt", quantity: "2 tsp" },
+        { name: "Butter", quantity: "1½ tbsp", note: "melted" },
+        { name: "Pepper", quantity: "to taste" },
+        { name: "Eggs" },
+      ],
+      3,
+    );
+
+    expect(scaled.map((i) => i.quantity)).toEqual([
+      "1 1/2 cups",
+      "6-9 cloves",
+      "2 tbsp",
+      "4 1/2 tbsp",
+      "to taste",
+      undefined,
+    ]);
+    expect(scaled[3].note).toBe("melted");
+    expect(
+      scaleIngredients([{ name: "Milk", quantity: "1 cup" }], 0.5)[0].quantity,
+    ).toBe("1/2 cup");
+    expect(() => scaleIngredients([], 0)).toThrow();
+  });
+
+  test("convertIngredientUnits converts between metric and US measures", () => {
+    const ingredients = [
+      { name: "Milk", quantity: "1 cup" },
+      { name: "Cheese", quantity: "8 oz", note: "grated" },
+      { name: "Salt", quantity: "1 tsp" },
+      { name: "Eggs", quantity: "2" },
+    ];
+
+    const metric = convertIngredientUnits(ingredients, UnitSystem.Metric);
+    expect(metric.map((i) => i.quantity)).toEqual([
+      "240 ml",
+      "230 g",
+      "1 tsp",
+      "2",
+    ]);
+    expect(metric[1].note).toBe("grated");
+
+    const imperial = convertIngredientUnits(
+      [
+        { name: "Stock", quantity: "250 ml" },
+        { name: "Flour", quantity: "1 kg" },
+      ],
+      UnitSystem.Imperial,
+    );
+    expect(imperial.map((i) => i.quantity)).toEqual(["1 cup", "2 1/4 lb"]);
+  });
+
+  test("convertIngredientUnits handles non-ASCII text after the unit", () => {
+    const converted = convertIngredientUnits(
+      [
+        { name: "Milk", quantity: "1 c ȺȺȺȺ" },
+        { name: "Sugar", quantity: "2 ÉCUELLES" },
+      ],
+      UnitSystem.Metric,
+    );
+    expect(converted.map((i) => i.quantity)).toEqual([
+      "240 ml ȺȺȺȺ",
+      "2 ÉCUELLES",
+    ]);
+  });
+
+  test("humanizeMinutes formats hours and minutes", () => {
+    expect(humanizeMinutes(45)).toBe("45 min");
+    expect(humanizeMinutes(120)).toBe("2 hr");
+    expect(humanizeMinutes(90)).toBe("1 hr 30 min");
+    expect(() => humanizeMinutes(-5)).toThrow();
+  });
+
+  /** A page with `json` embedded as JSON-LD */
+  function jsonLdPage(json: object): string {
+    return `<html><head><script type="application/ld+json">
+${JSON.stringify(json)}
+</script></head><body><h1>Recipe</h1></body></html>`;
+  }
+
+  test("parseRecipeHtml reads a recipe from a JSON-LD @graph", () => {
+    const html = jsonLdPage({
+      "@context": "https://schema.org",
+      "@graph": [
+        { "@type": "WebPage", name: "Banana bread - Example Kitchen" },
+        {
+          "@type": ["Recipe"],
+          name: "Banana bread",
+          description: "Moist &amp; easy",
+          recipeYield: ["8", "8 slices"],
+          recipeIngredient: ["3 ripe bananas", "2 cups flour"],
+          prepTime: "PT15M",
+          cookTime: "PT1H",
+          image: [{ "@type": "ImageObject", url: "https://example.com/b.jpg" }],
+          publisher: { "@type": "Organization", name: "Example Kitchen" },
+          recipeInstructions: [
+            {
+              "@type": "HowToSection",
+              name: "Batter",
+              itemListElement: [
+                { "@type": "HowToStep", text: "Mash the bananas." },
+                { "@type": "HowToStep", text: "Stir in the flour." },
+              ],
+            },
+            {
+              "@type": "HowToSection",
+              name: "Baking",
+              itemListElement: [
+                { "@type": "HowToStep", text: "Bake for an hour." },
+              ],
+            },
+          ],
+        },
+      ],
+    });
+
+    expect(parseRecipeHtml(html)).toEqual({
+      name: "Banana bread",
+      ingredients: [
+        { name: "ripe bananas", quantity: "3" },
+        { name: "flour", quantity: "2 cups" },
+      ],
+      preparationSteps: [
+        "Mash the bananas.",
+        "Stir in the flour.",
+        "Bake for an hour.",
+      ],
+      note: "Moist & easy",
+      sourceName: "Example Kitchen",
+      servings: "8 slices",
+      prepTime: 15,
+      cookTime: 60,
+      photoUrl: "https://example.com/b.jpg",
+    });
+  });
+
+  test("parseRecipeHtml reads a recipe from microdata", () => {
+    const html = `<div itemscope itemtype="https://schema.org/Recipe">
+  <h1 itemprop="name">Pancakes</h1>
+  <img itemprop="image" src="/pancakes.jpg">
+  <meta itemprop="prepTime" content="PT10M">
+  <time itemprop="cookTime" datetime="PT20M">20 minutes</time>
+  <span itemprop="recipeYield">4 servings</span>
+  <ul>
+    <li itemprop="recipeIngredient">1 cup flour</li>
+    <li itemprop="recipeIngredient">1 egg</li>
+  </ul>
+  <p itemprop="recipeInstructions">Whisk everything.</p>
+  <p itemprop="recipeInstructions">Fry in a hot pan.</p>
+</div>`;
+
+    expect(parseRecipeHtml(html)).toEqual({
+      name: "Pancakes",
+      ingredients: [
+        { name: "flour", quantity: "1 cup" },
+        { name: "egg", quantity: "1" },
+      ],
+      preparationSteps: ["Whisk everything.", "Fry in a hot pan."],
+      servings: "4 servings",
+      prepTime: 10,
+      cookTime: 20,
+    });
+    expect(() => parseRecipeHtml("<p>No recipe here</p>")).toThrow(
+      "No schema.org recipe found",
+    );
+  });
+
+  test("parseRecipeHtml reads ISO 8601 durations", () => {
+    const times = (prepTime: string, cookTime?: string, totalTime?: string) => {
+      const recipe = parseRecipeHtml(
+        jsonLdPage({
+          "@type": "Recipe",
+          name: "T",
+          prepTime,
+          cookTime,
+          totalTime,
+        }),
+      );
+      return [recipe.prepTime, recipe.cookTime];
+    };
+
+    expect(times("PT1H30M", "P1DT2H")).toEqual([90, 1560]);
+    expect(times("PT90S", "PT1.5H")).toEqual([2, 90]);
+    expect(times("45", "1 hr 30 mins")).toEqual([45, 90]);
+    // Cook time is worked out from the total when it's missing
+    expect(times("PT15M", undefined, "PT1H")).toEqual([15, 45]);
+    expect(times("PT1H", undefined, "PT30M")).toEqual([60, undefined]);
+    // Negative and malformed durations are left out
+    expect(times("-15", "PT-5M")).toEqual([undefined, undefined]);
+    expect(times("PT1X", "P")).toEqual([undefined, undefined]);
+    expect(times("PT", "PT1H2")).toEqual([undefined, undefined]);
+  });
+
   test("SavedTokens interface is correctly typed", () => {
     const tokens: SavedTokens = {
       userId: "test-user-id",
@@ -47,7 +303,7 @@ describe("AnyListClient API", () => {
     expect(retrievedTokens.accessToken).toBe("fake-access");
   });
 
-  test("client methods exist", () => {
+  test("getDeletedItems is empty for a new client", () => {
     const tokens: SavedTokens = {
       userId: "fake-user",
       accessToken: "fake-access",
@@ -56,261 +312,2984 @@ describe("AnyListClient API", () => {
     };
 
     const client = AnyListClient.fromTokens(tokens);
-
-    expect(typeof client.getLists).toBe("function");
-    expect(typeof client.createList).toBe("function");
-    expect(typeof client.deleteList).toBe("function");
-    expect(typeof client.getListById).toBe("function");
-    expect(typeof client.getListByName).toBe("function");
-    expect(typeof client.renameList).toBe("function");
-    expect(typeof client.addItem).toBe("function");
-    expect(typeof client.addItemWithDetails).toBe("function");
-    expect(typeof client.deleteItem).toBe("function");
-    expect(typeof client.crossOffItem).toBe("function");
-    expect(typeof client.uncheckItem).toBe("function");
-    expect(typeof client.updateItem).toBe("function");
-    expect(typeof client.bulkDeleteItems).toBe("function");
-    expect(typeof client.deleteAllCrossedOffItems).toBe("function");
-    expect(typeof client.getRecipes).toBe("function");
-    expect(typeof client.getRecipeById).toBe("function");
-    expect(typeof client.getRecipeByName).toBe("function");
-    expect(typeof client.createRecipe).toBe("function");
-    expect(typeof client.updateRecipe).toBe("function");
-    expect(typeof client.deleteRecipe).toBe("function");
-    expect(typeof client.addRecipeToList).toBe("function");
-    expect(typeof client.uploadPhoto).toBe("function");
-    // Category methods
-    expect(typeof client.createCategory).toBe("function");
-    expect(typeof client.deleteCategory).toBe("function");
-    expect(typeof client.renameCategory).toBe("function");
-    // Store methods
-    expect(typeof client.getStoresForList).toBe("function");
-    expect(typeof client.createStore).toBe("function");
-    expect(typeof client.updateStore).toBe("function");
-    expect(typeof client.getStoreFiltersForList).toBe("function");
-    expect(typeof client.deleteStore).toBe("function");
-    // Favourites methods
-    expect(typeof client.getFavourites).toBe("function");
-    expect(typeof client.getFavouritesLists).toBe("function");
-    expect(typeof client.getFavouritesForList).toBe("function");
-    expect(typeof client.addFavourite).toBe("function");
-    expect(typeof client.addFavouriteToList).toBe("function");
-    expect(typeof client.removeFavourite).toBe("function");
-    expect(typeof client.addFavouriteToShoppingList).toBe("function");
-    // Meal planning methods
-    expect(typeof client.getMealPlanEvents).toBe("function");
-    expect(typeof client.createMealPlanEvent).toBe("function");
-    expect(typeof client.updateMealPlanEvent).toBe("function");
-    expect(typeof client.deleteMealPlanEvent).toBe("function");
-    expect(typeof client.addMealPlanIngredientsToList).toBe("function");
-    // iCalendar methods
-    expect(typeof client.enableIcalendar).toBe("function");
-    expect(typeof client.disableIcalendar).toBe("function");
-    expect(typeof client.getIcalendarUrl).toBe("function");
-    // Recipe collection methods
-    expect(typeof client.getRecipeCollections).toBe("function");
-    expect(typeof client.createRecipeCollection).toBe("function");
-    expect(typeof client.deleteRecipeCollection).toBe("function");
-    expect(typeof client.addRecipeToCollection).toBe("function");
-    expect(typeof client.removeRecipeFromCollection).toBe("function");
+    expect(client.getDeletedItems("some-list")).toEqual([]);
   });
-});
 
-describe.runIf(hasCredentials)("AnyListClient Integration", () => {
-  let client: AnyListClient;
-  let testList: List;
-  const listName = testListName("node", getPlatform());
-  const addedItemIds: string[] = [];
+  test("setTimeZone accepts IANA names and rejects unknown ones", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-  beforeAll(async () => {
-    client = await AnyListClient.login(TEST_EMAIL!, TEST_PASSWORD!);
+    const client = AnyListClient.fromTokens(tokens);
+    expect(client.getTimeZone()).toBeNull();
+    client.setTimeZone("America/Vancouver");
+    expect(client.getTimeZone()).toBe("America/Vancouver");
+    expect(() => client.setTimeZone("Mars/Olympus_Mons")).toThrow();
+    expect(client.getTimeZone()).toBe("America/Vancouver");
+    client.setTimeZone(null);
+    expect(client.getTimeZone()).toBeNull();
   });
 
-  afterAll(async () => {
-    // Clean up: delete the test list (and all its items)
-    if (client && testList) {
-      try {
-        await client.deleteList(testList.id);
-        console.log(`Cleaned up test list: ${listName}`);
-      } catch {
-        // Fallback: try to delete items if list deletion fails
-        for (const itemId of addedItemIds) {
-          try {
-            await client.deleteItem(testList.id, itemId);
-          } catch {
-            // Ignore cleanup errors
-          }
-        }
-      }
-    }
-  });
+  test("category rules match exact names, then prefixes, then regexes", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-  test("login returns authenticated client", () => {
-    expect(client).toBeInstanceOf(AnyListClient);
-  });
+    const client = AnyListClient.fromTokens(tokens);
+    client.addCategoryRule({
+      pattern: "milk",
+      matchType: CategoryRuleMatch.Regex,
+      category: "Regex",
+    });
+    client.addCategoryRule({
+      pattern: "Oat",
+      matchType: CategoryRuleMatch.Prefix,
+      category: "Grains",
+    });
+    client.addCategoryRule({
+      pattern: "Oat milk",
+      matchType: CategoryRuleMatch.Prefix,
+      category: "Dairy alternatives",
+    });
+    client.addCategoryRule({ pattern: "oat milk", category: "Fridge" });
+    client.addCategoryRule({
+      pattern: "bananas",
+      category: "Produce",
+      listId: "other-list",
+    });
 
-  test("getTokens returns valid tokens after login", () => {
-    const tokens = client.getTokens();
+    expect(client.matchCategoryRule("  OAT MILK ")).toBe("Fridge");
+    expect(client.matchCategoryRule("Oat milk, barista")).toBe(
+      "Dairy alternatives",
+    );
+    expect(client.matchCategoryRule("Oatcakes")).toBe("Grains");
+    expect(client.matchCategoryRule("Whole MILK")).toBe("Regex");
+    expect(client.matchCategoryRule("Bananas")).toBeNull();
+    expect(client.matchCategoryRule("Bananas", "other-list")).toBe("Produce");
 
-    expect(tokens.userId).toBeTruthy();
-    expect(tokens.accessToken).toBeTruthy();
-    expect(tokens.refreshToken).toBeTruthy();
-    expect(typeof tokens.isPremiumUser).toBe("boolean");
+    expect(() =>
+      client.addCategoryRule({
+        pattern: "(",
+        matchType: CategoryRuleMatch.Regex,
+        category: "Broken",
+      }),
+    ).toThrow("Invalid regex");
+    expect(() =>
+      client.addCategoryRule({ pattern: " ", category: "X" }),
+    ).toThrow();
+
+    const [first] = client.getCategoryRules();
+    expect(client.removeCategoryRule(first.id)).toBe(true);
+    expect(client.removeCategoryRule(first.id)).toBe(false);
+    expect(client.matchCategoryRule("Whole milk")).toBeNull();
   });
 
-  test("fromTokens can restore session", async () => {
-    const tokens = client.getTokens();
-    const restoredClient = AnyListClient.fromTokens(tokens);
+  test("category rules persist to their file", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+    const dir = mkdtempSync(join(tmpdir(), "anylist-rules-"));
+    const path = join(dir, "rules.json");
 
-    // Verify the restored client works
-    const lists = await restoredClient.getLists();
-    expect(Array.isArray(lists)).toBe(true);
+    try {
+      const client = AnyListClient.fromTokens(tokens);
+      client.addCategoryRule({ pattern: "eggs", category: "Dairy" });
+      expect(client.setCategoryRulesFile(path)).toHaveLength(1);
+      client.addCategoryRule({
+        pattern: "^fresh ",
+        matchType: CategoryRuleMatch.Regex,
+        category: "Produce",
+      });
+      expect(JSON.parse(readFileSync(path, "utf8"))).toHaveLength(2);
+
+      const restored = AnyListClient.fromTokens(tokens);
+      const rules = restored.setCategoryRulesFile(path);
+      expect(rules.map((r) => r.pattern)).toEqual(["eggs", "^fresh "]);
+      expect(restored.matchCategoryRule("Fresh basil")).toBe("Produce");
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
   });
 
-  test("getLists returns array of lists", async () => {
-    const lists = await client.getLists();
+  test("meal plan dates are checked before any request is made", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    expect(Array.isArray(lists)).toBe(true);
-    // Every AnyList account has at least one default list
-    if (lists.length > 0) {
-      expect(lists[0]).toHaveProperty("id");
-      expect(lists[0]).toHaveProperty("name");
-      expect(lists[0]).toHaveProperty("items");
-    }
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(
+      client.getMealPlanEvents("01/02/2024", "2024-01-05"),
+    ).rejects.toThrow("Ambiguous date");
+    await expect(
+      client.getMealPlanEvents("next tuesday", "2024-01-05"),
+    ).rejects.toThrow("Invalid date");
+    await expect(
+      client.getMealPlanEvents(new Date("not a date"), "2024-01-05"),
+    ).rejects.toThrow("Invalid Date");
+    await expect(
+      client.getMealPlanStats("2024-01-05", "2024-01-01"),
+    ).rejects.toThrow("must not be before");
   });
 
-  test("createList creates a new list", async () => {
-    console.log(`Creating test list: ${listName}`);
-    testList = await client.createList(listName);
+  test("addFavourites rejects blank names before any request is made", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    expect(testList).toHaveProperty("id");
-    expect(testList.name).toBe(listName);
-    expect(Array.isArray(testList.items)).toBe(true);
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(client.addFavourites("list", [])).resolves.toEqual([]);
+    await expect(
+      client.addFavourites("list", [{ name: "Rice" }, { name: "  " }]),
+    ).rejects.toThrow("Favourite at index 1 has no name");
   });
 
-  test("addItem adds item to list", async () => {
-    await client.addItem(testList.id, "Test Item 1");
+  test("subscribe and watchList reject a zero poll interval", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    // Fetch the list to verify
-    const lists = await client.getLists();
-    const updatedList = lists.find((l) => l.id === testList.id);
+    const client = AnyListClient.fromTokens(tokens);
+    expect(ChangeEventType.ItemAdded).toBe("itemAdded");
+    await expect(
+      client.subscribe(() => {}, { pollIntervalMs: 0 }),
+    ).rejects.toThrow("Poll interval");
+    await expect(client.watchList("some-list", 0, () => {})).rejects.toThrow(
+      "Poll interval",
+    );
+  });
 
-    expect(updatedList).toBeDefined();
-    const addedItem = updatedList!.items.find((i) => i.name === "Test Item 1");
-    expect(addedItem).toBeDefined();
-    expect(addedItem!.checked).toBe(false);
+  test("startWebhookBridge rejects invalid URLs", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    addedItemIds.push(addedItem!.id);
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(
+      client.startWebhookBridge({ url: "not a url", secret: "s3cret" }),
+    ).rejects.toThrow("Invalid URL");
   });
 
-  test("addItemWithDetails adds item with quantity and note", async () => {
-    await client.addItemWithDetails(
-      testList.id,
-      "Test Item 2",
-      "2 lbs",
-      "Get the organic one",
-      "Produce",
-    );
+  test("getChangesSince rejects invalid cursors", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    const lists = await client.getLists();
-    const updatedList = lists.find((l) => l.id === testList.id);
-    const addedItem = updatedList!.items.find((i) => i.name === "Test Item 2");
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(client.getChangesSince("not a cursor")).rejects.toThrow(
+      "Invalid sync cursor",
+    );
+  });
 
-    expect(addedItem).toBeDefined();
-    expect(addedItem!.quantity).toBe("2 lbs");
+  test("setOfflineQueue picks up changes already queued in its file", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
 
-    expect(addedItem!.note).toBe("Get the organic one");
+    const dir = mkdtempSync(join(tmpdir(), "anylist-queue-"));
+    try {
+      const path = join(dir, "queue.json");
+      const client = AnyListClient.fromTokens(tokens);
+      expect(client.getQueuedOperations()).toEqual([]);
+      client.setOfflineQueue(path);
+      expect(JSON.parse(readFileSync(path, "utf8"))).toEqual([]);
+      // Written through a temporary file that's moved into place
+      expect(readdirSync(dir)).toEqual(["queue.json"]);
 
-    addedItemIds.push(addedItem!.id);
+      const queued = {
+        id: "op-1",
+        change: QueuedChange.Add,
+        description: "Add Milk",
+        listId: "list-1",
+        itemId: "item-1",
+        name: "Milk",
+        queuedAt: 1700000000000,
+      };
+      writeFileSync(
+        path,
+        JSON.stringify([{ ...queued, endpoint: "data/x", body: "" }]),
+      );
+      const restarted = AnyListClient.fromTokens(tokens);
+      restarted.setOfflineQueue(path);
+      expect(restarted.getQueuedOperations()).toEqual([queued]);
+      expect(() => restarted.setOfflineQueue(null)).toThrow("still queued");
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  test("setConflictResolver accepts a callback or null", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    client.setConflictResolver(() => ConflictResolution.Merge);
+    client.setConflictResolver(null);
+    expect(ConflictType.DuplicateItem).toBe("duplicateItem");
+    expect(ConflictType.ItemRenamed).toBe("itemRenamed");
+    expect(ConflictResolution.KeepLocal).toBe("keepLocal");
+    expect(QueueEventType.Discarded).toBe("discarded");
+  });
+
+  test("setOptimisticUpdates can be turned on and off", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    client.setOptimisticUpdates(true, () => {});
+    client.setOptimisticUpdates(false);
+    expect(OptimisticEventType.RolledBack).toBe("rolledBack");
+  });
+
+  test("getJournal reads entries appended by other clients", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const dir = mkdtempSync(join(tmpdir(), "anylist-journal-"));
+    try {
+      const path = join(dir, "journal.jsonl");
+      const client = AnyListClient.fromTokens(tokens);
+      expect(client.getJournal()).toEqual([]);
+      client.setJournalFile(path);
+      expect(readFileSync(path, "utf8")).toBe("");
+
+      const entry = (id: string, finishedAt: number) => ({
+        id,
+        operation: "addItem",
+        entityIds: ["list-1", "item-1"],
+        startedAt: finishedAt - 100,
+        finishedAt,
+        succeeded: true,
+        processId: 1234,
+      });
+      writeFileSync(
+        path,
+        [entry("a", 2000), entry("b", 1000)]
+          .map((e) => JSON.stringify(e) + "\n")
+          .join("") + '{"id":"partial',
+      );
+      expect(client.getJournal()).toEqual([entry("b", 1000), entry("a", 2000)]);
+      expect(client.getJournal({ since: 1500 })).toEqual([entry("a", 2000)]);
+
+      client.setJournalFile(null);
+      expect(client.getJournal()).toEqual([]);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  test("startAutoRefresh checks its intervals", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    expect(() => client.startAutoRefresh({ lists: 0 })).toThrow(
+      "Refresh interval must be at least 1 ms",
+    );
+    client.startAutoRefresh({ lists: 60_000, recipes: 600_000 }, () => {});
+    client.stopAutoRefresh();
+    expect(RefreshEventType.Refreshed).toBe("refreshed");
+  });
+
+  test("submitRawOperation checks what it's given", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    const encoded = client.encodeOperation({
+      handlerId: "set-list-item-checked",
+      listId: "list-1",
+      listItemId: "item-1",
+      updatedValue: "y",
+    });
+    expect(Buffer.isBuffer(encoded)).toBe(true);
+    expect(encoded.includes("set-list-item-checked")).toBe(true);
+
+    await expect(
+      client.submitRawOperation("list-2", encoded),
+    ).rejects.toThrow("an operation is for list list-1, not list-2");
+    await expect(
+      client.submitRawOperation("list-1", Buffer.alloc(0)),
+    ).rejects.toThrow("it has no operations");
+    await expect(
+      client.submitRawOperation("list-1", Buffer.from([0xff, 0xff])),
+    ).rejects.toThrow("Invalid operation list");
+  });
+
+  test("request only sends credentials to AnyList", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(
+      client.request("POST", "https://example.com/data/user-data/get"),
+    ).rejects.toThrow("isn't relative to AnyList");
+    await expect(
+      client.request("POST", "//example.com/data/user-data/get"),
+    ).rejects.toThrow("isn't relative to AnyList");
+    await expect(
+      client.request("NOT A METHOD", "data/user-data/get"),
+    ).rejects.toThrow("Invalid method");
+  });
+
+  test("batch collects changes until it's committed", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    const batch = client.batch();
+    expect(batch).toBeInstanceOf(Batch);
+    expect(batch.size).toBe(0);
+    await expect(batch.commit()).resolves.toBe(0);
+
+    const itemId = batch.addItem("list-1", "Milk");
+    expect(itemId).toMatch(/^[0-9a-f]{32}$/);
+    batch.setCategory("list-1", itemId, "dairy");
+    batch.crossOffItem("list-1", itemId);
+    expect(batch.size).toBe(3);
+  });
+
+  test("getListItems needs a limit of at least 1", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(
+      client.getListItems("list-1", { limit: 0 }),
+    ).rejects.toThrow("Limit must be at least 1");
+  });
+
+  test("setDriftDetection takes a callback or null", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    client.setDriftDetection(() => {});
+    client.setDriftDetection(null);
+    expect(DriftReason.ListChanged).toBe("listChanged");
+    expect(DriftReason.ChangesSkipped).toBe("changesSkipped");
+    expect(DriftReason.RefreshRequired).toBe("refreshRequired");
+  });
+
+  test("getSyncStatus starts out with nothing fetched", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    expect(client.getSyncStatus()).toEqual({
+      queuedOperations: 0,
+      realtimeConnected: false,
+    });
+  });
+
+  test("diffSnapshots reports list and recipe changes", () => {
+    const milk = { id: "i1", name: "Milk", checked: false, note: "" };
+    const a = {
+      version: 1,
+      lists: [
+        { id: "l1", name: "Groceries", items: [milk] },
+        { id: "l2", name: "Hardware", items: [] },
+      ],
+      recipes: [
+        { id: "r1", name: "Soup", rating: 3, updatedAt: 1 },
+        { id: "r2", name: "Bread" },
+      ],
+    };
+    const b = {
+      version: 1,
+      lists: [
+        {
+          id: "l1",
+          name: "Groceries",
+          items: [
+            { ...milk, checked: true },
+            { id: "i2", name: "Eggs", checked: false, note: "" },
+          ],
+        },
+        { id: "l3", name: "Party", items: [] },
+      ],
+      recipes: [
+        { id: "r1", name: "Tomato soup", rating: 5, updatedAt: 2 },
+        { id: "r3", name: "Pie" },
+      ],
+    };
+
+    const diff = diffSnapshots(JSON.stringify(a), JSON.stringify(b));
+    expect(diff.listsAdded.map((l) => l.id)).toEqual(["l3"]);
+    expect(diff.listsRemoved.map((l) => l.id)).toEqual(["l2"]);
+    expect(diff.listsChanged).toHaveLength(1);
+    expect(diff.listsChanged[0].added.map((i) => i.name)).toEqual(["Eggs"]);
+    expect(diff.listsChanged[0].updated.map((i) => i.checked)).toEqual([true]);
+    expect(diff.recipesAdded.map((r) => r.name)).toEqual(["Pie"]);
+    expect(diff.recipesRemoved.map((r) => r.name)).toEqual(["Bread"]);
+    expect(diff.recipesChanged).toEqual([
+      {
+        recipeId: "r1",
+        name: "Tomato soup",
+        oldName: "Soup",
+        changedFields: ["name", "rating"],
+      },
+    ]);
+    expect(diffSnapshots(JSON.stringify(a), JSON.stringify(a))).toEqual({
+      listsAdded: [],
+      listsRemoved: [],
+      listsChanged: [],
+      recipesAdded: [],
+      recipesRemoved: [],
+      recipesChanged: [],
+    });
+  });
+
+  test("diffSnapshots ignores photos that failed to download", () => {
+    const soup = { id: "r1", name: "Soup" };
+    const a = { version: 1, recipes: [soup] };
+    const b = {
+      version: 1,
+      recipes: [{ ...soup, photoError: "Request failed with status: 404" }],
+    };
+
+    const diff = diffSnapshots(JSON.stringify(a), JSON.stringify(b));
+    expect(diff.recipesChanged).toEqual([]);
+  });
+
+  test("restoreFromSnapshot rejects invalid snapshots", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+    await expect(client.restoreFromSnapshot("not json")).rejects.toThrow(
+      "Invalid snapshot",
+    );
+    await expect(
+      client.restoreFromSnapshot(JSON.stringify({ version: 99, lists: [] })),
+    ).rejects.toThrow("newer than this library supports");
+  });
+
+  test("importRecipesFromPaprika reports each recipe that fails", async () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    // The fake tokens make every recipe fail, without stopping the import
+    const client = AnyListClient.fromTokens(tokens);
+    const result = await client.importRecipesFromPaprika(
+      paprikaExport([
+        { name: "Soup", ingredients: "1 onion\n2 cups stock" },
+        { name: "Bread", directions: "Knead.\nBake." },
+      ]),
+    );
+    expect(result.created).toEqual([]);
+    expect(result.failed.map((f) => f.name)).toEqual(["Soup", "Bread"]);
+    expect(result.failed.every((f) => f.error.length > 0)).toBe(true);
+
+    await expect(
+      client.importRecipesFromPaprika(Buffer.from("not a zip")),
+    ).rejects.toThrow("Invalid Paprika export");
+  });
+
+  test("client methods exist", () => {
+    const tokens: SavedTokens = {
+      userId: "fake-user",
+      accessToken: "fake-access",
+      refreshToken: "fake-refresh",
+      isPremiumUser: false,
+    };
+
+    const client = AnyListClient.fromTokens(tokens);
+
+    expect(typeof client.getLists).toBe("function");
+    expect(typeof client.createList).toBe("function");
+    expect(typeof client.deleteList).toBe("function");
+    expect(typeof client.getListById).toBe("function");
+    expect(typeof client.getListItems).toBe("function");
+    expect(typeof client.getListByName).toBe("function");
+    expect(typeof client.findOrCreateList).toBe("function");
+    expect(typeof client.renameList).toBe("function");
+    expect(typeof client.getItemById).toBe("function");
+    expect(typeof client.addItem).toBe("function");
+    expect(typeof client.addItemWithDetails).toBe("function");
+    expect(typeof client.addItemIfNotExists).toBe("function");
+    expect(typeof client.patchItem).toBe("function");
+    expect(typeof client.upsertItem).toBe("function");
+    expect(typeof client.deleteItem).toBe("function");
+    expect(typeof client.crossOffItem).toBe("function");
+    expect(typeof client.uncheckItem).toBe("function");
+    expect(typeof client.updateItem).toBe("function");
+    expect(typeof client.bulkDeleteItems).toBe("function");
+    expect(typeof client.bulkCrossOffItems).toBe("function");
+    expect(typeof client.bulkUncheckItems).toBe("function");
+    expect(typeof client.bulkCategorizeItems).toBe("function");
+    expect(typeof client.deleteAllCrossedOffItems).toBe("function");
+    expect(typeof client.getDeletedItems).toBe("function");
+    expect(typeof client.batch).toBe("function");
+    expect(typeof client.restoreItem).toBe("function");
+    expect(typeof client.getRecipes).toBe("function");
+    expect(typeof client.getRecipeById).toBe("function");
+    expect(typeof client.getRecipesByIds).toBe("function");
+    expect(typeof client.getRecipeByName).toBe("function");
+    expect(typeof client.searchRecipes).toBe("function");
+    expect(typeof client.findRecipesUsingIngredient).toBe("function");
+    expect(typeof client.getRecentRecipes).toBe("function");
+    expect(typeof client.createRecipe).toBe("function");
+    expect(typeof client.updateRecipe).toBe("function");
+    expect(typeof client.deleteRecipe).toBe("function");
+    expect(typeof client.scaleRecipe).toBe("function");
+    expect(typeof client.addRecipeToList).toBe("function");
+    expect(typeof client.addRecipesToList).toBe("function");
+    expect(typeof client.uploadPhoto).toBe("function");
+    expect(typeof client.getRecipeHistory).toBe("function");
+    expect(typeof client.revertRecipe).toBe("function");
+    expect(typeof client.patchRecipe).toBe("function");
+    expect(typeof client.rateRecipe).toBe("function");
+    expect(typeof client.markRecipeCooked).toBe("function");
+    expect(typeof client.duplicateRecipe).toBe("function");
+    expect(typeof client.createPhotoRecipe).toBe("function");
+    expect(typeof client.getRecipePhotos).toBe("function");
+    expect(typeof client.clearRecipePhoto).toBe("function");
+    expect(typeof client.replaceRecipePhoto).toBe("function");
+    expect(typeof client.downloadRecipePhoto).toBe("function");
+    expect(typeof client.importRecipeFromUrl).toBe("function");
+    expect(typeof client.importRecipeFromSchemaOrg).toBe("function");
+    expect(typeof client.importRecipesFromPaprika).toBe("function");
+    expect(typeof client.importRecipesFromMealie).toBe("function");
+    expect(typeof client.exportRecipe).toBe("function");
+    expect(typeof client.renderRecipeHtml).toBe("function");
+    expect(typeof client.exportAllRecipes).toBe("function");
+    expect(typeof client.exportSnapshot).toBe("function");
+    expect(typeof client.restoreFromSnapshot).toBe("function");
+    // Subscription methods
+    expect(typeof client.subscribe).toBe("function");
+    expect(typeof client.watchList).toBe("function");
+    expect(typeof client.getChangesSince).toBe("function");
+    expect(typeof client.startWebhookBridge).toBe("function");
+    // Cache methods
+    expect(typeof client.setCacheOptions).toBe("function");
+    expect(typeof client.invalidateCache).toBe("function");
+    expect(typeof client.startAutoRefresh).toBe("function");
+    expect(typeof client.stopAutoRefresh).toBe("function");
+    // Offline queue methods
+    expect(typeof client.setOfflineQueue).toBe("function");
+    expect(typeof client.setConflictResolver).toBe("function");
+    expect(typeof client.getQueuedOperations).toBe("function");
+    expect(typeof client.flushQueue).toBe("function");
+    // Optimistic update methods
+    expect(typeof client.setOptimisticUpdates).toBe("function");
+    // Journal methods
+    expect(typeof client.setJournalFile).toBe("function");
+    expect(typeof client.getJournal).toBe("function");
+    // Sync status methods
+    expect(typeof client.getSyncStatus).toBe("function");
+    expect(typeof client.setDriftDetection).toBe("function");
+    // List settings methods
+    expect(typeof client.getListSettings).toBe("function");
+    expect(typeof client.updateListSettings).toBe("function");
+    expect(typeof client.getListNotificationSettings).toBe("function");
+    expect(typeof client.updateListNotificationSettings).toBe("function");
+    // Category methods
+    expect(typeof client.createCategory).toBe("function");
+    expect(typeof client.ensureCategory).toBe("function");
+    expect(typeof client.deleteCategory).toBe("function");
+    expect(typeof client.renameCategory).toBe("function");
+    expect(typeof client.getCategoryGroupsForList).toBe("function");
+    expect(typeof client.addCategoryRule).toBe("function");
+    expect(typeof client.removeCategoryRule).toBe("function");
+    expect(typeof client.getCategoryRules).toBe("function");
+    expect(typeof client.setCategoryRulesFile).toBe("function");
+    expect(typeof client.matchCategoryRule).toBe("function");
+    expect(typeof client.createCategoryGroup).toBe("function");
+    expect(typeof client.deleteCategoryGroup).toBe("function");
+    expect(typeof client.getActiveCategoryGroup).toBe("function");
+    expect(typeof client.setActiveCategoryGroup).toBe("function");
+    // Store methods
+    expect(typeof client.getStoresForList).toBe("function");
+    expect(typeof client.createStore).toBe("function");
+    expect(typeof client.ensureStore).toBe("function");
+    expect(typeof client.updateStore).toBe("function");
+    expect(typeof client.getStoreFiltersForList).toBe("function");
+    expect(typeof client.deleteStore).toBe("function");
+    expect(typeof client.getItemsByStore).toBe("function");
+    // Favourites methods
+    expect(typeof client.getFavourites).toBe("function");
+    expect(typeof client.getFavouritesLists).toBe("function");
+    expect(typeof client.getFavouritesForList).toBe("function");
+    expect(typeof client.renameFavouritesList).toBe("function");
+    expect(typeof client.addFavourite).toBe("function");
+    expect(typeof client.addFavouriteToList).toBe("function");
+    expect(typeof client.addFavourites).toBe("function");
+    expect(typeof client.promoteCheckedItemsToFavourites).toBe("function");
+    expect(typeof client.reorderFavourites).toBe("function");
+    expect(typeof client.removeFavourite).toBe("function");
+    expect(typeof client.updateFavourite).toBe("function");
+    expect(typeof client.addFavouriteToShoppingList).toBe("function");
+    expect(typeof client.addAllFavouritesToShoppingList).toBe("function");
+    expect(typeof client.getFavouriteStats).toBe("function");
+    // Meal planning methods
+    expect(typeof client.setTimeZone).toBe("function");
+    expect(typeof client.getTimeZone).toBe("function");
+    expect(typeof client.getMealPlanCalendar).toBe("function");
+    expect(typeof client.getMealPlanEvents).toBe("function");
+    expect(typeof client.getMealPlanEventById).toBe("function");
+    expect(typeof client.createMealPlanEvent).toBe("function");
+    expect(typeof client.createNoteEvent).toBe("function");
+    expect(typeof client.createLeftoversEvent).toBe("function");
+    expect(typeof client.bulkCreateMealPlanEvents).toBe("function");
+    expect(typeof client.updateMealPlanEvent).toBe("function");
+    expect(typeof client.deleteMealPlanEvent).toBe("function");
+    expect(typeof client.reorderMealPlanEvents).toBe("function");
+    expect(typeof client.copyMealPlanWeek).toBe("function");
+    expect(typeof client.saveMealPlanTemplate).toBe("function");
+    expect(typeof client.getMealPlanTemplates).toBe("function");
+    expect(typeof client.applyMealPlanTemplate).toBe("function");
+    expect(typeof client.deleteMealPlanTemplate).toBe("function");
+    expect(typeof client.addMealPlanIngredientsToList).toBe("function");
+    expect(typeof client.suggestMealPlan).toBe("function");
+    expect(typeof client.searchMealPlanEvents).toBe("function");
+    expect(typeof client.getMealPlanStats).toBe("function");
+    expect(typeof client.getMealPlanNutrition).toBe("function");
+    expect(typeof client.renderMealPlanDigest).toBe("function");
+    expect(typeof client.exportMealPlanToIcs).toBe("function");
+    // iCalendar methods
+    expect(typeof client.enableIcalendar).toBe("function");
+    expect(typeof client.disableIcalendar).toBe("function");
+    expect(typeof client.fetchIcalendarEvents).toBe("function");
+    expect(typeof client.getIcalendarUrl).toBe("function");
+    // Recipe collection methods
+    expect(typeof client.getRecipeCollections).toBe("function");
+    expect(typeof client.getRecipeCollectionById).toBe("function");
+    expect(typeof client.getRecipeCollectionByName).toBe("function");
+    expect(typeof client.getRecipesInCollection).toBe("function");
+    expect(typeof client.createRecipeCollection).toBe("function");
+    expect(typeof client.deleteRecipeCollection).toBe("function");
+    expect(typeof client.addRecipeToCollection).toBe("function");
+    expect(typeof client.removeRecipeFromCollection).toBe("function");
+    // Raw operation methods
+    expect(typeof client.encodeOperation).toBe("function");
+    expect(typeof client.submitRawOperation).toBe("function");
+    expect(typeof client.request).toBe("function");
+  });
+});
+
+describe.runIf(hasCredentials)("AnyListClient Integration", () => {
+  let client: AnyListClient;
+  let testList: List;
+  const listName = testListName("node", getPlatform());
+  const addedItemIds: string[] = [];
+
+  beforeAll(async () => {
+    client = await AnyListClient.login(TEST_EMAIL!, TEST_PASSWORD!);
+  });
+
+  afterAll(async () => {
+    // Clean up: delete the test list (and all its items)
+    if (client && testList) {
+      try {
+        await client.deleteList(testList.id);
+        console.log(`Cleaned up test list: ${listName}`);
+      } catch {
+        // Fallback: try to delete items if list deletion fails
+        for (const itemId of addedItemIds) {
+          try {
+            await client.deleteItem(testList.id, itemId);
+          } catch {
+            // Ignore cleanup errors
+          }
+        }
+      }
+    }
+  });
+
+  test("login returns authenticated client", () => {
+    expect(client).toBeInstanceOf(AnyListClient);
+  });
+
+  test("getTokens returns valid tokens after login", () => {
+    const tokens = client.getTokens();
+
+    expect(tokens.userId).toBeTruthy();
+    expect(tokens.accessToken).toBeTruthy();
+    expect(tokens.refreshToken).toBeTruthy();
+    expect(typeof tokens.isPremiumUser).toBe("boolean");
+  });
+
+  test("fromTokens can restore session", async () => {
+    const tokens = client.getTokens();
+    const restoredClient = AnyListClient.fromTokens(tokens);
+
+    // Verify the restored client works
+    const lists = await restoredClient.getLists();
+    expect(Array.isArray(lists)).toBe(true);
+  });
+
+  test("getLists returns array of lists", async () => {
+    const lists = await client.getLists();
+
+    expect(Array.isArray(lists)).toBe(true);
+    // Every AnyList account has at least one default list
+    if (lists.length > 0) {
+      expect(lists[0]).toHaveProperty("id");
+      expect(lists[0]).toHaveProperty("name");
+      expect(lists[0]).toHaveProperty("items");
+    }
+  });
+
+  test("createList creates a new list", async () => {
+    console.log(`Creating test list: ${listName}`);
+    testList = await client.createList(listName);
+
+    expect(testList).toHaveProperty("id");
+    expect(testList.name).toBe(listName);
+    expect(Array.isArray(testList.items)).toBe(true);
+  });
+
+  test("addItem adds item to list", async () => {
+    await client.addItem(testList.id, "Test Item 1");
+
+    // Fetch the list to verify
+    const lists = await client.getLists();
+    const updatedList = lists.find((l) => l.id === testList.id);
+
+    expect(updatedList).toBeDefined();
+    const addedItem = updatedList!.items.find((i) => i.name === "Test Item 1");
+    expect(addedItem).toBeDefined();
+    expect(addedItem!.checked).toBe(false);
+
+    addedItemIds.push(addedItem!.id);
+  });
+
+  test("getItemById gets an item or reports it's not found", async () => {
+    const item = await client.addItem(testList.id, `CI By ID ${shortId()}`);
+    try {
+      expect(await client.getItemById(testList.id, item.id)).toMatchObject({
+        id: item.id,
+        name: item.name,
+      });
+    } finally {
+      await client.deleteItem(testList.id, item.id);
+    }
+    await expect(client.getItemById(testList.id, item.id)).rejects.toThrow(
+      "not found",
+    );
+  });
+
+  test("addItemWithDetails adds item with quantity and note", async () => {
+    await client.addItemWithDetails(
+      testList.id,
+      "Test Item 2",
+      "2 lbs",
+      "Get the organic one",
+      "Produce",
+    );
+
+    const lists = await client.getLists();
+    const updatedList = lists.find((l) => l.id === testList.id);
+    const addedItem = updatedList!.items.find((i) => i.name === "Test Item 2");
+
+    expect(addedItem).toBeDefined();
+    expect(addedItem!.quantity).toBe("2 lbs");
+
+    expect(addedItem!.note).toBe("Get the organic one");
+
+    addedItemIds.push(addedItem!.id);
   });
 
   test("crossOffItem checks the item", async () => {
     const itemId = addedItemIds[0];
     await client.crossOffItem(testList.id, itemId);
 
-    const lists = await client.getLists();
-    const updatedList = lists.find((l) => l.id === testList.id);
-    const item = updatedList!.items.find((i) => i.id === itemId);
+    const lists = await client.getLists();
+    const updatedList = lists.find((l) => l.id === testList.id);
+    const item = updatedList!.items.find((i) => i.id === itemId);
+
+    expect(item!.checked).toBe(true);
+  });
+
+  test("uncheckItem unchecks the item", async () => {
+    const itemId = addedItemIds[0];
+    await client.uncheckItem(testList.id, itemId);
+
+    const lists = await client.getLists();
+    const updatedList = lists.find((l) => l.id === testList.id);
+    const item = updatedList!.items.find((i) => i.id === itemId);
+
+    expect(item!.checked).toBe(false);
+  });
+
+  test("deleteItem removes item from list", async () => {
+    const itemId = addedItemIds.pop()!;
+    await client.deleteItem(testList.id, itemId);
+
+    const lists = await client.getLists();
+    const updatedList = lists.find((l) => l.id === testList.id);
+    const deletedItem = updatedList!.items.find((i) => i.id === itemId);
+
+    expect(deletedItem).toBeUndefined();
+  });
+
+  test("restoreItem re-adds a deleted item", async () => {
+    const deleted = client.getDeletedItems(testList.id);
+    expect(deleted.length).toBeGreaterThan(0);
+
+    const restored = await client.restoreItem(testList.id, deleted[0].id);
+    expect(restored.name).toBe(deleted[0].name);
+    expect(client.getDeletedItems(testList.id)).toHaveLength(0);
+
+    addedItemIds.push(restored.id);
+  });
+
+  test("getRecipes returns array", async () => {
+    const recipes = await client.getRecipes();
+
+    expect(Array.isArray(recipes)).toBe(true);
+    // Recipes may be empty for a new account
+    if (recipes.length > 0) {
+      expect(recipes[0]).toHaveProperty("id");
+      expect(recipes[0]).toHaveProperty("name");
+      expect(recipes[0]).toHaveProperty("ingredients");
+      expect(recipes[0]).toHaveProperty("preparationSteps");
+    }
+  });
+
+  test("createRecipe creates a new recipe with all options", async () => {
+    const recipeName = `CI Recipe ${shortId()} ${dateStamp()}`;
+
+    const recipe = await client.createRecipe({
+      name: recipeName,
+      ingredients: [
+        { name: "Flour", quantity: "2 cups" },
+        { name: "Salt", quantity: "1 tsp", note: "optional" },
+      ],
+      preparationSteps: [
+        "Mix dry ingredients",
+        "Add water",
+        "Knead until smooth",
+      ],
+      note: "Test recipe note",
+      sourceName: "CI Test",
+      sourceUrl: "https://example.com/recipe",
+      servings: "4",
+      prepTime: 15,
+      cookTime: 30,
+      rating: 5,
+    });
+
+    expect(recipe).toHaveProperty("id");
+    expect(recipe.name).toBe(recipeName);
+    expect(recipe.ingredients).toHaveLength(2);
+    expect(recipe.ingredients[0].name).toBe("Flour");
+    expect(recipe.ingredients[0].quantity).toBe("2 cups");
+    expect(recipe.preparationSteps).toHaveLength(3);
+    expect(recipe.note).toBe("Test recipe note");
+    expect(recipe.sourceName).toBe("CI Test");
+    expect(recipe.sourceUrl).toBe("https://example.com/recipe");
+    expect(recipe.servings).toBe("4");
+    expect(recipe.prepTime).toBe(15);
+    expect(recipe.cookTime).toBe(30);
+    expect(recipe.totalTime).toBe(45);
+    expect(recipe.rating).toBe(5);
+
+    // Verify we can fetch it back
+    const fetchedRecipe = await client.getRecipeById(recipe.id);
+    expect(fetchedRecipe.name).toBe(recipeName);
+    expect(fetchedRecipe.note).toBe("Test recipe note");
+  });
+
+  test("getRecipesByIds returns recipes in the order requested", async () => {
+    const tag = shortId();
+    const first = await client.createRecipe({
+      name: `CI By ID A ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const second = await client.createRecipe({
+      name: `CI By ID B ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+
+    const recipes = await client.getRecipesByIds([
+      second.id,
+      "missing",
+      first.id,
+    ]);
+    expect(recipes.map((r) => r.id)).toEqual([second.id, first.id]);
+
+    await client.deleteRecipe(first.id);
+    await client.deleteRecipe(second.id);
+  });
+
+  test("searchRecipes matches names and ingredients", async () => {
+    const tag = shortId();
+    const recipe = await client.createRecipe({
+      name: `CI Search ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Saffron ${tag}` }],
+      preparationSteps: [],
+    });
+
+    const byName = await client.searchRecipes(`ci search ${tag}`);
+    expect(byName.map((r) => r.id)).toContain(recipe.id);
+
+    const byIngredient = await client.searchRecipes(`saffron ${tag}`, {
+      fields: [RecipeSearchField.Ingredients],
+    });
+    expect(byIngredient.map((r) => r.id)).toContain(recipe.id);
+
+    const noteOnly = await client.searchRecipes(`saffron ${tag}`, {
+      fields: [RecipeSearchField.Note],
+    });
+    expect(noteOnly.map((r) => r.id)).not.toContain(recipe.id);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("getRecentRecipes lists newly added recipes first", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Recent ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    expect(recipe.createdAt).toBeGreaterThan(0);
+
+    const recent = await client.getRecentRecipes({ limit: 5 });
+    expect(recent.length).toBeLessThanOrEqual(5);
+    expect(recent[0].id).toBe(recipe.id);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("findRecipesUsingIngredient matches plurals and typos", async () => {
+    const tag = shortId();
+    const recipe = await client.createRecipe({
+      name: `CI Ingredient ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Boneless chicken thighs ${tag}`, quantity: "4" }],
+      preparationSteps: [],
+    });
+
+    const exact = await client.findRecipesUsingIngredient(
+      `chicken thigh ${tag}`,
+    );
+    expect(exact.map((r) => r.id)).toContain(recipe.id);
+
+    const typo = await client.findRecipesUsingIngredient(
+      `chiken thighs ${tag}`,
+    );
+    expect(typo.map((r) => r.id)).toContain(recipe.id);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("recipes expose timers found in their steps", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Timers ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: ["Mix well", "Bake for 25-30 minutes"],
+    });
+
+    expect(recipe.timers).toEqual([
+      { stepIndex: 1, seconds: 1500, label: "25-30 minutes" },
+    ]);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("duplicateRecipe copies a recipe under a new name", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Original ${shortId()} ${dateStamp()}`,
+      ingredients: [{ name: "Flour", quantity: "2 cups" }],
+      preparationSteps: ["Mix"],
+      rating: 4,
+    });
+
+    const copy = await client.duplicateRecipe(recipe.id);
+    expect(copy.id).not.toBe(recipe.id);
+    expect(copy.name).toBe(`${recipe.name} (copy)`);
+    expect(copy.ingredients).toEqual(recipe.ingredients);
+    expect(copy.preparationSteps).toEqual(["Mix"]);
+    expect(copy.rating).toBe(4);
+    expect(copy.kind).toBe(RecipeKind.Standard);
+
+    const rated = await client.rateRecipe(copy.id, 2);
+    expect(rated.rating).toBe(2);
+    expect(rated.ingredients).toEqual(recipe.ingredients);
+
+    await client.deleteRecipe(copy.id);
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("createPhotoRecipe creates a photo-only recipe", async () => {
+    // A 1x1 transparent PNG
+    const page = Buffer.from(
+      "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=",
+      "base64",
+    );
+    const recipe = await client.createPhotoRecipe(
+      `CI Photo ${shortId()} ${dateStamp()}`,
+      [page, page],
+      "CI Cookbook",
+    );
+    expect(recipe.kind).toBe(RecipeKind.Photo);
+    expect(recipe.photoIds).toHaveLength(2);
+    expect(recipe.sourceName).toBe("CI Cookbook");
+
+    const photos = await client.getRecipePhotos(recipe.id);
+    expect(photos).toHaveLength(2);
+
+    await expect(client.createPhotoRecipe("CI Empty", [])).rejects.toThrow();
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("markRecipeCooked sets lastCookedAt", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Cooked ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    expect(recipe.lastCookedAt).toBeUndefined();
+
+    const event = await client.markRecipeCooked(recipe.id, "2024-01-15");
+    expect(event.recipeId).toBe(recipe.id);
+    expect(event.eventType).toBe(MealPlanEventType.Recipe);
+    expect(event.date).toBe("2024-01-15");
+
+    const cooked = await client.getRecipeById(recipe.id);
+    expect(cooked.lastCookedAt).toBe("2024-01-15");
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("getMealPlanCalendar returns the calendar for new events", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    expect(calendar.id).toBeTruthy();
+    expect(Array.isArray(calendar.labels)).toBe(true);
+
+    const title = `CI Meal ${shortId()}`;
+    const event = await client.createMealPlanEvent(
+      calendar.id,
+      "2024-01-16",
+      null,
+      title,
+      calendar.labels[0]?.id,
+    );
+    expect(event.title).toBe(title);
+
+    const fetched = await client.getMealPlanEventById(event.id);
+    expect(fetched.date).toBe("2024-01-16");
+    expect(fetched.title).toBe(title);
+
+    await client.deleteMealPlanEvent(calendar.id, event.id);
+    await expect(client.getMealPlanEventById(event.id)).rejects.toThrow();
+  });
+
+  test("createNoteEvent adds a note-only event", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const text = `Eating out ${shortId()}`;
+    const note = await client.createNoteEvent("2023-09-08", text);
+    expect(note.eventType).toBe(MealPlanEventType.Note);
+    expect(note.title).toBe(text);
+
+    const fetched = await client.getMealPlanEventById(note.id);
+    expect(fetched.eventType).toBe(MealPlanEventType.Note);
+    await expect(client.createNoteEvent("2023-09-08", "  ")).rejects.toThrow();
+
+    await client.deleteMealPlanEvent(calendar.id, note.id);
+  });
+
+  test("createLeftoversEvent plans leftovers of a recipe event", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const recipe = await client.createRecipe({
+      name: `CI Chili ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const dinner = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-09-10",
+      recipe.id,
+    );
+
+    const leftovers = await client.createLeftoversEvent(
+      "2023-09-11",
+      dinner.id,
+    );
+    expect(leftovers.eventType).toBe(MealPlanEventType.Leftovers);
+    expect(leftovers.recipeId).toBe(recipe.id);
+
+    const note = await client.createNoteEvent("2023-09-12", "Eating out");
+    await expect(
+      client.createLeftoversEvent("2023-09-13", note.id),
+    ).rejects.toThrow();
+
+    for (const event of [dinner, leftovers, note]) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("bulkCreateMealPlanEvents creates events in one request", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const dates = ["2023-08-01", "2023-08-02", "2023-08-03"];
+
+    const events = await client.bulkCreateMealPlanEvents(
+      dates.map((date) => ({
+        date,
+        title: `Dinner ${tag}`,
+        details: "from CI",
+      })),
+    );
+    expect(events.map((e) => e.date)).toEqual(dates);
+
+    const fetched = await client.getMealPlanEvents("2023-08-01", "2023-08-03");
+    const created = fetched.filter((e) => e.title === `Dinner ${tag}`);
+    expect(created).toHaveLength(3);
+    expect(created[0].details).toBe("from CI");
+
+    for (const event of events) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await expect(
+      client.bulkCreateMealPlanEvents([{ date: "August 1" }]),
+    ).rejects.toThrow();
+  });
+
+  test("reorderMealPlanEvents sets the order of a day's events", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const dinner = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-02-02",
+      null,
+      `Dinner ${tag}`,
+    );
+    const breakfast = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-02-02",
+      null,
+      `Breakfast ${tag}`,
+    );
+
+    const reordered = await client.reorderMealPlanEvents("2023-02-02", [
+      breakfast.id,
+      dinner.id,
+    ]);
+    expect(reordered.slice(0, 2).map((e) => e.id)).toEqual([
+      breakfast.id,
+      dinner.id,
+    ]);
+
+    const events = await client.getMealPlanEvents("2023-02-02", "2023-02-02");
+    const ids = events.map((e) => e.id);
+    expect(ids.indexOf(breakfast.id)).toBeLessThan(ids.indexOf(dinner.id));
+    await expect(
+      client.reorderMealPlanEvents("2023-02-02", ["missing"]),
+    ).rejects.toThrow();
+
+    await client.deleteMealPlanEvent(calendar.id, dinner.id);
+    await client.deleteMealPlanEvent(calendar.id, breakfast.id);
+  });
+
+  test("copyMealPlanWeek copies a week's events to another week", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const title = `CI Rotation ${shortId()}`;
+    const monday = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-03-06",
+      null,
+      title,
+    );
+    const sunday = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-03-12",
+      null,
+      title,
+    );
+
+    const copies = await client.copyMealPlanWeek("2023-03-06", "2023-04-03");
+    const copied = copies.filter((e) => e.title === title);
+    expect(copied.map((e) => e.date).sort()).toEqual([
+      "2023-04-03",
+      "2023-04-09",
+    ]);
+
+    for (const event of [monday, sunday, ...copies]) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await expect(
+      client.copyMealPlanWeek("March 6", "2023-04-03"),
+    ).rejects.toThrow();
+  });
+
+  test("meal plan templates can be saved and applied to another week", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const title = `CI Template ${shortId()}`;
+    const event = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-05-03",
+      null,
+      title,
+    );
+
+    const template = await client.saveMealPlanTemplate("CI Week", "2023-05-01");
+    expect(template.name).toBe("CI Week");
+    expect(template.events).toContainEqual(
+      expect.objectContaining({ day: 2, title }),
+    );
+    expect(client.getMealPlanTemplates().map((t) => t.id)).toContain(
+      template.id,
+    );
+
+    const applied = await client.applyMealPlanTemplate(
+      template.id,
+      "2023-06-05",
+    );
+    expect(applied.find((e) => e.title === title)?.date).toBe("2023-06-07");
+
+    client.deleteMealPlanTemplate(template.id);
+    expect(client.getMealPlanTemplates().map((t) => t.id)).not.toContain(
+      template.id,
+    );
+    await expect(
+      client.applyMealPlanTemplate(template.id, "2023-06-05"),
+    ).rejects.toThrow();
+
+    for (const e of [event, ...applied]) {
+      await client.deleteMealPlanEvent(calendar.id, e.id);
+    }
+  });
+
+  test("suggestMealPlan plans recipes from a collection, avoiding recent ones", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const recipes = [];
+    for (const name of ["Curry", "Tacos", "Risotto"]) {
+      recipes.push(
+        await client.createRecipe({
+          name: `CI ${name} ${tag}`,
+          ingredients: [],
+          preparationSteps: [],
+        }),
+      );
+    }
+    const collection = await client.createRecipeCollection(`CI Suggest ${tag}`);
+    for (const recipe of recipes) {
+      await client.addRecipeToCollection(collection.id, recipe.id);
+    }
+    const recent = await client.createMealPlanEvent(
+      calendar.id,
+      "2022-01-01",
+      recipes[0].id,
+    );
+
+    const suggested = await client.suggestMealPlan("2022-01-03", {
+      collectionId: collection.id,
+      avoidRecentDays: 7,
+    });
+    const ids = suggested.map((e) => e.recipeId);
+    expect(ids).not.toContain(recipes[0].id);
+    expect(ids).toContain(recipes[1].id);
+    expect(ids).toContain(recipes[2].id);
+
+    for (const event of [recent, ...suggested]) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await client.deleteRecipeCollection(collection.id);
+    for (const recipe of recipes) {
+      await client.deleteRecipe(recipe.id);
+    }
+  });
+
+  test("searchMealPlanEvents finds events by recipe name, most recent first", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const recipe = await client.createRecipe({
+      name: `CI Lasagna ${tag}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const events = await client.bulkCreateMealPlanEvents([
+      { date: "2022-12-01", recipeId: recipe.id },
+      { date: "2022-12-20", recipeId: recipe.id },
+      { date: "2022-12-21", title: `Leftover lasagna ${tag}` },
+    ]);
+
+    const found = await client.searchMealPlanEvents(`lasagna ${tag}`);
+    expect(found.map((e) => e.date)).toEqual([
+      "2022-12-21",
+      "2022-12-20",
+      "2022-12-01",
+    ]);
+
+    const ranged = await client.searchMealPlanEvents(`lasagna ${tag}`, {
+      startDate: "2022-12-10",
+      endDate: "2022-12-20",
+    });
+    expect(ranged.map((e) => e.id)).toEqual([events[1].id]);
+
+    for (const event of events) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("getMealPlanStats counts recipes and finds days without dinner", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const dinner = calendar.labels.find(
+      (l) => l.name.toLowerCase() === "dinner",
+    );
+    const recipe = await client.createRecipe({
+      name: `CI Stats ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const events = await client.bulkCreateMealPlanEvents(
+      ["2022-11-01", "2022-11-03"].map((date) => ({
+        date,
+        recipeId: recipe.id,
+        labelId: dinner?.id,
+      })),
+    );
+
+    const stats = await client.getMealPlanStats("2022-11-01", "2022-11-03");
+    expect(stats.eventCount).toBeGreaterThanOrEqual(2);
+    expect(stats.recipes.find((r) => r.recipeId === recipe.id)).toMatchObject({
+      name: recipe.name,
+      count: 2,
+    });
+    expect(stats.daysWithoutDinner).not.toContain("2022-11-01");
+    expect(stats.daysWithoutDinner).not.toContain("2022-11-03");
+    await expect(
+      client.getMealPlanStats("2022-11-03", "2022-11-01"),
+    ).rejects.toThrow();
+
+    for (const event of events) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("getMealPlanNutrition adds up planned recipes per day", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const oats = await client.createRecipe({
+      name: `CI Oats ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      nutritionalInfo: "Calories: 300 kcal\nProtein: 10 g\nCarbohydrate: 50 g\nFat: 6 g",
+    });
+    const stew = await client.createRecipe({
+      name: `CI Stew ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      nutritionalInfo: "500 calories, 30g protein, 20g carbs, 25g fat",
+    });
+    const plain = await client.createRecipe({
+      name: `CI Plain ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const events = [
+      await client.createMealPlanEvent(calendar.id, "2023-09-04", oats.id),
+      await client.createMealPlanEvent(calendar.id, "2023-09-04", stew.id),
+      await client.createMealPlanEvent(calendar.id, "2023-09-05", plain.id),
+    ];
+
+    const nutrition = await client.getMealPlanNutrition(
+      "2023-09-04",
+      "2023-09-05",
+    );
+    expect(nutrition.days.map((day) => day.date)).toEqual([
+      "2023-09-04",
+      "2023-09-05",
+    ]);
+    expect(nutrition.days[0].recipeCount).toBeGreaterThanOrEqual(2);
+    expect(nutrition.days[0].totals.calories).toBeGreaterThanOrEqual(800);
+    expect(nutrition.days[0].totals.protein).toBeGreaterThanOrEqual(40);
+    expect(nutrition.total.fat).toBeGreaterThanOrEqual(31);
+    expect(nutrition.recipesWithoutNutrition).toContain(plain.id);
+    await expect(
+      client.getMealPlanNutrition("2023-09-05", "2023-09-04"),
+    ).rejects.toThrow();
+
+    for (const event of events) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    for (const recipe of [oats, stew, plain]) {
+      await client.deleteRecipe(recipe.id);
+    }
+  });
+
+  test("renderMealPlanDigest summarizes each day as Markdown or HTML", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const recipe = await client.createRecipe({
+      name: `CI Digest ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      sourceUrl: "https://example.com/digest",
+      prepTime: 20,
+    });
+    const event = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-08-07",
+      recipe.id,
+    );
+
+    const markdown = await client.renderMealPlanDigest(
+      "2023-08-07",
+      "2023-08-08",
+      DigestFormat.Markdown,
+    );
+    expect(markdown).toContain("## Monday, August 7");
+    expect(markdown).toContain(
+      `[${recipe.name}](https://example.com/digest) (prep 20 min)`,
+    );
+    expect(markdown).toContain("## Tuesday, August 8");
+
+    const html = await client.renderMealPlanDigest(
+      "2023-08-07",
+      "2023-08-07",
+      DigestFormat.Html,
+    );
+    expect(html).toContain("<h2>Monday, August 7</h2>");
+    expect(html).toContain(
+      `<a href="https://example.com/digest">${recipe.name}</a>`,
+    );
+    await expect(
+      client.renderMealPlanDigest(
+        "2023-08-08",
+        "2023-08-07",
+        DigestFormat.Html,
+      ),
+    ).rejects.toThrow();
+
+    await client.deleteMealPlanEvent(calendar.id, event.id);
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("meal plan methods read Dates in the client's time zone", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const title = `CI Time zone ${shortId()}`;
+    // 8pm on November 1st in Vancouver, already November 2nd in UTC
+    const evening = new Date("2023-11-02T03:00:00Z");
+
+    client.setTimeZone("America/Vancouver");
+    try {
+      const event = await client.createMealPlanEvent(
+        calendar.id,
+        evening,
+        null,
+        title,
+      );
+      expect(event.date).toBe("2023-11-01");
+
+      const events = await client.getMealPlanEvents(evening, evening);
+      expect(events.map((e) => e.id)).toContain(event.id);
+
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    } finally {
+      client.setTimeZone(null);
+    }
+  });
+
+  test("meal plan dates are normalized to YYYY-MM-DD", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const title = `CI Dates ${shortId()}`;
+
+    client.setTimeZone("UTC");
+    try {
+      const fromMillis = await client.createMealPlanEvent(
+        calendar.id,
+        Date.UTC(2023, 11, 4, 12),
+        null,
+        title,
+      );
+      expect(fromMillis.date).toBe("2023-12-04");
+
+      const fromIso = await client.createMealPlanEvent(
+        calendar.id,
+        "2023-12-05T23:30:00-02:00",
+        null,
+        title,
+      );
+      expect(fromIso.date).toBe("2023-12-06");
+
+      const unpadded = await client.createNoteEvent("2023-12-7", title);
+      expect(unpadded.date).toBe("2023-12-07");
+
+      for (const event of [fromMillis, fromIso, unpadded]) {
+        await client.deleteMealPlanEvent(calendar.id, event.id);
+      }
+    } finally {
+      client.setTimeZone(null);
+    }
+  });
+
+  test("exportMealPlanToIcs builds an iCalendar document", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const recipe = await client.createRecipe({
+      name: `CI Ics ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const event = await client.createMealPlanEvent(
+      calendar.id,
+      "2023-07-14",
+      recipe.id,
+    );
+
+    const ics = await client.exportMealPlanToIcs("2023-07-14", "2023-07-14");
+    expect(ics.startsWith("BEGIN:VCALENDAR\r\n")).toBe(true);
+    expect(ics.trimEnd().endsWith("END:VCALENDAR")).toBe(true);
+    expect(ics).toContain("DTSTART;VALUE=DATE:20230714");
+    expect(ics).toContain(`SUMMARY:${recipe.name}`);
+    expect(ics).toContain(`UID:${event.id}@anylist.com`);
+
+    await client.deleteMealPlanEvent(calendar.id, event.id);
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("fetchIcalendarEvents reads the hosted feed when enabled", async () => {
+    const url = await client.getIcalendarUrl();
+    if (!url) {
+      await expect(client.fetchIcalendarEvents()).rejects.toThrow(
+        "not enabled",
+      );
+      return;
+    }
+
+    const events = await client.fetchIcalendarEvents();
+    expect(Array.isArray(events)).toBe(true);
+    for (const event of events) {
+      expect(event.uid).toBeTruthy();
+      expect(event.start).toMatch(/^\d{4}-\d{2}-\d{2}/);
+    }
+  });
+
+  test("addRecipesToList adds scaled ingredients from several recipes", async () => {
+    const tag = shortId();
+    const soup = await client.createRecipe({
+      name: `CI Soup ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Leek ${tag}`, quantity: "1/2 cup" }],
+      preparationSteps: [],
+    });
+    const salad = await client.createRecipe({
+      name: `CI Salad ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Radish ${tag}`, quantity: "2" }],
+      preparationSteps: [],
+    });
+
+    await client.addRecipesToList(testList.id, [
+      { recipeId: soup.id, scaleFactor: 2 },
+      { recipeId: salad.id },
+    ]);
+
+    const lists = await client.getLists();
+    const items = lists.find((l) => l.id === testList.id)!.items;
+    expect(items.find((i) => i.name === `Leek ${tag}`)?.quantity).toBe("1 cup");
+    expect(items.find((i) => i.name === `Radish ${tag}`)?.quantity).toBe("2");
+
+    await client.deleteRecipe(soup.id);
+    await client.deleteRecipe(salad.id);
+  });
+
+  test("addMealPlanIngredientsToList skips excluded recipes and scales events", async () => {
+    const calendar = await client.getMealPlanCalendar();
+    const tag = shortId();
+    const stew = await client.createRecipe({
+      name: `CI Stew ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Carrot ${tag}`, quantity: "2" }],
+      preparationSteps: [],
+    });
+    const pie = await client.createRecipe({
+      name: `CI Pie ${tag} ${dateStamp()}`,
+      ingredients: [{ name: `Apple ${tag}`, quantity: "3" }],
+      preparationSteps: [],
+    });
+    const events = await client.bulkCreateMealPlanEvents([
+      { date: "2023-10-10", recipeId: stew.id },
+      { date: "2023-10-11", recipeId: pie.id },
+    ]);
+
+    await client.addMealPlanIngredientsToList(
+      testList.id,
+      "2023-10-10",
+      "2023-10-11",
+      {
+        excludeRecipeIds: [pie.id],
+        scaleFactors: { [events[0].id]: 2 },
+      },
+    );
+
+    const lists = await client.getLists();
+    const items = lists.find((l) => l.id === testList.id)!.items;
+    expect(items.find((i) => i.name === `Carrot ${tag}`)?.quantity).toBe("4");
+    expect(items.find((i) => i.name === `Apple ${tag}`)).toBeUndefined();
+
+    for (const event of events) {
+      await client.deleteMealPlanEvent(calendar.id, event.id);
+    }
+    await client.deleteRecipe(stew.id);
+    await client.deleteRecipe(pie.id);
+  });
+
+  test("ingredient section headers round-trip", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Headers ${shortId()} ${dateStamp()}`,
+      ingredients: [
+        { name: "For the sauce", isHeader: true },
+        { name: "Tomatoes", quantity: "2" },
+      ],
+      preparationSteps: [],
+    });
+
+    const fetched = await client.getRecipeById(recipe.id);
+    expect(fetched.ingredients[0]).toMatchObject({
+      name: "For the sauce",
+      isHeader: true,
+    });
+    expect(fetched.ingredients[1]).toMatchObject({
+      name: "Tomatoes",
+      isHeader: false,
+    });
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("structured steps keep their section headers", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Steps ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      steps: [
+        { text: "Make the sauce", isHeader: true },
+        { text: "Simmer for 20 minutes" },
+      ],
+    });
+
+    expect(recipe.preparationSteps).toEqual([
+      "# Make the sauce",
+      "Simmer for 20 minutes",
+    ]);
+    expect(recipe.steps[0]).toEqual({
+      text: "Make the sauce",
+      isHeader: true,
+      timers: [],
+    });
+    expect(recipe.steps[1].isHeader).toBe(false);
+    expect(recipe.steps[1].timers[0].seconds).toBe(1200);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("ingredients link to sub-recipes, which expand when added to a list", async () => {
+    const tag = shortId();
+    const dough = await client.createRecipe({
+      name: `CI Dough ${tag}`,
+      ingredients: [{ name: `Yeast ${tag}`, quantity: "1 tsp" }],
+      preparationSteps: [],
+    });
+    const stock = await client.createRecipe({
+      name: `CI Stock ${tag}`,
+      ingredients: [{ name: `Bones ${tag}` }],
+      preparationSteps: [],
+    });
+    const pizza = await client.createRecipe({
+      name: `CI Pizza ${tag} ${dateStamp()}`,
+      ingredients: [
+        { name: `CI Dough ${tag}`, quantity: "2 batches", note: "see recipe" },
+        { name: `CI Stock ${tag}`, quantity: "2 cups" },
+        { name: `CI Stock ${tag} — see recipe`, quantity: "1 cup" },
+        { name: `Basil ${tag}` },
+      ],
+      preparationSteps: [],
+    });
+
+    try {
+      // Only ingredients marked "see recipe" are linked
+      const fetched = await client.getRecipeById(pizza.id);
+      expect(fetched.ingredients.map((i) => i.recipeId)).toEqual([
+        dough.id,
+        undefined,
+        stock.id,
+        undefined,
+      ]);
+
+      await client.addRecipeToList(pizza.id, testList.id);
+      const lists = await client.getLists();
+      const items = lists.find((l) => l.id === testList.id)!.items;
+      const names = items.map((i) => i.name);
+      expect(items.find((i) => i.name === `Yeast ${tag}`)?.quantity).toBe(
+        "2 tsp",
+      );
+      expect(names).toContain(`Basil ${tag}`);
+      expect(names).not.toContain(`CI Dough ${tag}`);
+      // Measured amounts of a linked recipe aren't read as batches
+      expect(names).toContain(`CI Stock ${tag}`);
+      expect(names).toContain(`CI Stock ${tag} — see recipe`);
+      expect(names).not.toContain(`Bones ${tag}`);
+    } finally {
+      await client.deleteRecipe(pizza.id);
+      await client.deleteRecipe(stock.id);
+      await client.deleteRecipe(dough.id);
+    }
+  });
+
+  test("importRecipesFromMealie reports the recipes it created", async () => {
+    const tag = shortId();
+    const result = await client.importRecipesFromMealie({
+      recipes: [
+        { name: `CI Mealie A ${tag}`, tags: [{ name: `CI Tag ${tag}` }] },
+        { name: `CI Mealie B ${tag}`, recipeCategory: [`CI Tag ${tag}`] },
+      ],
+    });
+
+    try {
+      expect(result.failed).toEqual([]);
+      expect(result.created.map((r) => r.name)).toEqual([
+        `CI Mealie A ${tag}`,
+        `CI Mealie B ${tag}`,
+      ]);
+      const collection = await client.getRecipeCollectionByName(
+        `CI Tag ${tag}`,
+      );
+      expect(collection.recipeIds).toEqual(result.created.map((r) => r.id));
+      await client.deleteRecipeCollection(collection.id);
+    } finally {
+      for (const recipe of result.created) {
+        await client.deleteRecipe(recipe.id);
+      }
+    }
+  });
+
+  test("getRecipesInCollection returns recipes in collection order", async () => {
+    const tag = shortId();
+    const first = await client.createRecipe({
+      name: `CI Collected A ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const second = await client.createRecipe({
+      name: `CI Collected B ${tag} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    const collection = await client.createRecipeCollection(
+      `CI Collection ${tag}`,
+    );
+    await client.addRecipeToCollection(collection.id, second.id);
+    await client.addRecipeToCollection(collection.id, first.id);
+
+    const recipes = await client.getRecipesInCollection(collection.id);
+    expect(recipes.map((r) => r.id)).toEqual([second.id, first.id]);
+
+    const byId = await client.getRecipeCollectionById(collection.id);
+    expect(byId.recipeIds).toEqual([second.id, first.id]);
+    const byName = await client.getRecipeCollectionByName(
+      `CI Collection ${tag}`,
+    );
+    expect(byName.id).toBe(collection.id);
+
+    await client.deleteRecipeCollection(collection.id);
+    await client.deleteRecipe(first.id);
+    await client.deleteRecipe(second.id);
+  });
+
+  test("revertRecipe restores an earlier version", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI History ${shortId()} ${dateStamp()}`,
+      ingredients: [{ name: "Flour", quantity: "1 cup" }],
+      preparationSteps: ["Mix"],
+      rating: 3,
+    });
+    expect(client.getRecipeHistory(recipe.id)).toEqual([]);
+
+    await client.rateRecipe(recipe.id, 5);
+    const history = client.getRecipeHistory(recipe.id);
+    expect(history).toHaveLength(1);
+    expect(history[0].recipe.rating).toBe(3);
+
+    const reverted = await client.revertRecipe(recipe.id, history[0].id);
+    expect(reverted.rating).toBe(3);
+    expect(client.getRecipeHistory(recipe.id)[0].recipe.rating).toBe(5);
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("patchRecipe only changes the given fields", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Patch ${shortId()} ${dateStamp()}`,
+      ingredients: [{ name: "Flour", quantity: "1 cup" }],
+      preparationSteps: ["Mix", "Bake"],
+      servings: "4",
+    });
+
+    const patched = await client.patchRecipe(recipe.id, {
+      name: `${recipe.name} (renamed)`,
+      cookTime: 30,
+    });
+    expect(patched.name).toBe(`${recipe.name} (renamed)`);
+    expect(patched.cookTime).toBe(30);
+    expect(patched.ingredients).toEqual(recipe.ingredients);
+    expect(patched.preparationSteps).toEqual(["Mix", "Bake"]);
+    expect(patched.servings).toBe("4");
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("createRecipe accepts ISO 8601 durations", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI Durations ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      prepTime: "PT20M",
+      cookTime: "PT1H30M",
+    });
+    expect(recipe.prepTime).toBe(20);
+    expect(recipe.cookTime).toBe(90);
+    expect(recipe.totalTime).toBe(110);
+
+    await expect(
+      client.createRecipe({
+        name: `CI Durations ${shortId()}`,
+        ingredients: [],
+        preparationSteps: [],
+        prepTime: "soon",
+      }),
+    ).rejects.toThrow();
+
+    await client.deleteRecipe(recipe.id);
+  });
+
+  test("createRecipe rejects an invalid photoUrl", async () => {
+    await expect(
+      client.createRecipe({
+        name: `CI Photo URL ${shortId()}`,
+        ingredients: [],
+        preparationSteps: [],
+        photoUrl: "not a url",
+      }),
+    ).rejects.toThrow("Invalid URL");
+  });
+
+  test("getRecipes filters by rating and total time", async () => {
+    const quick = await client.createRecipe({
+      name: `CI Quick ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      prepTime: 10,
+      cookTime: 15,
+      rating: 5,
+    });
+    const slow = await client.createRecipe({
+      name: `CI Slow ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+      cookTime: 120,
+      rating: 5,
+    });
+
+    const recipes = await client.getRecipes({ minRating: 4, maxTotalTime: 30 });
+    const ids = recipes.map((r) => r.id);
+    expect(ids).toContain(quick.id);
+    expect(ids).not.toContain(slow.id);
+    expect(recipes.every((r) => (r.rating ?? 0) >= 4)).toBe(true);
+
+    await expect(
+      client.getRecipes({ collectionId: "no-such-collection" }),
+    ).rejects.toThrow();
+
+    await client.deleteRecipe(quick.id);
+    await client.deleteRecipe(slow.id);
+  });
+
+  test("exportRecipe renders Markdown and JSON", async () => {
+    const recipeName = `CI Export ${shortId()} ${dateStamp()}`;
+    const recipe = await client.createRecipe({
+      name: recipeName,
+      ingredients: [{ name: "Flour", quantity: "2 cups" }],
+      preparationSteps: ["Mix"],
+      prepTime: 75,
+    });
+
+    const markdown = await client.exportRecipe(
+      recipe.id,
+      ExportFormat.Markdown,
+    );
+    expect(markdown).toContain(`# ${recipeName}`);
+    expect(markdown).toContain("- 2 cups Flour");
+    expect(markdown).toContain("1. Mix");
+    expect(markdown).toContain("**Prep time:** 1 hr 15 min");
 
-    expect(item!.checked).toBe(true);
+    const json = JSON.parse(
+      await client.exportRecipe(recipe.id, ExportFormat.Json),
+    );
+    expect(json.name).toBe(recipeName);
+    expect(json.ingredients[0].quantity).toBe("2 cups");
+    expect(json.photoUrls).toEqual([]);
+
+    await client.deleteRecipe(recipe.id);
   });
 
-  test("uncheckItem unchecks the item", async () => {
-    const itemId = addedItemIds[0];
-    await client.uncheckItem(testList.id, itemId);
+  test("renderRecipeHtml renders a printable document", async () => {
+    const recipe = await client.createRecipe({
+      name: `CI HTML <${shortId()}> ${dateStamp()}`,
+      ingredients: [{ name: "Flour", quantity: "2 cups" }],
+      preparationSteps: ["Mix"],
+      note: "Family favourite",
+    });
 
-    const lists = await client.getLists();
-    const updatedList = lists.find((l) => l.id === testList.id);
-    const item = updatedList!.items.find((i) => i.id === itemId);
+    const full = await client.renderRecipeHtml(recipe.id);
+    expect(full).toMatch(/^<!DOCTYPE html>/);
+    expect(full).toContain("&lt;");
+    expect(full).toContain("<li>2 cups Flour</li>");
+    expect(full).toContain("Family favourite");
 
-    expect(item!.checked).toBe(false);
+    const compact = await client.renderRecipeHtml(recipe.id, {
+      template: RecipeHtmlTemplate.Compact,
+    });
+    expect(compact).toContain("<li>Mix</li>");
+    expect(compact).not.toContain("Family favourite");
+
+    await client.deleteRecipe(recipe.id);
   });
 
-  test("deleteItem removes item from list", async () => {
-    const itemId = addedItemIds.pop()!;
-    await client.deleteItem(testList.id, itemId);
+  test("getCategoryGroupsForList lists groups usable by createCategory", async () => {
+    const groups = await client.getCategoryGroupsForList(testList.id);
+    expect(Array.isArray(groups)).toBe(true);
+    if (groups.length === 0) return;
 
-    const lists = await client.getLists();
-    const updatedList = lists.find((l) => l.id === testList.id);
-    const deletedItem = updatedList!.items.find((i) => i.id === itemId);
+    const group = groups[0];
+    expect(group.id).toBeTruthy();
+    const indexes = group.categories.map((c) => c.sortIndex);
+    expect(indexes).toEqual([...indexes].sort((a, b) => a - b));
 
-    expect(deletedItem).toBeUndefined();
+    const name = `CI category ${shortId()}`;
+    const category = await client.createCategory(testList.id, group.id, name);
+    try {
+      const updated = await client.getCategoryGroupsForList(testList.id);
+      const categories = updated.find((g) => g.id === group.id)!.categories;
+      expect(categories.some((c) => c.id === category.id)).toBe(true);
+    } finally {
+      await client.deleteCategory(testList.id, category.id);
+    }
+
+    await expect(
+      client.getCategoryGroupsForList("missing-list"),
+    ).rejects.toThrow();
   });
 
-  test("getRecipes returns array", async () => {
-    const recipes = await client.getRecipes();
+  test("createCategoryGroup and deleteCategoryGroup manage groupings", async () => {
+    const name = `CI By Meal ${shortId()}`;
+    const group = await client.createCategoryGroup(testList.id, name);
+    expect(group.name).toBe(name);
+    expect(group.categories).toEqual([]);
 
-    expect(Array.isArray(recipes)).toBe(true);
-    // Recipes may be empty for a new account
-    if (recipes.length > 0) {
-      expect(recipes[0]).toHaveProperty("id");
-      expect(recipes[0]).toHaveProperty("name");
-      expect(recipes[0]).toHaveProperty("ingredients");
-      expect(recipes[0]).toHaveProperty("preparationSteps");
+    const groups = await client.getCategoryGroupsForList(testList.id);
+    expect(groups.some((g) => g.id === group.id)).toBe(true);
+
+    const category = await client.createCategory(
+      testList.id,
+      group.id,
+      "Breakfast",
+    );
+    expect(category.name).toBe("Breakfast");
+
+    await client.deleteCategoryGroup(testList.id, group.id);
+    const remaining = await client.getCategoryGroupsForList(testList.id);
+    expect(remaining.some((g) => g.id === group.id)).toBe(false);
+
+    await expect(
+      client.deleteCategoryGroup(testList.id, group.id),
+    ).rejects.toThrow("not found");
+    const settings = await client.getListSettings(testList.id);
+    if (settings.categoryGroupId) {
+      await expect(
+        client.deleteCategoryGroup(testList.id, settings.categoryGroupId),
+      ).rejects.toThrow("grouped by");
     }
   });
 
-  test("createRecipe creates a new recipe with all options", async () => {
-    const recipeName = `CI Recipe ${shortId()} ${dateStamp()}`;
+  test("addItem categorizes items with the category rules", async () => {
+    const name = `CI rule ${shortId()}`;
+    const rule = client.addCategoryRule({
+      pattern: "CI rule",
+      matchType: CategoryRuleMatch.Prefix,
+      category: "Pantry",
+      listId: testList.id,
+    });
+    try {
+      const item = await client.addItem(testList.id, name);
+      expect(item.category).toBe("Pantry");
+      const explicit = await client.addItemWithDetails(
+        testList.id,
+        `${name} 2`,
+        null,
+        null,
+        "Frozen",
+      );
+      expect(explicit.category).toBe("Frozen");
+      await client.deleteItem(testList.id, item.id);
+      await client.deleteItem(testList.id, explicit.id);
+    } finally {
+      client.removeCategoryRule(rule.id);
+    }
+  });
 
-    const recipe = await client.createRecipe({
-      name: recipeName,
-      ingredients: [
-        { name: "Flour", quantity: "2 cups" },
-        { name: "Salt", quantity: "1 tsp", note: "optional" },
-      ],
-      preparationSteps: [
-        "Mix dry ingredients",
-        "Add water",
-        "Knead until smooth",
+  test("subscribe reports added and checked items", async () => {
+    const events: ChangeEvent[] = [];
+    const subscription = await client.subscribe((e) => events.push(e), {
+      realtime: false,
+      pollIntervalMs: 500,
+    });
+    const item = await client.addItem(
+      testList.id,
+      `CI subscribed ${shortId()}`,
+    );
+    try {
+      expect(subscription.realtime).toBe(false);
+      const seen = async (type: ChangeEventType) => {
+        for (let i = 0; i < 40; i++) {
+          const event = events.find(
+            (e) => e.type === type && e.item?.id === item.id,
+          );
+          if (event) return event;
+          await new Promise((resolve) => setTimeout(resolve, 250));
+        }
+        throw new Error(`No ${type} event for ${item.name}`);
+      };
+
+      const added = await seen(ChangeEventType.ItemAdded);
+      expect(added.listId).toBe(testList.id);
+      expect(added.item?.checked).toBe(false);
+
+      await client.crossOffItem(testList.id, item.id);
+      const checked = await seen(ChangeEventType.ItemChecked);
+      expect(checked.item?.checked).toBe(true);
+    } finally {
+      subscription.unsubscribe();
+      await client.deleteItem(testList.id, item.id);
+    }
+  });
+
+  test("watchList reports each set of changes", async () => {
+    const changes: ListChanges[] = [];
+    const watch = await client.watchList(testList.id, 500, (c) =>
+      changes.push(c),
+    );
+    const item = await client.addItem(testList.id, `CI watched ${shortId()}`);
+    try {
+      const seen = async (match: (c: ListChanges) => boolean) => {
+        for (let i = 0; i < 40; i++) {
+          const found = changes.find(match);
+          if (found) return found;
+          await new Promise((resolve) => setTimeout(resolve, 250));
+        }
+        throw new Error(`No matching changes for ${item.name}`);
+      };
+
+      const added = await seen((c) => c.added.some((i) => i.id === item.id));
+      expect(added.listId).toBe(testList.id);
+      expect(added.oldName).toBeUndefined();
+
+      await client.crossOffItem(testList.id, item.id);
+      const updated = await seen((c) =>
+        c.updated.some((i) => i.id === item.id && i.checked),
+      );
+      expect(updated.added).toEqual([]);
+
+      await client.deleteItem(testList.id, item.id);
+      await seen((c) => c.removed.some((i) => i.id === item.id));
+      expect(
+        changes.every(
+          (c) => c.added.length + c.removed.length + c.updated.length > 0,
+        ),
+      ).toBe(true);
+    } finally {
+      watch.unsubscribe();
+      await client.deleteItem(testList.id, item.id).catch(() => {});
+    }
+  });
+
+  test("getChangesSince returns only what changed since the cursor", async () => {
+    const { cursor } = await client.getChangesSince();
+
+    const unchanged = await client.getChangesSince(cursor);
+    expect(unchanged.lists.some((l) => l.id === testList.id)).toBe(false);
+    expect(unchanged.deletedListIds).toEqual([]);
+
+    const item = await client.addItem(testList.id, `CI delta ${shortId()}`);
+    try {
+      const changes = await client.getChangesSince(unchanged.cursor);
+      const list = changes.lists.find((l) => l.id === testList.id);
+      expect(list?.items.some((i) => i.id === item.id)).toBe(true);
+
+      const recent = await client.getChangesSince(Date.now() - 60_000);
+      expect(recent.lists.some((l) => l.id === testList.id)).toBe(true);
+      expect(recent.deletedListIds).toEqual([]);
+    } finally {
+      await client.deleteItem(testList.id, item.id);
+    }
+  });
+
+  test("cached lists are reused until invalidated", async () => {
+    client.setCacheOptions({ listsTtlMs: 60_000 });
+    const other = AnyListClient.fromTokens(client.getTokens());
+    const name = `CI cached ${shortId()}`;
+    let listId: string | undefined;
+    try {
+      await client.getLists();
+      listId = (await other.createList(name)).id;
+
+      const cached = await client.getLists();
+      expect(cached.some((l) => l.id === listId)).toBe(false);
+
+      client.invalidateCache(CacheEntity.Lists);
+      const fresh = await client.getLists();
+      expect(fresh.some((l) => l.id === listId)).toBe(true);
+    } finally {
+      client.setCacheOptions({});
+      if (listId) await client.deleteList(listId);
+    }
+  });
+
+  test("the client's own changes drop cached lists", async () => {
+    client.setCacheOptions({ listsTtlMs: 60_000 });
+    const name = `CI own change ${shortId()}`;
+    let listId: string | undefined;
+    try {
+      await client.getLists();
+      listId = (await client.createList(name)).id;
+      expect((await client.getLists()).some((l) => l.id === listId)).toBe(true);
+
+      const item = await client.addItem(listId, "Milk");
+      const lists = await client.getLists();
+      const list = lists.find((l) => l.id === listId);
+      expect(list?.items.map((i) => i.id)).toEqual([item.id]);
+    } finally {
+      client.setCacheOptions({});
+      if (listId) await client.deleteList(listId);
+    }
+  });
+
+  test("flushQueue sends nothing when nothing is queued", async () => {
+    const dir = mkdtempSync(join(tmpdir(), "anylist-queue-"));
+    const events: QueueEvent[] = [];
+    try {
+      client.setOfflineQueue(join(dir, "queue.json"), (e) => events.push(e));
+      const item = await client.addItem(testList.id, `CI online ${shortId()}`);
+      await client.crossOffItem(testList.id, item.id);
+      await client.deleteItem(testList.id, item.id);
+
+      expect(await client.flushQueue()).toBe(0);
+      expect(client.getQueuedOperations()).toEqual([]);
+      expect(events.some((e) => e.type === QueueEventType.Queued)).toBe(false);
+    } finally {
+      client.setOfflineQueue(null);
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  test("flushQueue asks the resolver about changes to deleted items", async () => {
+    const dir = mkdtempSync(join(tmpdir(), "anylist-queue-"));
+    const path = join(dir, "queue.json");
+    const conflicts: QueueConflict[] = [];
+    const events: QueueEvent[] = [];
+    try {
+      const queued = {
+        id: `op-${shortId()}`,
+        change: QueuedChange.Check,
+        description: "Cross off item",
+        listId: testList.id,
+        itemId: `missing-${shortId()}`,
+        queuedAt: Date.now(),
+      };
+      writeFileSync(
+        path,
+        JSON.stringify([
+          { ...queued, endpoint: "data/shopping-lists/update", body: "" },
+        ]),
+      );
+      client.setOfflineQueue(path, (e) => events.push(e));
+      client.setConflictResolver((conflict) => {
+        conflicts.push(conflict);
+        return ConflictResolution.KeepRemote;
+      });
+
+      expect(await client.flushQueue()).toBe(0);
+      expect(conflicts.map((c) => c.type)).toEqual([ConflictType.ItemDeleted]);
+      expect(conflicts[0].operation.id).toBe(queued.id);
+      expect(events.map((e) => e.type)).toEqual([QueueEventType.Discarded]);
+      expect(client.getQueuedOperations()).toEqual([]);
+    } finally {
+      client.setConflictResolver(null);
+      client.setOfflineQueue(null);
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  test("setOptimisticUpdates shows changes before AnyList confirms them", async () => {
+    const events: OptimisticEvent[] = [];
+    client.setOptimisticUpdates(true, (e) => events.push(e));
+    let itemId: string | undefined;
+    try {
+      const item = await client.addItem(
+        testList.id,
+        `CI optimistic ${shortId()}`,
+      );
+      itemId = item.id;
+      await client.crossOffItem(testList.id, item.id);
+      const local = await client.getListById(testList.id);
+      expect(local.items.find((i) => i.id === item.id)?.checked).toBe(true);
+
+      for (let i = 0; i < 40 && events.length < 2; i++) {
+        await new Promise((resolve) => setTimeout(resolve, 250));
+      }
+      expect(events.map((e) => e.type)).toEqual([
+        OptimisticEventType.Confirmed,
+        OptimisticEventType.Confirmed,
+      ]);
+      expect(events[0].operation.itemId).toBe(item.id);
+
+      const remote = await client.getListById(testList.id);
+      expect(remote.items.find((i) => i.id === item.id)?.checked).toBe(true);
+    } finally {
+      client.setOptimisticUpdates(false);
+      if (itemId) await client.deleteItem(testList.id, itemId);
+    }
+  });
+
+  test("optimistic changes to renamed items go to the resolver", async () => {
+    const tag = shortId();
+    const item = await client.addItem(testList.id, `CI before ${tag}`);
+    const events: OptimisticEvent[] = [];
+    const conflicts: QueueConflict[] = [];
+    client.setConflictResolver((conflict) => {
+      conflicts.push(conflict);
+      return ConflictResolution.KeepRemote;
+    });
+    client.setOptimisticUpdates(true, (e) => events.push(e));
+    try {
+      await client.getLists();
+      await client.patchItem(testList.id, item.id, { name: `CI after ${tag}` });
+      await client.crossOffItem(testList.id, item.id);
+
+      for (let i = 0; i < 40 && events.length < 1; i++) {
+        await new Promise((resolve) => setTimeout(resolve, 250));
+      }
+      expect(conflicts.map((c) => c.type)).toEqual([ConflictType.ItemRenamed]);
+      expect(conflicts[0].remoteItem?.name).toBe(`CI after ${tag}`);
+      expect(events.map((e) => e.type)).toEqual([
+        OptimisticEventType.RolledBack,
+      ]);
+      const list = await client.getListById(testList.id);
+      expect(list.items.find((i) => i.id === item.id)?.checked).toBe(false);
+    } finally {
+      client.setOptimisticUpdates(false);
+      client.setConflictResolver(null);
+      await client.deleteItem(testList.id, item.id);
+    }
+  });
+
+  test("concurrent changes to a list all land", async () => {
+    const tag = shortId();
+    const names = ["a", "b", "c", "d", "e"].map((n) => `CI ${n} ${tag}`);
+    const items = await Promise.all(
+      names.map((name) => client.addItem(testList.id, name)),
+    );
+    try {
+      await Promise.all(
+        items.map((item) => client.crossOffItem(testList.id, item.id)),
+      );
+      const list = await client.getListById(testList.id);
+      const ours = list.items.filter((i) => names.includes(i.name));
+      expect(ours).toHaveLength(names.length);
+      expect(ours.every((i) => i.checked)).toBe(true);
+    } finally {
+      await client.bulkDeleteItems(testList.id, items.map((i) => i.id));
+    }
+  });
+
+  test("batch commits all of its changes or none of them", async () => {
+    const tag = shortId();
+    const batch = client.batch();
+    const flour = batch.addItem(testList.id, `CI flour ${tag}`);
+    const soap = batch.addItem(testList.id, `CI soap ${tag}`, "2");
+    try {
+      batch.setCategory(testList.id, flour, "baking");
+      batch.crossOffItem(testList.id, soap);
+      batch.crossOffItem(testList.id, "missing-item");
+      await expect(batch.commit()).rejects.toThrow("not found");
+      expect(batch.size).toBe(5);
+      let list = await client.getListById(testList.id);
+      expect(list.items.some((i) => i.id === flour)).toBe(false);
+
+      const retry = client.batch();
+      const milk = retry.addItem(
+        testList.id,
+        `CI milk ${tag}`,
+        null,
+        null,
+        "dairy",
+      );
+      retry.crossOffItem(testList.id, milk);
+      expect(await retry.commit()).toBe(2);
+      expect(retry.size).toBe(0);
+      list = await client.getListById(testList.id);
+      const item = list.items.find((i) => i.id === milk);
+      expect(item).toMatchObject({ category: "dairy", checked: true });
+      await client.deleteItem(testList.id, milk);
+    } finally {
+      const list = await client.getListById(testList.id);
+      const leftover = list.items.filter((i) => [flour, soap].includes(i.id));
+      await client.bulkDeleteItems(testList.id, leftover.map((i) => i.id));
+    }
+  });
+
+  test("bulkCategorizeItems sets categories in one call", async () => {
+    const tag = shortId();
+    const flour = await client.addItem(testList.id, `CI flour ${tag}`);
+    const soap = await client.addItem(testList.id, `CI soap ${tag}`);
+    try {
+      const updated = await client.bulkCategorizeItems(testList.id, [
+        { itemId: flour.id, category: "baking" },
+        { itemId: soap.id, category: "household" },
+      ]);
+      expect(updated.map((i) => i.category)).toEqual(["baking", "household"]);
+
+      const list = await client.getListById(testList.id);
+      const byId = new Map(list.items.map((i) => [i.id, i]));
+      expect(byId.get(flour.id)?.category).toBe("baking");
+      expect(byId.get(soap.id)?.category).toBe("household");
+
+      await expect(
+        client.bulkCategorizeItems(testList.id, [
+          { itemId: flour.id, category: "pantry" },
+          { itemId: "missing-item", category: "pantry" },
+        ]),
+      ).rejects.toThrow("not found");
+      expect(await client.bulkCategorizeItems(testList.id, [])).toEqual([]);
+    } finally {
+      await client.bulkDeleteItems(testList.id, [flour.id, soap.id]);
+    }
+  });
+
+  test("ensureStore reuses stores whatever their case", async () => {
+    const name = `CI Costco ${shortId()}`;
+    const created = await client.ensureStore(testList.id, name);
+    try {
+      expect(created.name).toBe(name);
+      for (const variant of [name.toLowerCase(), ` ${name.toUpperCase()}`]) {
+        const again = await client.ensureStore(testList.id, variant);
+        expect(again.id).toBe(created.id);
+      }
+
+      const stores = await client.getStoresForList(testList.id);
+      expect(
+        stores.filter((s) => s.name.toLowerCase() === name.toLowerCase()),
+      ).toHaveLength(1);
+    } finally {
+      await client.deleteStore(testList.id, created.id);
+    }
+  });
+
+  test("getItemsByStore lists the items to buy at a store", async () => {
+    const store = await client.createStore(
+      testList.id,
+      `CI store ${shortId()}`,
+    );
+    const item = await client.addItem(testList.id, `CI anywhere ${shortId()}`);
+    const checked = await client.addItem(testList.id, `CI bought ${shortId()}`);
+    await client.crossOffItem(testList.id, checked.id);
+    try {
+      const items = await client.getItemsByStore(testList.id, store.id);
+      expect(items.some((i) => i.id === item.id)).toBe(true);
+      expect(items.some((i) => i.id === checked.id)).toBe(false);
+      expect(items.every((i) => !i.checked)).toBe(true);
+
+      const withChecked = await client.getItemsByStore(testList.id, store.id, {
+        includeChecked: true,
+      });
+      expect(withChecked.some((i) => i.id === checked.id)).toBe(true);
+
+      const assigned = await client.getItemsByStore(testList.id, store.id, {
+        includeUnassigned: false,
+      });
+      expect(assigned.some((i) => i.id === item.id)).toBe(false);
+
+      await expect(
+        client.getItemsByStore(testList.id, "missing-store"),
+      ).rejects.toThrow("not found");
+    } finally {
+      await client.deleteItem(testList.id, item.id);
+      await client.deleteItem(testList.id, checked.id);
+      await client.deleteStore(testList.id, store.id);
+    }
+  });
+
+  test("ensureCategory reuses categories whatever their case", async () => {
+    const groups = await client.getCategoryGroupsForList(testList.id);
+    if (groups.length === 0) return;
+
+    const group = groups[0];
+    const name = `CI Snacks ${shortId()}`;
+    const created = await client.ensureCategory(testList.id, group.id, name);
+    try {
+      expect(created.name).toBe(name);
+      const again = await client.ensureCategory(
+        testList.id,
+        group.id,
+        `  ${name.toUpperCase()} `,
+      );
+      expect(again.id).toBe(created.id);
+
+      const categories = (await client.getCategoryGroupsForList(testList.id))
+        .find((g) => g.id === group.id)!
+        .categories.filter((c) => c.name.toLowerCase() === name.toLowerCase());
+      expect(categories).toHaveLength(1);
+    } finally {
+      await client.deleteCategory(testList.id, created.id);
+    }
+  });
+
+  test("setActiveCategoryGroup switches a list's grouping", async () => {
+    const previous = await client.getActiveCategoryGroup(testList.id);
+    const group = await client.createCategoryGroup(
+      testList.id,
+      `CI By Recipe ${shortId()}`,
+    );
+    try {
+      const active = await client.setActiveCategoryGroup(testList.id, group.id);
+      expect(active.id).toBe(group.id);
+      expect((await client.getActiveCategoryGroup(testList.id))?.id).toBe(
+        group.id,
+      );
+      expect((await client.getListSettings(testList.id)).categoryGroupId).toBe(
+        group.id,
+      );
+
+      await expect(
+        client.setActiveCategoryGroup(testList.id, "missing-group"),
+      ).rejects.toThrow("not found");
+    } finally {
+      if (previous) {
+        await client.setActiveCategoryGroup(testList.id, previous.id);
+        await client.deleteCategoryGroup(testList.id, group.id);
+      }
+    }
+  });
+
+  test("renameFavouritesList renames a favourites list", async () => {
+    const lists = await client.getFavouritesLists();
+    const favourites = lists.find((l) => l.shoppingListId === testList.id);
+    // New lists don't always get a favourites list right away
+    if (!favourites) return;
+
+    const newName = `${favourites.name} (renamed)`;
+    try {
+      const renamed = await client.renameFavouritesList(
+        favourites.id,
+        newName,
+      );
+      expect(renamed.id).toBe(favourites.id);
+      expect(renamed.name).toBe(newName);
+
+      const updated = await client.getFavouritesLists();
+      expect(updated.find((l) => l.id === favourites.id)?.name).toBe(newName);
+    } finally {
+      await client.renameFavouritesList(favourites.id, favourites.name);
+    }
+  });
+
+  test("renameFavouritesList rejects unknown lists", async () => {
+    await expect(
+      client.renameFavouritesList("missing-favourites-list", "Name"),
+    ).rejects.toThrow();
+  });
+
+  test("updateFavourite changes only the supplied fields", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    const listId = lists[0].id;
+    const favourite = await client.addFavouriteToList(
+      listId,
+      `CI favourite ${shortId()}`,
+    );
+    try {
+      const updated = await client.updateFavourite(listId, favourite.id, {
+        name: `${favourite.name} (edited)`,
+        quantity: "2",
+      });
+      expect(updated.id).toBe(favourite.id);
+      expect(updated.name).toBe(`${favourite.name} (edited)`);
+      expect(updated.quantity).toBe("2");
+      expect(updated.category).toBe(favourite.category);
+
+      const refreshed = (await client.getFavouritesLists())
+        .find((l) => l.id === listId)
+        ?.items.find((f) => f.id === favourite.id);
+      expect(refreshed?.name).toBe(`${favourite.name} (edited)`);
+      expect(refreshed?.quantity).toBe("2");
+    } finally {
+      await client.removeFavourite(listId, favourite.id);
+    }
+  });
+
+  test("addFavourites adds every favourite in one call", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    const listId = lists[0].id;
+    const prefix = `CI pantry ${shortId()}`;
+    const added = await client.addFavourites(listId, [
+      { name: `${prefix} rice`, quantity: "2 kg" },
+      { name: `${prefix} lentils`, details: "red" },
+      { name: `${prefix} oats`, category: "Breakfast" },
+    ]);
+    try {
+      expect(added.map((f) => f.name)).toEqual([
+        `${prefix} rice`,
+        `${prefix} lentils`,
+        `${prefix} oats`,
+      ]);
+      expect(added[0].quantity).toBe("2 kg");
+      expect(added[1].details).toBe("red");
+
+      const items = (await client.getFavouritesLists()).find(
+        (l) => l.id === listId,
+      )!.items;
+      for (const favourite of added) {
+        expect(items.some((f) => f.id === favourite.id)).toBe(true);
+      }
+    } finally {
+      for (const favourite of added) {
+        await client.removeFavourite(listId, favourite.id);
+      }
+    }
+  });
+
+  test("reorderFavourites sets the order of a favourites list", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    const listId = lists[0].id;
+    const prefix = `CI aisle ${shortId()}`;
+    const [bread, milk] = await client.addFavourites(listId, [
+      { name: `${prefix} bread` },
+      { name: `${prefix} milk` },
+    ]);
+    try {
+      const reordered = await client.reorderFavourites(listId, [
+        milk.id,
+        bread.id,
+      ]);
+      expect(reordered.slice(0, 2).map((f) => f.id)).toEqual([
+        milk.id,
+        bread.id,
+      ]);
+      expect(reordered.map((f) => f.sortIndex)).toEqual(
+        reordered.map((_, i) => i),
+      );
+
+      const items = (await client.getFavouritesLists()).find(
+        (l) => l.id === listId,
+      )!.items;
+      expect(items.slice(0, 2).map((f) => f.id)).toEqual([milk.id, bread.id]);
+
+      await expect(
+        client.reorderFavourites(listId, ["missing-favourite"]),
+      ).rejects.toThrow();
+    } finally {
+      await client.removeFavourite(listId, bread.id);
+      await client.removeFavourite(listId, milk.id);
+    }
+  });
+
+  test("promoteCheckedItemsToFavourites adds new crossed off items", async () => {
+    const favourites = (await client.getFavouritesLists()).find(
+      (l) => l.shoppingListId === testList.id,
+    );
+    if (!favourites) {
+      await expect(
+        client.promoteCheckedItemsToFavourites(testList.id),
+      ).rejects.toThrow("No favourites list");
+      return;
+    }
+
+    const name = `CI promote ${shortId()}`;
+    const item = await client.addItem(testList.id, name);
+    await client.crossOffItem(testList.id, item.id);
+    const promoted = await client.promoteCheckedItemsToFavourites(testList.id);
+    try {
+      const favourite = promoted.find((f) => f.name === name);
+      expect(favourite).toBeDefined();
+      expect(favourite!.listId).toBe(favourites.id);
+
+      // Already a favourite now, so it isn't added twice
+      const again = await client.promoteCheckedItemsToFavourites(testList.id);
+      expect(again.some((f) => f.name === name)).toBe(false);
+    } finally {
+      for (const favourite of promoted) {
+        await client.removeFavourite(favourites.id, favourite.id);
+      }
+      await client.deleteItem(testList.id, item.id);
+    }
+  });
+
+  test("getFavouriteStats counts favourites added to a shopping list", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    const listId = lists[0].id;
+    const prefix = `CI staple ${shortId()}`;
+    const [staple, oneOff] = await client.addFavourites(listId, [
+      { name: `${prefix} eggs` },
+      { name: `${prefix} saffron` },
+    ]);
+    const itemIds: string[] = [];
+    try {
+      for (const favourite of [staple, staple, oneOff]) {
+        const item = await client.addFavouriteToShoppingList(
+          listId,
+          favourite.id,
+          testList.id,
+        );
+        itemIds.push(item.id);
+      }
+
+      const stats = await client.getFavouriteStats(listId);
+      expect(stats[0].favouriteId).toBe(staple.id);
+      expect(stats[0].timesAdded).toBe(2);
+      expect(stats[0].lastAddedAt).toBeGreaterThan(0);
+      const saffron = stats.find((s) => s.favouriteId === oneOff.id);
+      expect(saffron?.timesAdded).toBe(1);
+    } finally {
+      for (const itemId of itemIds) {
+        await client.deleteItem(testList.id, itemId);
+      }
+      await client.removeFavourite(listId, staple.id);
+      await client.removeFavourite(listId, oneOff.id);
+    }
+  });
+
+  test("addAllFavouritesToShoppingList can skip items already listed", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    const listId = lists[0].id;
+    const prefix = `CI weekly ${shortId()}`;
+    const favourites = await client.addFavourites(listId, [
+      { name: `${prefix} apples`, quantity: "6" },
+      { name: `${prefix} pears` },
+      { name: `${prefix} plums` },
+    ]);
+    const listed = await client.addItem(testList.id, `${prefix} pears`);
+    const crossedOff = await client.addItem(testList.id, `${prefix} plums`);
+    await client.crossOffItem(testList.id, crossedOff.id);
+    const itemIds = [listed.id, crossedOff.id];
+    try {
+      const items = await client.addAllFavouritesToShoppingList(
+        listId,
+        testList.id,
+        { skipExisting: true },
+      );
+      itemIds.push(...items.map((i) => i.id));
+
+      const apples = items.find((i) => i.name === `${prefix} apples`);
+      expect(apples?.quantity).toBe("6");
+      expect(items.some((i) => i.id === listed.id)).toBe(false);
+      const plums = items.find((i) => i.id === crossedOff.id);
+      expect(plums?.checked).toBe(false);
+
+      const list = await client.getListById(testList.id);
+      const ours = list.items.filter((i) => i.name.startsWith(prefix));
+      expect(ours.map((i) => i.name).sort()).toEqual([
+        `${prefix} apples`,
+        `${prefix} pears`,
+        `${prefix} plums`,
+      ]);
+      expect(ours.every((i) => !i.checked)).toBe(true);
+    } finally {
+      for (const itemId of new Set(itemIds)) {
+        await client.deleteItem(testList.id, itemId);
+      }
+      for (const favourite of favourites) {
+        await client.removeFavourite(listId, favourite.id);
+      }
+    }
+  });
+
+  test("updateFavourite rejects unknown favourites", async () => {
+    const lists = await client.getFavouritesLists();
+    if (lists.length === 0) return;
+
+    await expect(
+      client.updateFavourite(lists[0].id, "missing-favourite", { name: "X" }),
+    ).rejects.toThrow();
+  });
+
+  test("restoreFromSnapshot recreates lists and skips ones that exist", async () => {
+    const exported = JSON.parse(await client.exportSnapshot());
+    expect(exported.version).toBe(1);
+    expect(exported.lists.some((l: List) => l.id === testList.id)).toBe(true);
+
+    const name = `CI restore ${shortId()} ${dateStamp()}`;
+    const snapshot = JSON.stringify({
+      version: 1,
+      lists: [
+        {
+          id: "old-list",
+          name,
+          items: [
+            { id: "old-milk", name: "Milk", checked: false, note: "" },
+            { id: "old-eggs", name: "Eggs", checked: true, note: "dozen" },
+          ],
+        },
       ],
-      note: "Test recipe note",
-      sourceName: "CI Test",
-      sourceUrl: "https://example.com/recipe",
-      servings: "4",
-      prepTime: 15,
-      cookTime: 30,
-      rating: 5,
     });
+    let listId: string | undefined;
+    try {
+      const first = await client.restoreFromSnapshot(snapshot);
+      const list = first.created.find((e) => e.type === SnapshotEntity.List);
+      listId = list?.newId;
+      expect(list?.oldId).toBe("old-list");
+      expect(first.created.filter((e) => e.listId === listId)).toHaveLength(2);
+      expect(first.skipped).toEqual([]);
 
-    expect(recipe).toHaveProperty("id");
-    expect(recipe.name).toBe(recipeName);
-    expect(recipe.ingredients).toHaveLength(2);
-    expect(recipe.ingredients[0].name).toBe("Flour");
-    expect(recipe.ingredients[0].quantity).toBe("2 cups");
-    expect(recipe.preparationSteps).toHaveLength(3);
-    expect(recipe.note).toBe("Test recipe note");
-    expect(recipe.sourceName).toBe("CI Test");
-    expect(recipe.sourceUrl).toBe("https://example.com/recipe");
-    expect(recipe.servings).toBe("4");
-    expect(recipe.prepTime).toBe(15);
-    expect(recipe.cookTime).toBe(30);
-    expect(recipe.rating).toBe(5);
+      const restored = await client.getListById(listId!);
+      const eggs = restored.items.find((i) => i.name === "Eggs");
+      expect(eggs?.checked).toBe(true);
+      expect(eggs?.note).toBe("dozen");
 
-    // Verify we can fetch it back
-    const fetchedRecipe = await client.getRecipeById(recipe.id);
-    expect(fetchedRecipe.name).toBe(recipeName);
-    expect(fetchedRecipe.note).toBe("Test recipe note");
+      const again = await client.restoreFromSnapshot(snapshot, {
+        mode: RestoreMode.Merge,
+      });
+      expect(again.created).toEqual([]);
+      expect(again.skipped.map((e) => e.newId)).toContain(eggs?.id);
+    } finally {
+      if (listId) await client.deleteList(listId);
+    }
+  });
+
+  test("startAutoRefresh fetches straight away and reports it", async () => {
+    const events: RefreshEvent[] = [];
+    const refreshed = new Promise<void>((resolve) => {
+      client.startAutoRefresh({ lists: 60_000 }, (event) => {
+        events.push(event);
+        resolve();
+      });
+    });
+    try {
+      await refreshed;
+      expect(events[0]).toMatchObject({
+        type: RefreshEventType.Refreshed,
+        entity: CacheEntity.Lists,
+      });
+      const lists = await client.getLists();
+      expect(lists.some((l) => l.id === testList.id)).toBe(true);
+    } finally {
+      client.stopAutoRefresh();
+    }
+  });
+
+  test("concurrent reads share one fetch", async () => {
+    const [a, b, c] = await Promise.all([
+      client.getLists(),
+      client.getLists(),
+      client.getListById(testList.id),
+    ]);
+    expect(b).toEqual(a);
+    expect(a.find((l) => l.id === testList.id)).toEqual(c);
+  });
+
+  test("getListItems pages through a list's items", async () => {
+    const tag = shortId();
+    const added = [];
+    for (const name of ["apples", "pears", "plums"]) {
+      added.push(await client.addItem(testList.id, `CI ${name} ${tag}`));
+    }
+    try {
+      const list = await client.getListById(testList.id);
+      const paged = [];
+      let offset: number | undefined = 0;
+      while (offset !== undefined) {
+        const page = await client.getListItems(testList.id, {
+          offset,
+          limit: 2,
+        });
+        expect(page.total).toBe(list.items.length);
+        expect(page.items.length).toBeLessThanOrEqual(2);
+        paged.push(...page.items);
+        offset = page.nextOffset;
+      }
+      expect(paged.map((i) => i.id)).toEqual(list.items.map((i) => i.id));
+
+      const past = await client.getListItems(testList.id, { offset: 10000 });
+      expect(past.items).toEqual([]);
+      expect(past.nextOffset).toBeUndefined();
+    } finally {
+      for (const item of added) {
+        await client.deleteItem(testList.id, item.id);
+      }
+    }
+  });
+
+  test("batches don't report drift when nothing else changed", async () => {
+    const events: DriftEvent[] = [];
+    client.setDriftDetection((event) => events.push(event));
+    const batch = client.batch();
+    const item = batch.addItem(testList.id, `CI drift ${shortId()}`);
+    try {
+      batch.crossOffItem(testList.id, item);
+      expect(await batch.commit()).toBe(2);
+      await new Promise((resolve) => setTimeout(resolve, 100));
+      expect(events).toEqual([]);
+    } finally {
+      client.setDriftDetection(null);
+      await client.deleteItem(testList.id, item);
+    }
+  });
+
+  test("receipts match changes to their journal entries", async () => {
+    const dir = mkdtempSync(join(tmpdir(), "anylist-journal-"));
+    const item = await client.addItem(testList.id, `CI receipt ${shortId()}`);
+    try {
+      client.setJournalFile(join(dir, "journal.jsonl"));
+      const before = Date.now();
+      const receipt = await client.crossOffItem(testList.id, item.id);
+      expect(receipt.operation).toBe("crossOffItem");
+      expect(receipt.completedAt).toBeGreaterThanOrEqual(before);
+
+      const entry = client.getJournal().find((e) => e.id === receipt.id);
+      expect(entry).toMatchObject({
+        operation: "crossOffItem",
+        entityIds: [testList.id, item.id],
+        succeeded: true,
+      });
+    } finally {
+      client.setJournalFile(null);
+      rmSync(dir, { recursive: true, force: true });
+      await client.deleteItem(testList.id, item.id);
+    }
+  });
+
+  test("a change made of other changes has one journal entry", async () => {
+    const dir = mkdtempSync(join(tmpdir(), "anylist-journal-"));
+    const recipe = await client.createRecipe({
+      name: `CI Journaled ${shortId()} ${dateStamp()}`,
+      ingredients: [],
+      preparationSteps: [],
+    });
+    let eventId: string | undefined;
+    try {
+      client.setJournalFile(join(dir, "journal.jsonl"));
+      const receipt = await client.addRecipeToList(recipe.id, testList.id);
+      eventId = (await client.markRecipeCooked(recipe.id)).id;
+
+      expect(client.getJournal().map((e) => e.operation)).toEqual([
+        "addRecipeToList",
+        "markRecipeCooked",
+      ]);
+      expect(client.getJournal()[0].id).toBe(receipt.id);
+    } finally {
+      client.setJournalFile(null);
+      rmSync(dir, { recursive: true, force: true });
+      if (eventId) {
+        const calendar = await client.getMealPlanCalendar();
+        await client.deleteMealPlanEvent(calendar.id, eventId);
+      }
+      await client.deleteRecipe(recipe.id);
+    }
+  });
+
+  test("request returns AnyList's raw response", async () => {
+    const response = await client.request("POST", "data/user-data/get");
+    expect(Buffer.isBuffer(response)).toBe(true);
+    expect(response.includes(testList.id)).toBe(true);
+  });
+
+  test("getSyncStatus reports fetches and realtime connections", async () => {
+    const before = Date.now();
+    await client.getLists();
+    const subscription = await client.subscribe(() => {});
+    try {
+      const status = client.getSyncStatus();
+      expect(status.listsFetchedAt).toBeGreaterThanOrEqual(before);
+      expect(status.realtimeConnected).toBe(subscription.realtime);
+    } finally {
+      subscription.unsubscribe();
+    }
   });
 
   test("deleteList removes a list", async () => {
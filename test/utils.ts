@@ -1,3 +1,5 @@
+import { gzipSync } from "node:zlib";
+
 /** Generate a short random ID for test identification */
 export function shortId(): string {
   return Math.random().toString(36).substring(2, 8);
@@ -13,3 +15,59 @@ export function testListName(runtime: string, target?: string): string {
   const platform = target || "unknown";
   return `CI ${runtime}-${platform} ${shortId()} ${dateStamp()}`;
 }
+
+/** CRC-32 of `data`, as zip archives store it */
+function crc32(data: Buffer): number {
+  let crc = 0xffffffff;
+  for (const byte of data) {
+    crc ^= byte;
+    for (let bit = 0; bit < 8; bit++) {
+      crc = crc & 1 ? (crc >>> 1) ^ 0xedb88320 : crc >>> 1;
+    }
+  }
+  return (crc ^ 0xffffffff) >>> 0;
+}
+
+/** Build a `.paprikarecipes` export: a zip of gzipped recipe JSON files */
+export function paprikaExport(recipes: object[]): Buffer {
+  const files: Buffer[] = [];
+  const directory: Buffer[] = [];
+  let offset = 0;
+  recipes.forEach((recipe, index) => {
+    const name = Buffer.from(`recipe-${index}.paprikarecipe`);
+    const data = gzipSync(JSON.stringify(recipe));
+    const crc = crc32(data);
+
+    const local = Buffer.alloc(30);
+    local.writeUInt32LE(0x04034b50, 0);
+    local.writeUInt16LE(20, 4);
+    local.writeUInt32LE(crc, 14);
+    local.writeUInt32LE(data.length, 18);
+    local.writeUInt32LE(data.length, 22);
+    local.writeUInt16LE(name.length, 26);
+
+    const central = Buffer.alloc(46);
+    central.writeUInt32LE(0x02014b50, 0);
+    central.writeUInt16LE(20, 4);
+    central.writeUInt16LE(20, 6);
+    central.writeUInt32LE(crc, 16);
+    central.writeUInt32LE(data.length, 20);
+    central.writeUInt32LE(data.length, 24);
+    central.writeUInt16LE(name.length, 28);
+    central.writeUInt32LE(offset, 42);
+
+    files.push(local, name, data);
+    directory.push(central, name);
+    offset += local.length + name.length + data.length;
+  });
+
+  const directorySize = directory.reduce((size, b) => size + b.length, 0);
+  const end = Buffer.alloc(22);
+  end.writeUInt32LE(0x06054b50, 0);
+  end.writeUInt16LE(recipes.length, 8);
+  end.writeUInt16LE(recipes.length, 10);
+  end.writeUInt32LE(directorySize, 12);
+  end.writeUInt32LE(offset, 16);
+
+  return Buffer.concat([...files, ...directory, end]);
+}